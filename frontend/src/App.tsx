@@ -38,8 +38,11 @@ export default function App() {
     onReload: () => {
       loadModel()
     },
-    onError: (msg) => {
-      setError(msg)
+    onModel: (model) => {
+      setModel(model)
+    },
+    onError: (msg, errors) => {
+      setError(errors.length > 0 ? errors.map((e) => (e.file ? `${e.file}:${e.line}:${e.column}: ${e.message}` : e.message)).join('\n') : msg)
     },
   })
 
@@ -50,7 +53,7 @@ export default function App() {
         <div className="flex-1 relative">
           {loading && <LoadingSpinner message="Loading model..." />}
           {error && (
-            <div className="absolute top-4 left-1/2 -translate-x-1/2 bg-red-900/90 text-red-100 px-4 py-2 rounded-lg z-10 max-w-md">
+            <div className="absolute top-4 left-1/2 -translate-x-1/2 bg-red-900/90 text-red-100 px-4 py-2 rounded-lg z-10 max-w-md whitespace-pre-line">
               {error}
             </div>
           )}
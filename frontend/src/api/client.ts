@@ -15,11 +15,14 @@ async function fetchJson<T>(url: string, options?: RequestInit): Promise<T> {
   const response = await fetch(url, options)
 
   if (!response.ok) {
-    throw new ApiError(
-      `Failed to fetch ${url}`,
-      response.status,
-      response.statusText
-    )
+    // Error responses carry { error, errors? } bodies
+    const body = await response.json().catch(() => null)
+    const details: string[] = body?.errors?.map((e: { message: string }) => e.message) ?? []
+    const message = body?.error
+      ? [body.error, ...details].join('\n')
+      : `Failed to fetch ${url}`
+
+    throw new ApiError(message, response.status, response.statusText)
   }
 
   return response.json()
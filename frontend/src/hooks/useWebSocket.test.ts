@@ -42,13 +42,35 @@ describe('useWebSocket', () => {
     expect(onReload).toHaveBeenCalled()
   })
 
+  it('should handle model message', () => {
+    const onModel = vi.fn()
+    renderHook(() => useWebSocket({ onModel }))
+
+    const model = { persons: [], systems: [{ id: 'shop', name: 'Shop' }] }
+    const messageHandler = messageHandlers.get('message')
+    messageHandler?.({ data: JSON.stringify({ type: 'model', model }) })
+    expect(onModel).toHaveBeenCalledWith(model)
+  })
+
+  it('should pass structured parse errors', () => {
+    const onError = vi.fn()
+    renderHook(() => useWebSocket({ onError }))
+
+    const errors = [{ message: 'model.yaml: invalid YAML' }]
+    const messageHandler = messageHandlers.get('message')
+    messageHandler?.({
+      data: JSON.stringify({ type: 'error', message: 'Parse errors: 1 errors encountered', errors }),
+    })
+    expect(onError).toHaveBeenCalledWith('Parse errors: 1 errors encountered', errors)
+  })
+
   it('should handle error message', () => {
     const onError = vi.fn()
     renderHook(() => useWebSocket({ onError }))
 
     const messageHandler = messageHandlers.get('message')
     messageHandler?.({ data: JSON.stringify({ type: 'error', message: 'Test error' }) })
-    expect(onError).toHaveBeenCalledWith('Test error')
+    expect(onError).toHaveBeenCalledWith('Test error', [])
   })
 
   it('should reconnect on close', () => {
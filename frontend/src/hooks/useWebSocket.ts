@@ -1,13 +1,24 @@
 import { useEffect, useRef } from 'react'
+import type { C4Model } from '../types/c4'
+
+export interface ParseError {
+  message: string
+  file?: string
+  line?: number
+  column?: number
+}
 
 interface WebSocketMessage {
-  type: 'reload' | 'error'
+  type: 'reload' | 'model' | 'error'
   message?: string
+  model?: C4Model
+  errors?: ParseError[]
 }
 
 interface UseWebSocketOptions {
   onReload?: () => void
-  onError?: (error: string) => void
+  onModel?: (model: C4Model) => void
+  onError?: (error: string, errors: ParseError[]) => void
 }
 
 export function useWebSocket(options: UseWebSocketOptions = {}) {
@@ -25,8 +36,10 @@ export function useWebSocket(options: UseWebSocketOptions = {}) {
 
           if (msg.type === 'reload') {
             options.onReload?.()
+          } else if (msg.type === 'model' && msg.model) {
+            options.onModel?.(msg.model)
           } else if (msg.type === 'error') {
-            options.onError?.(msg.message ?? 'Unknown error')
+            options.onError?.(msg.message ?? 'Unknown error', msg.errors ?? [])
           }
         } catch (err) {
           console.error('Failed to parse WebSocket message:', err)
@@ -54,7 +67,7 @@ export function useWebSocket(options: UseWebSocketOptions = {}) {
       }
       wsRef.current?.close()
     }
-  }, [options.onReload, options.onError])
+  }, [options.onReload, options.onModel, options.onError])
 
   return wsRef
 }
@@ -39,6 +39,7 @@ export interface Relationship {
   technology?: string[];
   tags?: string[];
   properties?: Record<string, any>;
+  implied?: boolean;
 }
 
 export interface FlowStep {
@@ -85,6 +86,7 @@ export interface C4Model {
   containers: Container[];
   components: Component[];
   relationships: Relationship[];
+  impliedRelationships?: Relationship[];
   flows: Flow[];
   deployments: Deployment[];
   options: Options;
@@ -0,0 +1,156 @@
+use super::{load_model, CliError, Result};
+use crate::model::{Cycle, ElementType};
+use clap::{Args, Subcommand};
+use std::path::Path;
+
+#[derive(Args, Debug)]
+pub struct AnalyzeArgs {
+    #[command(subcommand)]
+    pub command: AnalyzeCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AnalyzeCommand {
+    /// Report dependency cycles between systems, containers and components
+    Cycles(CyclesArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CyclesArgs {
+    /// Only check one level: system, container, component
+    #[arg(long)]
+    pub level: Option<String>,
+
+    /// Output results as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_analyze(args: AnalyzeArgs, work_dir: &Path, verbose: bool) -> Result<()> {
+    match args.command {
+        AnalyzeCommand::Cycles(cycles) => run_cycles(cycles, work_dir, verbose),
+    }
+}
+
+fn run_cycles(args: CyclesArgs, work_dir: &Path, verbose: bool) -> Result<()> {
+    let levels = match args.level.as_deref() {
+        None => vec![
+            ElementType::System,
+            ElementType::Container,
+            ElementType::Component,
+        ],
+        Some("system") => vec![ElementType::System],
+        Some("container") => vec![ElementType::Container],
+        Some("component") => vec![ElementType::Component],
+        Some(level) => {
+            return Err(CliError::Analyze(format!(
+                "invalid level '{}'. Must be 'system', 'container' or 'component'",
+                level
+            )))
+        }
+    };
+
+    if verbose {
+        println!("Analyzing workspace in {}", work_dir.display());
+    }
+
+    let model = load_model(work_dir, CliError::Analyze)?;
+    let cycles: Vec<Cycle> = levels
+        .into_iter()
+        .flat_map(|level| model.find_cycles(level))
+        .collect();
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&cycles)
+            .map_err(|e| CliError::Analyze(format!("JSON serialization failed: {}", e)))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    if cycles.is_empty() {
+        println!("No dependency cycles found");
+        return Ok(());
+    }
+
+    println!("Found {} dependency cycles", cycles.len());
+    let mut level = None;
+    for cycle in &cycles {
+        if level != Some(cycle.level) {
+            level = Some(cycle.level);
+            println!();
+            println!("{}:", heading(cycle.level));
+        }
+        println!("  {}", cycle);
+    }
+
+    Ok(())
+}
+
+fn heading(level: ElementType) -> &'static str {
+    match level {
+        ElementType::System => "Software systems",
+        ElementType::Container => "Containers",
+        _ => "Components",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            r#"systems:
+  - id: shop
+    name: Shop
+  - id: billing
+    name: Billing
+relationships:
+  - from: shop
+    to: billing
+  - from: billing
+    to: shop
+"#,
+        )
+        .unwrap();
+        dir
+    }
+
+    fn cycles_args(level: Option<&str>, json: bool) -> AnalyzeArgs {
+        AnalyzeArgs {
+            command: AnalyzeCommand::Cycles(CyclesArgs {
+                level: level.map(str::to_string),
+                json,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_analyze_cycles() {
+        let dir = create_workspace();
+        assert!(run_analyze(cycles_args(None, false), dir.path(), false).is_ok());
+        assert!(run_analyze(cycles_args(Some("container"), true), dir.path(), false).is_ok());
+    }
+
+    #[test]
+    fn test_analyze_cycles_invalid_level() {
+        let dir = create_workspace();
+        let err = run_analyze(cycles_args(Some("person"), false), dir.path(), false).unwrap_err();
+        assert!(err.to_string().contains("invalid level 'person'"));
+    }
+
+    #[test]
+    fn test_analyze_cycles_without_workspace() {
+        let dir = TempDir::new().unwrap();
+        assert!(run_analyze(cycles_args(None, false), dir.path(), false).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use super::{CliError, Result};
+use crate::exporter::images::{ImageFormat, ImageOptions};
 use crate::exporter::Exporter;
 use crate::parser::Parser;
 use clap::Args;
@@ -23,9 +24,41 @@ pub struct BuildArgs {
     #[arg(long = "images")]
     pub images: bool,
 
+    /// Export Structurizr DSL (workspace.dsl)
+    #[arg(long = "structurizr")]
+    pub structurizr: bool,
+
+    /// Export C4-PlantUML diagrams (plantuml/*.puml)
+    #[arg(long = "plantuml")]
+    pub plantuml: bool,
+
+    /// Export Mermaid C4 diagrams (mermaid/*.mmd)
+    #[arg(long = "mermaid")]
+    pub mermaid: bool,
+
+    /// Export Graphviz DOT graphs per level (dot/*.dot)
+    #[arg(long = "dot")]
+    pub dot: bool,
+
+    /// Export D2 graphs per level (d2/*.d2)
+    #[arg(long = "d2")]
+    pub d2: bool,
+
+    /// Export a draw.io file with a page per view (workspace.drawio)
+    #[arg(long = "drawio")]
+    pub drawio: bool,
+
     /// Image format: png, svg
     #[arg(long = "format", default_value = "png")]
     pub format: String,
+
+    /// Scale factor for PNG images
+    #[arg(long = "scale", default_value = "1.0")]
+    pub scale: f32,
+
+    /// Resolution for PNG images (overrides --scale, 96 DPI = scale 1.0)
+    #[arg(long = "dpi", conflicts_with = "scale")]
+    pub dpi: Option<u32>,
 }
 
 pub fn run_build(args: BuildArgs, work_dir: &Path, verbose: bool) -> Result<()> {
@@ -43,12 +76,16 @@ pub fn run_build(args: BuildArgs, work_dir: &Path, verbose: bool) -> Result<()>
     }
 
     // Validate image format
-    if args.images && args.format != "png" && args.format != "svg" {
-        return Err(CliError::Build(format!(
-            "invalid image format '{}'. Must be 'png' or 'svg'",
-            args.format
-        )));
-    }
+    let image_options = if args.images {
+        let format: ImageFormat = args.format.parse().map_err(CliError::Build)?;
+        let options = match args.dpi {
+            Some(dpi) => ImageOptions::new(format).with_dpi(dpi),
+            None => ImageOptions::new(format).with_scale(args.scale),
+        };
+        Some(options)
+    } else {
+        None
+    };
 
     // Create output directory
     let abs_output = if args.output.is_absolute() {
@@ -105,12 +142,93 @@ pub fn run_build(args: BuildArgs, work_dir: &Path, verbose: bool) -> Result<()>
         println!("  model.json");
     }
 
+    // Export Structurizr DSL
+    if args.structurizr {
+        println!("Exporting Structurizr DSL...");
+        let name = parser
+            .get_mod_file()
+            .map(|m| m.name.clone())
+            .unwrap_or_default();
+        exporter
+            .export_structurizr(&name)
+            .map_err(|e| CliError::Build(format!("Structurizr export failed: {}", e)))?;
+        println!("  workspace.dsl");
+    }
+
+    // Export PlantUML diagrams
+    if args.plantuml {
+        println!("Exporting PlantUML...");
+        let written = exporter
+            .export_plantuml()
+            .map_err(|e| CliError::Build(format!("PlantUML export failed: {}", e)))?;
+        for path in written {
+            if let Ok(rel) = path.strip_prefix(&abs_output) {
+                println!("  {}", rel.display());
+            }
+        }
+    }
+
+    // Export Mermaid diagrams
+    if args.mermaid {
+        println!("Exporting Mermaid...");
+        let written = exporter
+            .export_mermaid()
+            .map_err(|e| CliError::Build(format!("Mermaid export failed: {}", e)))?;
+        for path in written {
+            if let Ok(rel) = path.strip_prefix(&abs_output) {
+                println!("  {}", rel.display());
+            }
+        }
+    }
+
+    // Export DOT graphs
+    if args.dot {
+        println!("Exporting DOT...");
+        let written = exporter
+            .export_dot()
+            .map_err(|e| CliError::Build(format!("DOT export failed: {}", e)))?;
+        for path in written {
+            if let Ok(rel) = path.strip_prefix(&abs_output) {
+                println!("  {}", rel.display());
+            }
+        }
+    }
+
+    // Export D2 graphs
+    if args.d2 {
+        println!("Exporting D2...");
+        let written = exporter
+            .export_d2()
+            .map_err(|e| CliError::Build(format!("D2 export failed: {}", e)))?;
+        for path in written {
+            if let Ok(rel) = path.strip_prefix(&abs_output) {
+                println!("  {}", rel.display());
+            }
+        }
+    }
+
+    // Export draw.io file
+    if args.drawio {
+        println!("Exporting draw.io...");
+        let path = exporter
+            .export_drawio()
+            .map_err(|e| CliError::Build(format!("draw.io export failed: {}", e)))?;
+        if let Ok(rel) = path.strip_prefix(&abs_output) {
+            println!("  {}", rel.display());
+        }
+    }
+
     // Export images
-    if args.images {
+    if let Some(options) = image_options {
         println!("Exporting images ({})...", args.format);
-        exporter
-            .export_images()
+        let written = exporter
+            .export_images(&options)
             .map_err(|e| CliError::Build(format!("image export failed: {}", e)))?;
+        for path in written {
+            if let Ok(rel) = path.strip_prefix(&abs_output) {
+                println!("  {}", rel.display());
+            }
+        }
     }
 
     println!();
@@ -132,7 +250,15 @@ mod tests {
             html: true,
             json: false,
             images: false,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
         assert_eq!(args.output, PathBuf::from("./dist"));
@@ -149,7 +275,15 @@ mod tests {
             html: false,
             json: true,
             images: true,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "svg".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
         assert_eq!(args.output, PathBuf::from("./output"));
@@ -167,7 +301,15 @@ mod tests {
             html: true,
             json: false,
             images: false,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
         let result = run_build(args, dir.path(), false);
@@ -193,7 +335,15 @@ mod tests {
             html: true,
             json: false,
             images: false,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
         run_build(args, dir.path(), false).unwrap();
@@ -215,7 +365,15 @@ mod tests {
             html: true,
             json: false,
             images: false,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
         run_build(args, dir.path(), false).unwrap();
@@ -239,7 +397,15 @@ mod tests {
             html: false,
             json: true,
             images: false,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
         run_build(args, dir.path(), false).unwrap();
@@ -247,6 +413,193 @@ mod tests {
         assert!(output_dir.join("model.json").exists());
     }
 
+    #[test]
+    fn test_build_structurizr_output() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: shop\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "systems:\n  - id: app\n    name: App\n",
+        )
+        .unwrap();
+
+        let output_dir = dir.path().join("dist");
+        let args = BuildArgs {
+            output: output_dir.clone(),
+            html: false,
+            json: false,
+            images: false,
+            structurizr: true,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
+            format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
+        };
+
+        run_build(args, dir.path(), false).unwrap();
+
+        let dsl = fs::read_to_string(output_dir.join("workspace.dsl")).unwrap();
+        assert!(dsl.starts_with("workspace \"shop\" {"));
+        assert!(dsl.contains("app = softwareSystem \"App\""));
+    }
+
+    #[test]
+    fn test_build_plantuml_output() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: shop\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "systems:\n  - id: app\n    name: App\n",
+        )
+        .unwrap();
+
+        let output_dir = dir.path().join("dist");
+        let args = BuildArgs {
+            output: output_dir.clone(),
+            html: false,
+            json: false,
+            images: false,
+            structurizr: false,
+            plantuml: true,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
+            format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
+        };
+
+        run_build(args, dir.path(), false).unwrap();
+
+        let puml = fs::read_to_string(output_dir.join("plantuml/context-app.puml")).unwrap();
+        assert!(puml.contains("System(app, \"App\")"));
+    }
+
+    #[test]
+    fn test_build_mermaid_output() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: shop\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "systems:\n  - id: app\n    name: App\n",
+        )
+        .unwrap();
+
+        let output_dir = dir.path().join("dist");
+        let args = BuildArgs {
+            output: output_dir.clone(),
+            html: false,
+            json: false,
+            images: false,
+            structurizr: false,
+            plantuml: false,
+            mermaid: true,
+            dot: false,
+            d2: false,
+            drawio: false,
+            format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
+        };
+
+        run_build(args, dir.path(), false).unwrap();
+
+        let diagram = fs::read_to_string(output_dir.join("mermaid/context-app.mmd")).unwrap();
+        assert!(diagram.starts_with("C4Context\n"));
+    }
+
+    #[test]
+    fn test_build_dot_and_d2_output() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: shop\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "systems:\n  - id: app\n    name: App\n",
+        )
+        .unwrap();
+
+        let output_dir = dir.path().join("dist");
+        let args = BuildArgs {
+            output: output_dir.clone(),
+            html: false,
+            json: false,
+            images: false,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: true,
+            d2: true,
+            drawio: false,
+            format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
+        };
+
+        run_build(args, dir.path(), false).unwrap();
+
+        let dot = fs::read_to_string(output_dir.join("dot/system.dot")).unwrap();
+        assert!(dot.contains("\"app\" [label=\"App\\n[Software System]\""));
+        assert!(output_dir.join("d2/component.d2").exists());
+    }
+
+    #[test]
+    fn test_build_drawio_output() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: shop\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "systems:\n  - id: app\n    name: App\n",
+        )
+        .unwrap();
+
+        let output_dir = dir.path().join("dist");
+        let args = BuildArgs {
+            output: output_dir.clone(),
+            html: false,
+            json: false,
+            images: false,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: true,
+            format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
+        };
+
+        run_build(args, dir.path(), false).unwrap();
+
+        let drawio = fs::read_to_string(output_dir.join("workspace.drawio")).unwrap();
+        assert!(drawio.contains("<diagram id=\"context-app\""));
+    }
+
     #[test]
     fn test_build_images_output() {
         let dir = TempDir::new().unwrap();
@@ -262,14 +615,91 @@ mod tests {
             html: false,
             json: false,
             images: true,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
-        // Image export is not yet implemented but should not error
         let result = run_build(args, dir.path(), false);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_build_svg_images() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "systems:\n  - id: app\n    name: App\n",
+        )
+        .unwrap();
+
+        let output_dir = dir.path().join("dist");
+        let args = BuildArgs {
+            output: output_dir.clone(),
+            html: false,
+            json: false,
+            images: true,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
+            format: "svg".to_string(),
+            scale: 1.0,
+            dpi: None,
+        };
+
+        run_build(args, dir.path(), false).unwrap();
+        assert!(output_dir.join("images/context-app.svg").exists());
+    }
+
+    #[test]
+    fn test_build_png_images_with_dpi() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "systems:\n  - id: app\n    name: App\n",
+        )
+        .unwrap();
+
+        let output_dir = dir.path().join("dist");
+        let args = BuildArgs {
+            output: output_dir.clone(),
+            html: false,
+            json: false,
+            images: true,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
+            format: "png".to_string(),
+            scale: 1.0,
+            dpi: Some(192),
+        };
+
+        run_build(args, dir.path(), false).unwrap();
+        assert!(output_dir.join("images/context-app.png").exists());
+    }
+
     #[test]
     fn test_build_invalid_image_format() {
         let dir = TempDir::new().unwrap();
@@ -284,7 +714,15 @@ mod tests {
             html: false,
             json: false,
             images: true,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "invalid".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
         let result = run_build(args, dir.path(), false);
@@ -310,7 +748,15 @@ mod tests {
             html: true,
             json: false,
             images: false,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
         run_build(args, dir.path(), false).unwrap();
@@ -331,7 +777,15 @@ mod tests {
             html: true,
             json: false,
             images: false,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
         run_build(args, dir.path(), false).unwrap();
@@ -355,14 +809,22 @@ mod tests {
             html: true,
             json: true,
             images: true,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "svg".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
         run_build(args, dir.path(), false).unwrap();
 
         assert!(output_dir.join("index.html").exists());
         assert!(output_dir.join("model.json").exists());
-        // Note: images/ directory is not created since image export is not yet implemented
+        assert!(output_dir.join("images").exists());
     }
 
     #[test]
@@ -380,7 +842,15 @@ mod tests {
             html: true,
             json: false,
             images: false,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
         // Run with verbose=true to cover verbose output lines
@@ -403,13 +873,18 @@ mod tests {
             html: false,
             json: false,
             images: true,
+            structurizr: false,
+            plantuml: false,
+            mermaid: false,
+            dot: false,
+            d2: false,
+            drawio: false,
             format: "png".to_string(),
+            scale: 1.0,
+            dpi: None,
         };
 
-        // Image export is currently unimplemented (prints message and returns Ok)
-        // Just verify the function runs without error in verbose mode
         run_build(args, dir.path(), true).unwrap();
-        // Note: images directory is not created since export_images is a stub
-        assert!(output_dir.exists());
+        assert!(output_dir.join("images").exists());
     }
 }
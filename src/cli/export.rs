@@ -0,0 +1,172 @@
+use super::{load_model, CliError, Result};
+use crate::exporter::graph::{level_name, Graph, LEVELS};
+use crate::exporter::{d2, dot, mermaid};
+use crate::model::Model;
+use clap::Args;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Diagram format: mermaid, dot, d2
+    pub format: String,
+
+    /// View to print, such as containers-shop for mermaid or container for
+    /// dot and d2; lists the views when omitted
+    pub view: Option<String>,
+}
+
+/// Diagram source formats that can be printed one view at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramFormat {
+    Mermaid,
+    Dot,
+    D2,
+}
+
+impl FromStr for DiagramFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "mermaid" => Ok(DiagramFormat::Mermaid),
+            "dot" => Ok(DiagramFormat::Dot),
+            "d2" => Ok(DiagramFormat::D2),
+            _ => Err(format!(
+                "invalid diagram format '{}'. Must be 'mermaid', 'dot' or 'd2'",
+                s
+            )),
+        }
+    }
+}
+
+pub fn run_export(args: ExportArgs, work_dir: &Path, verbose: bool) -> Result<()> {
+    let format: DiagramFormat = args.format.parse().map_err(CliError::Export)?;
+
+    // Progress goes to stderr so the diagram can be piped
+    if verbose {
+        eprintln!("Exporting from {}", work_dir.display());
+    }
+
+    let model = load_model(work_dir, CliError::Export)?;
+    let diagrams = match format {
+        DiagramFormat::Mermaid => mermaid::render_diagrams(&model),
+        DiagramFormat::Dot => render_levels(&model, dot::render_dot),
+        DiagramFormat::D2 => render_levels(&model, d2::render_d2),
+    };
+
+    let Some(view) = args.view else {
+        let keys: String = diagrams
+            .iter()
+            .map(|(key, _)| format!("{}\n", key))
+            .collect();
+        return write_stdout(&keys);
+    };
+
+    match diagrams.into_iter().find(|(key, _)| *key == view) {
+        Some((_, diagram)) => write_stdout(&diagram),
+        None => Err(CliError::Export(format!(
+            "unknown view '{}'. Run 'c4 export {}' to list the views",
+            view, args.format
+        ))),
+    }
+}
+
+/// Renders the graph at every level, keyed by level name
+fn render_levels(model: &Model, render: fn(&Graph) -> String) -> Vec<(String, String)> {
+    LEVELS
+        .into_iter()
+        .map(|level| {
+            (
+                level_name(level).to_string(),
+                render(&Graph::build(model, level)),
+            )
+        })
+        .collect()
+}
+
+/// Writes to stdout, treating a closed pipe (such as `| head`) as success
+fn write_stdout(text: &str) -> Result<()> {
+    match std::io::stdout().lock().write_all(text.as_bytes()) {
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => Ok(()),
+        result => result.map_err(CliError::Io),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "systems:\n  - id: shop\n    name: Shop\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    fn export_args(format: &str, view: Option<&str>) -> ExportArgs {
+        ExportArgs {
+            format: format.to_string(),
+            view: view.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_parse_diagram_format() {
+        assert_eq!("mermaid".parse(), Ok(DiagramFormat::Mermaid));
+        assert_eq!("dot".parse(), Ok(DiagramFormat::Dot));
+        assert_eq!("d2".parse(), Ok(DiagramFormat::D2));
+        assert!("visio".parse::<DiagramFormat>().is_err());
+    }
+
+    #[test]
+    fn test_export_view() {
+        let dir = create_workspace();
+        assert!(run_export(export_args("mermaid", None), dir.path(), false).is_ok());
+        assert!(run_export(
+            export_args("mermaid", Some("context-shop")),
+            dir.path(),
+            false
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_export_level() {
+        let dir = create_workspace();
+        assert!(run_export(export_args("dot", Some("container")), dir.path(), false).is_ok());
+        let err =
+            run_export(export_args("d2", Some("context-shop")), dir.path(), false).unwrap_err();
+        assert!(err.to_string().contains("Run 'c4 export d2'"));
+    }
+
+    #[test]
+    fn test_export_unknown_view() {
+        let dir = create_workspace();
+        let err = run_export(
+            export_args("mermaid", Some("context-crm")),
+            dir.path(),
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("unknown view 'context-crm'"));
+    }
+
+    #[test]
+    fn test_export_invalid_format() {
+        let dir = create_workspace();
+        let err = run_export(export_args("visio", None), dir.path(), false).unwrap_err();
+        assert!(err.to_string().contains("invalid diagram format 'visio'"));
+    }
+}
@@ -0,0 +1,208 @@
+use super::init::mod_file_content;
+use super::{CliError, Result};
+use crate::importer::structurizr::import_structurizr;
+use clap::Args;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// Source format: structurizr
+    pub format: String,
+
+    /// File to import, such as workspace.dsl or workspace.json
+    pub file: PathBuf,
+}
+
+/// Tools whose workspaces can be imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Structurizr,
+}
+
+impl FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "structurizr" => Ok(ImportFormat::Structurizr),
+            _ => Err(format!(
+                "invalid import format '{}'. Must be 'structurizr'",
+                s
+            )),
+        }
+    }
+}
+
+pub fn run_import(args: ImportArgs, work_dir: &Path, verbose: bool) -> Result<()> {
+    let format: ImportFormat = args.format.parse().map_err(CliError::Import)?;
+
+    if work_dir.join("c4.mod.yaml").exists() {
+        return Err(CliError::Import(
+            "workspace already initialized (c4.mod.yaml exists)".to_string(),
+        ));
+    }
+
+    if verbose {
+        println!("Importing {}", args.file.display());
+    }
+
+    let workspace = match format {
+        ImportFormat::Structurizr => {
+            import_structurizr(&args.file).map_err(|e| CliError::Import(e.to_string()))?
+        }
+    };
+
+    let mut files = vec![("c4.mod.yaml".to_string(), mod_file_content(&workspace.name))];
+    files.extend(workspace.files());
+
+    for (path, content) in &files {
+        let path = work_dir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                CliError::Import(format!("failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+        fs::write(&path, content)
+            .map_err(|e| CliError::Import(format!("failed to write {}: {}", path.display(), e)))?;
+        if verbose {
+            println!("  Wrote {}", path.display());
+        }
+    }
+
+    println!(
+        "Imported workspace \"{}\" into {} ({} files)",
+        workspace.name,
+        work_dir.display(),
+        files.len()
+    );
+    if !workspace.warnings.is_empty() {
+        println!("\nNot imported:");
+        for warning in &workspace.warnings {
+            println!("  - {}", warning);
+        }
+    }
+    println!("\nRun 'c4 validate' to check the result.");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use tempfile::TempDir;
+
+    const WORKSPACE_DSL: &str = r#"
+workspace "Online Shop" {
+    model {
+        customer = person "Customer"
+        shop = softwareSystem "Shop" {
+            api = container "API" "" "Rust" {
+                orders = component "Orders"
+            }
+            db = container "Database" "" "PostgreSQL" "Database"
+        }
+        payments = softwareSystem "Payments" "" "External"
+
+        customer -> api "Places orders" "HTTPS"
+        orders -> db "Stores orders"
+        api -> payments "Charges cards"
+
+        production = deploymentEnvironment "Production" {
+            deploymentNode "Cloud" {
+                containerInstance api
+            }
+        }
+    }
+    views {
+        dynamic shop "checkout" "Checkout" {
+            customer -> api "Submits order"
+            api -> payments "Charges card"
+        }
+    }
+}
+"#;
+
+    fn import_args(format: &str, file: &Path) -> ImportArgs {
+        ImportArgs {
+            format: format.to_string(),
+            file: file.to_path_buf(),
+        }
+    }
+
+    #[test]
+    fn test_parse_import_format() {
+        assert_eq!(
+            "structurizr".parse::<ImportFormat>().unwrap(),
+            ImportFormat::Structurizr
+        );
+        assert!("archi".parse::<ImportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_import_structurizr_writes_valid_workspace() {
+        let source = TempDir::new().unwrap();
+        let file = source.path().join("workspace.dsl");
+        fs::write(&file, WORKSPACE_DSL).unwrap();
+        let dir = TempDir::new().unwrap();
+
+        run_import(import_args("structurizr", &file), dir.path(), false).unwrap();
+
+        let mod_file = fs::read_to_string(dir.path().join("c4.mod.yaml")).unwrap();
+        assert!(mod_file.contains("name: online-shop\n"));
+        for path in [
+            "shared/personas.yaml",
+            "shared/external-systems.yaml",
+            "systems/shop/system.yaml",
+            "systems/shop/containers.yaml",
+            "systems/shop/containers/api/components.yaml",
+            "systems/shop/relationships.yaml",
+            "systems/shop/flows/checkout.yaml",
+            "deployments/production.yaml",
+        ] {
+            assert!(dir.path().join(path).exists(), "{} missing", path);
+        }
+
+        let mut parser = Parser::new(dir.path());
+        let model = parser.parse().unwrap();
+        assert!(parser.errors().is_empty(), "{:?}", parser.errors());
+        assert_eq!(model.systems.len(), 2);
+        assert_eq!(model.containers.len(), 2);
+        assert_eq!(model.components.len(), 1);
+        assert_eq!(model.flows.len(), 1);
+        assert_eq!(model.deployments.len(), 1);
+        assert!(model
+            .relationships
+            .iter()
+            .any(|r| r.from == "shop.api.orders" && r.to == "shop.db"));
+    }
+
+    #[test]
+    fn test_import_into_existing_workspace() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("c4.mod.yaml"), "version: \"1.0\"\n").unwrap();
+
+        let result = run_import(
+            import_args("structurizr", Path::new("workspace.dsl")),
+            dir.path(),
+            false,
+        );
+
+        assert!(
+            matches!(result, Err(CliError::Import(msg)) if msg.contains("already initialized"))
+        );
+    }
+
+    #[test]
+    fn test_import_invalid_format() {
+        let dir = TempDir::new().unwrap();
+        let result = run_import(import_args("archi", Path::new("x")), dir.path(), false);
+
+        assert!(
+            matches!(result, Err(CliError::Import(msg)) if msg.contains("invalid import format"))
+        );
+        assert!(!dir.path().join("c4.mod.yaml").exists());
+    }
+}
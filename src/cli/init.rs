@@ -1,4 +1,5 @@
 use super::{CliError, Result};
+use crate::parser::layout::DEFAULT_LAYOUT;
 use clap::Args;
 use std::fs;
 use std::path::Path;
@@ -62,7 +63,21 @@ fn create_directories(work_dir: &Path, minimal: bool) -> Result<()> {
 }
 
 fn create_mod_file(work_dir: &Path, name: &str) -> Result<()> {
-    let content = format!(
+    let mod_path = work_dir.join("c4.mod.yaml");
+    fs::write(mod_path, mod_file_content(name))
+        .map_err(|e| CliError::Init(format!("failed to create c4.mod.yaml: {}", e)))?;
+
+    Ok(())
+}
+
+/// The `c4.mod.yaml` of a new workspace, including every file in the
+/// standard layout
+pub fn mod_file_content(name: &str) -> String {
+    let layout: String = DEFAULT_LAYOUT
+        .iter()
+        .map(|pattern| format!("  - \"{}\"\n", pattern))
+        .collect();
+    format!(
         r#"version: "1.0"
 name: {}
 
@@ -70,21 +85,35 @@ include:
   - shared/*.yaml
   - systems/*/system.yaml
   - systems/*/containers.yaml
+  - systems/*/containers/*/*.yaml
   - systems/*/relationships.yaml
   - systems/*/flows/*.yaml
   - deployments/*.yaml
-"#,
-        name
-    );
-
-    let mod_path = work_dir.join("c4.mod.yaml");
-    fs::write(mod_path, content)
-        .map_err(|e| CliError::Init(format!("failed to create c4.mod.yaml: {}", e)))?;
 
-    Ok(())
+# Files matching these patterns belong to the captured system and container,
+# so their elements can leave out systemId and containerId
+layout:
+{}"#,
+        name, layout
+    )
 }
 
+/// Lint levels for the example, which leaves the email service unused and a
+/// relationship's technology for the user to fill in
+const EXAMPLE_LINT: &str = r#"
+# The example is incomplete on purpose; remove these once it is filled in
+lint:
+  orphan-element: off
+  missing-technology: off
+"#;
+
 fn create_example_files(work_dir: &Path) -> Result<()> {
+    let mod_path = work_dir.join("c4.mod.yaml");
+    let mut mod_file = fs::read_to_string(&mod_path)?;
+    mod_file.push_str(EXAMPLE_LINT);
+    fs::write(&mod_path, mod_file)
+        .map_err(|e| CliError::Init(format!("failed to update c4.mod.yaml: {}", e)))?;
+
     let files = [
         (
             "shared/personas.yaml",
@@ -239,6 +268,10 @@ mod tests {
         let content = fs::read_to_string(mod_path).unwrap();
         assert!(content.contains("name: test-project"));
         assert!(content.contains("version: \"1.0\""));
+        assert!(content.contains("  - \"**/systems/{system}/containers/{container}/**/*\"\n"));
+
+        let mod_file: crate::parser::ModFile = serde_yaml::from_str(&content).unwrap();
+        assert_eq!(mod_file.layout, crate::parser::layout::default_layout());
     }
 
     #[test]
@@ -297,6 +330,28 @@ mod tests {
         assert!(dir.path().join("deployments/production.yaml").exists());
     }
 
+    #[test]
+    fn test_init_example_passes_strict_validation() {
+        let dir = TempDir::new().unwrap();
+        let args = InitArgs {
+            name: None,
+            minimal: false,
+            example: true,
+        };
+        run_init(args, dir.path()).unwrap();
+
+        let args = crate::cli::validate::ValidateArgs {
+            files: vec![],
+            strict: true,
+            json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        assert!(crate::cli::validate::run_validate(args, &dir.path().to_path_buf(), false).is_ok());
+    }
+
     #[test]
     fn test_init_already_initialized() {
         let dir = TempDir::new().unwrap();
@@ -1,10 +1,17 @@
+pub mod analyze;
 pub mod build;
+pub mod export;
+pub mod import;
 pub mod init;
+pub mod module;
+pub mod report;
 pub mod serve;
 pub mod validate;
 
+use crate::model::Model;
+use crate::parser::Parser as ModelParser;
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -23,6 +30,18 @@ pub enum CliError {
 
     #[error("Build error: {0}")]
     Build(String),
+
+    #[error("Module error: {0}")]
+    Module(String),
+
+    #[error("Analysis error: {0}")]
+    Analyze(String),
+
+    #[error("Export error: {0}")]
+    Export(String),
+
+    #[error("Import error: {0}")]
+    Import(String),
 }
 
 pub type Result<T> = std::result::Result<T, CliError>;
@@ -63,6 +82,18 @@ pub enum Commands {
     /// Export C4 model to static artifacts
     Build(build::BuildArgs),
 
+    /// Print a single view as diagram source
+    Export(export::ExportArgs),
+
+    /// Import a workspace from another tool
+    Import(import::ImportArgs),
+
+    /// Manage imports and the c4.lock.yaml lockfile
+    Mod(module::ModArgs),
+
+    /// Analyze the model's structure
+    Analyze(analyze::AnalyzeArgs),
+
     /// Print version information
     Version,
 }
@@ -77,6 +108,16 @@ pub fn run() -> Result<()> {
         }
         Commands::Serve(args) => serve::run_serve(args, &get_work_dir(&cli.work_dir)?, cli.verbose),
         Commands::Build(args) => build::run_build(args, &get_work_dir(&cli.work_dir)?, cli.verbose),
+        Commands::Export(args) => {
+            export::run_export(args, &get_work_dir(&cli.work_dir)?, cli.verbose)
+        }
+        Commands::Import(args) => {
+            import::run_import(args, &get_work_dir(&cli.work_dir)?, cli.verbose)
+        }
+        Commands::Mod(args) => module::run_mod(args, &get_work_dir(&cli.work_dir)?, cli.verbose),
+        Commands::Analyze(args) => {
+            analyze::run_analyze(args, &get_work_dir(&cli.work_dir)?, cli.verbose)
+        }
         Commands::Version => {
             println!(
                 "c4 version {} ({})",
@@ -99,6 +140,18 @@ pub fn get_work_dir(path: &PathBuf) -> Result<PathBuf> {
     }
 }
 
+/// Parses the model in `work_dir`, reporting parse errors through `error`
+pub fn load_model(work_dir: &Path, error: fn(String) -> CliError) -> Result<Model> {
+    let mut parser = ModelParser::new(work_dir);
+    parser.parse().map_err(|e| {
+        let details: Vec<String> = parser.errors().iter().map(|e| format!("{:#}", e)).collect();
+        error(match details.is_empty() {
+            true => format!("failed to parse model: {:#}", e),
+            false => format!("failed to parse model: {}", details.join("; ")),
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
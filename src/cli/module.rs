@@ -0,0 +1,218 @@
+use super::{CliError, Result};
+use crate::parser::lock::{lock_import, verify_import, LockFile, LOCK_FILE};
+use crate::parser::{Importer, Parser};
+use clap::{Args, Subcommand};
+use std::path::Path;
+
+#[derive(Args, Debug)]
+pub struct ModArgs {
+    #[command(subcommand)]
+    pub command: ModCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ModCommand {
+    /// Fetch imports and record their resolved versions in c4.lock.yaml
+    Update(UpdateArgs),
+
+    /// Check that every import still matches c4.lock.yaml
+    Verify,
+}
+
+#[derive(Args, Debug)]
+pub struct UpdateArgs {
+    /// Only update these imports (default: all)
+    pub aliases: Vec<String>,
+}
+
+pub fn run_mod(args: ModArgs, work_dir: &Path, verbose: bool) -> Result<()> {
+    match args.command {
+        ModCommand::Update(update) => run_update(update, work_dir, verbose),
+        ModCommand::Verify => run_verify(work_dir, verbose),
+    }
+}
+
+fn run_update(args: UpdateArgs, work_dir: &Path, verbose: bool) -> Result<()> {
+    let mut parser = Parser::new(work_dir);
+    parser
+        .load_mod_file(&work_dir.join("c4.mod.yaml"))
+        .map_err(|e| CliError::Module(format!("{:#}", e)))?;
+    let imports = parser.get_mod_file().unwrap().imports.clone();
+
+    for alias in &args.aliases {
+        if !imports.contains_key(alias) {
+            return Err(CliError::Module(format!("unknown import {:?}", alias)));
+        }
+    }
+
+    let mut lock = LockFile::load(work_dir)
+        .map_err(|e| CliError::Module(format!("{:#}", e)))?
+        .unwrap_or_default();
+    lock.imports.retain(|alias, _| imports.contains_key(alias));
+
+    let mut aliases: Vec<&String> = imports.keys().collect();
+    aliases.sort();
+
+    let importer = Importer::new(work_dir);
+    for alias in aliases {
+        let selected = args.aliases.is_empty() || args.aliases.contains(alias);
+        if !selected && lock.imports.contains_key(alias) {
+            continue;
+        }
+
+        let entry = lock_import(&importer, alias, &imports[alias], true)
+            .map_err(|e| CliError::Module(format!("{:#}", e)))?;
+
+        let changed = lock.imports.get(alias) != Some(&entry);
+        if changed || verbose {
+            println!("  {} {}", alias, describe(&entry));
+        }
+        lock.imports.insert(alias.clone(), entry);
+    }
+
+    lock.save(work_dir)
+        .map_err(|e| CliError::Module(format!("{:#}", e)))?;
+    println!("✓ Wrote {} ({} imports)", LOCK_FILE, lock.imports.len());
+
+    Ok(())
+}
+
+fn run_verify(work_dir: &Path, verbose: bool) -> Result<()> {
+    let mut parser = Parser::new(work_dir);
+    parser
+        .load_mod_file(&work_dir.join("c4.mod.yaml"))
+        .map_err(|e| CliError::Module(format!("{:#}", e)))?;
+    let imports = parser.get_mod_file().unwrap().imports.clone();
+
+    let lock = LockFile::load(work_dir)
+        .map_err(|e| CliError::Module(format!("{:#}", e)))?
+        .ok_or_else(|| CliError::Module(format!("{} not found; run `c4 mod update`", LOCK_FILE)))?;
+
+    let mut aliases: Vec<&String> = imports.keys().collect();
+    aliases.sort();
+
+    let importer = Importer::new(work_dir);
+    let mut failures = Vec::new();
+    for alias in aliases {
+        match verify_import(&importer, alias, &imports[alias], lock.imports.get(alias)) {
+            Ok(_) => {
+                if verbose {
+                    println!("  ✓ {}", alias);
+                }
+            }
+            Err(e) => {
+                eprintln!("  ✗ {}", e);
+                failures.push(alias.clone());
+            }
+        }
+    }
+
+    for alias in lock.imports.keys() {
+        if !imports.contains_key(alias) {
+            eprintln!(
+                "  ✗ {:?} is locked but no longer imported; run `c4 mod update`",
+                alias
+            );
+            failures.push(alias.clone());
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(CliError::Module(format!(
+            "{} import(s) do not match {}",
+            failures.len(),
+            LOCK_FILE
+        )));
+    }
+
+    println!("✓ All imports match {}", LOCK_FILE);
+    Ok(())
+}
+
+fn describe(entry: &crate::parser::lock::LockEntry) -> String {
+    match &entry.commit {
+        Some(commit) => format!("{} @ {}", entry.source, &commit[..commit.len().min(12)]),
+        None => format!("{} ({})", entry.source, entry.hash),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_workspace() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let shared = dir.path().join("shared");
+        fs::create_dir_all(&shared).unwrap();
+        fs::write(
+            shared.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: shared\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            shared.join("systems.yaml"),
+            "systems:\n  - id: auth\n    name: Auth\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\nimports:\n  platform:\n    source: ./shared\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    fn update() -> ModArgs {
+        ModArgs {
+            command: ModCommand::Update(UpdateArgs { aliases: vec![] }),
+        }
+    }
+
+    fn verify() -> ModArgs {
+        ModArgs {
+            command: ModCommand::Verify,
+        }
+    }
+
+    #[test]
+    fn test_update_writes_lock_file() {
+        let dir = create_workspace();
+        run_mod(update(), dir.path(), false).unwrap();
+
+        let lock = LockFile::load(dir.path()).unwrap().unwrap();
+        assert!(lock.imports.contains_key("platform"));
+        assert!(run_mod(verify(), dir.path(), false).is_ok());
+    }
+
+    #[test]
+    fn test_verify_without_lock_file() {
+        let dir = create_workspace();
+        let result = run_mod(verify(), dir.path(), false);
+        assert!(matches!(result, Err(CliError::Module(_))));
+    }
+
+    #[test]
+    fn test_verify_detects_changed_import() {
+        let dir = create_workspace();
+        run_mod(update(), dir.path(), false).unwrap();
+
+        fs::write(dir.path().join("shared/systems.yaml"), "systems: []\n").unwrap();
+        assert!(run_mod(verify(), dir.path(), false).is_err());
+
+        run_mod(update(), dir.path(), false).unwrap();
+        assert!(run_mod(verify(), dir.path(), false).is_ok());
+    }
+
+    #[test]
+    fn test_update_unknown_alias() {
+        let dir = create_workspace();
+        let args = ModArgs {
+            command: ModCommand::Update(UpdateArgs {
+                aliases: vec!["nope".to_string()],
+            }),
+        };
+        assert!(run_mod(args, dir.path(), false).is_err());
+    }
+}
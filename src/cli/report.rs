@@ -0,0 +1,342 @@
+use super::validate::{ValidationError, ValidationResult};
+use crate::parser::lint::RULES;
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+/// Rule ID reported for parse, schema and reference errors, which do not
+/// come from a lint or architecture rule
+pub const DEFAULT_RULE: &str = "invalid-model";
+
+const TOOL_NAME: &str = "c4";
+const TOOL_URI: &str = "https://github.com/panbanda/c4";
+
+/// How `c4 validate` prints its results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Human,
+    Json,
+    Sarif,
+    Junit,
+    GithubAnnotations,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(OutputFormat::Human),
+            "json" => Ok(OutputFormat::Json),
+            "sarif" => Ok(OutputFormat::Sarif),
+            "junit" => Ok(OutputFormat::Junit),
+            "github-annotations" => Ok(OutputFormat::GithubAnnotations),
+            _ => Err(format!(
+                "invalid output format '{}'. Must be one of: human, json, sarif, junit, github-annotations",
+                s
+            )),
+        }
+    }
+}
+
+/// Problems paired with the level they are reported at
+fn problems(result: &ValidationResult) -> impl Iterator<Item = (&ValidationError, bool)> {
+    let errors = result.errors.iter().map(|e| (e, true));
+    let warnings = result.warnings.iter().map(|w| (w, false));
+    errors.chain(warnings)
+}
+
+fn rule_id(error: &ValidationError) -> &str {
+    error.rule.as_deref().unwrap_or(DEFAULT_RULE)
+}
+
+/// Renders the result as a SARIF 2.1.0 log for code scanning tools
+pub fn render_sarif(result: &ValidationResult) -> String {
+    let mut rule_ids: Vec<&str> = Vec::new();
+    for (problem, _) in problems(result) {
+        if !rule_ids.contains(&rule_id(problem)) {
+            rule_ids.push(rule_id(problem));
+        }
+    }
+
+    let rules: Vec<Value> = rule_ids
+        .iter()
+        .map(|id| {
+            let description = match RULES.iter().find(|r| r.id == *id) {
+                Some(rule) => rule.description,
+                None if *id == DEFAULT_RULE => "model cannot be parsed or resolved",
+                None => id,
+            };
+            json!({ "id": id, "shortDescription": { "text": description } })
+        })
+        .collect();
+
+    let results: Vec<Value> = problems(result)
+        .map(|(problem, is_error)| {
+            let mut sarif = json!({
+                "ruleId": rule_id(problem),
+                "ruleIndex": rule_ids.iter().position(|id| *id == rule_id(problem)),
+                "level": if is_error { "error" } else { "warning" },
+                "message": { "text": problem.message },
+            });
+            if let Some(file) = &problem.file {
+                let mut location = json!({ "artifactLocation": { "uri": file } });
+                if let Some(line) = problem.line {
+                    location["region"] = json!({ "startLine": line });
+                    if let Some(column) = problem.column {
+                        location["region"]["startColumn"] = json!(column);
+                    }
+                }
+                sarif["locations"] = json!([{ "physicalLocation": location }]);
+            }
+            sarif
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": TOOL_URI,
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    });
+    serde_json::to_string_pretty(&log).unwrap_or_default()
+}
+
+/// Renders the result as a JUnit XML report with one test case per problem.
+/// Warnings are failures in strict mode and skipped test cases otherwise; a
+/// clean workspace is a single passing test case.
+pub fn render_junit(result: &ValidationResult, strict: bool) -> String {
+    let mut cases = Vec::new();
+    let mut failures = 0;
+    let mut skipped = 0;
+
+    for (problem, is_error) in problems(result) {
+        let classname = problem.file.as_deref().unwrap_or("workspace");
+        let mut case = format!(
+            "    <testcase classname=\"{}\" name=\"{}\">\n",
+            xml_escape(classname),
+            xml_escape(&problem.to_string())
+        );
+        if is_error || strict {
+            failures += 1;
+            case.push_str(&format!(
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+                xml_escape(&problem.message),
+                xml_escape(rule_id(problem)),
+                xml_escape(&problem.to_string())
+            ));
+        } else {
+            skipped += 1;
+            case.push_str(&format!(
+                "      <skipped message=\"{}\"/>\n",
+                xml_escape(&format!("warning: {}", problem.message))
+            ));
+        }
+        case.push_str("    </testcase>\n");
+        cases.push(case);
+    }
+
+    if cases.is_empty() {
+        cases.push("    <testcase classname=\"workspace\" name=\"validate\"/>\n".to_string());
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"c4 validate\" tests=\"{0}\" failures=\"{1}\" skipped=\"{2}\">\n",
+        cases.len(),
+        failures,
+        skipped
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"c4 validate\" tests=\"{0}\" failures=\"{1}\" errors=\"0\" skipped=\"{2}\">\n",
+        cases.len(),
+        failures,
+        skipped
+    ));
+    for case in cases {
+        xml.push_str(&case);
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// Renders the result as GitHub Actions workflow commands, which show up as
+/// annotations on the changed files
+pub fn render_github_annotations(result: &ValidationResult) -> String {
+    let mut out = String::new();
+    for (problem, is_error) in problems(result) {
+        let mut properties = Vec::new();
+        if let Some(file) = &problem.file {
+            properties.push(format!("file={}", escape_property(file)));
+            if let Some(line) = problem.line {
+                properties.push(format!("line={}", line));
+            }
+            if let Some(column) = problem.column {
+                properties.push(format!("col={}", column));
+            }
+        }
+        properties.push(format!("title={}", escape_property(rule_id(problem))));
+
+        out.push_str(&format!(
+            "::{} {}::{}\n",
+            if is_error { "error" } else { "warning" },
+            properties.join(","),
+            escape_data(&problem.message)
+        ));
+    }
+    out
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::validate::ValidationStats;
+
+    fn error(
+        message: &str,
+        file: Option<&str>,
+        line: Option<usize>,
+        rule: Option<&str>,
+    ) -> ValidationError {
+        ValidationError {
+            message: message.to_string(),
+            file: file.map(str::to_string),
+            line,
+            column: line.map(|_| 5),
+            rule: rule.map(str::to_string),
+            suggestions: vec![],
+        }
+    }
+
+    fn create_result() -> ValidationResult {
+        ValidationResult {
+            valid: false,
+            errors: vec![
+                error(
+                    "unresolved reference \"order.db\"",
+                    Some("systems/order.yaml"),
+                    Some(6),
+                    None,
+                ),
+                error("Parse errors: 1 errors encountered", None, None, None),
+            ],
+            warnings: vec![error(
+                "shop: element has no relationships",
+                Some("model.yaml"),
+                Some(2),
+                Some("orphan-element"),
+            )],
+            stats: ValidationStats::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_output_format() {
+        assert_eq!("human".parse(), Ok(OutputFormat::Human));
+        assert_eq!(
+            "github-annotations".parse(),
+            Ok(OutputFormat::GithubAnnotations)
+        );
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_render_sarif() {
+        let log: Value = serde_json::from_str(&render_sarif(&create_result())).unwrap();
+        let run = &log["runs"][0];
+
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], DEFAULT_RULE);
+        assert_eq!(run["tool"]["driver"]["rules"][1]["id"], "orphan-element");
+        assert_eq!(
+            run["tool"]["driver"]["rules"][1]["shortDescription"]["text"],
+            "element has no relationships"
+        );
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["level"], "error");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "systems/order.yaml");
+        assert_eq!(location["region"]["startLine"], 6);
+        assert_eq!(location["region"]["startColumn"], 5);
+        assert!(results[1].get("locations").is_none());
+        assert_eq!(results[2]["level"], "warning");
+        assert_eq!(results[2]["ruleId"], "orphan-element");
+        assert_eq!(results[2]["ruleIndex"], 1);
+    }
+
+    #[test]
+    fn test_render_junit() {
+        let xml = render_junit(&create_result(), false);
+        assert!(xml.contains("tests=\"3\" failures=\"2\" skipped=\"1\""));
+        assert!(xml.contains(
+            "<testcase classname=\"systems/order.yaml\" name=\"systems/order.yaml:6:5: unresolved reference &quot;order.db&quot;\">"
+        ));
+        assert!(xml.contains("type=\"invalid-model\""));
+        assert!(xml.contains("<skipped message=\"warning: shop: element has no relationships\"/>"));
+
+        let strict = render_junit(&create_result(), true);
+        assert!(strict.contains("failures=\"3\" skipped=\"0\""));
+        assert!(strict.contains("type=\"orphan-element\""));
+    }
+
+    #[test]
+    fn test_render_junit_clean_workspace() {
+        let result = ValidationResult {
+            valid: true,
+            errors: vec![],
+            warnings: vec![],
+            stats: ValidationStats::default(),
+        };
+        let xml = render_junit(&result, false);
+        assert!(xml.contains("tests=\"1\" failures=\"0\""));
+        assert!(xml.contains("<testcase classname=\"workspace\" name=\"validate\"/>"));
+    }
+
+    #[test]
+    fn test_render_github_annotations() {
+        let out = render_github_annotations(&create_result());
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "::error file=systems/order.yaml,line=6,col=5,title=invalid-model::unresolved reference \"order.db\"",
+                "::error title=invalid-model::Parse errors: 1 errors encountered",
+                "::warning file=model.yaml,line=2,col=5,title=orphan-element::shop: element has no relationships",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_github_annotation_escaping() {
+        assert_eq!(escape_data("50%\nnext"), "50%25%0Anext");
+        assert_eq!(escape_property("a,b:c"), "a%2Cb%3Ac");
+    }
+}
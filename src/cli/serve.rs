@@ -60,15 +60,12 @@ pub fn run_serve(args: ServeArgs, work_dir: &Path, verbose: bool) -> Result<()>
         verbose,
     };
 
-    println!(
-        "Starting development server at http://{}:{}",
-        args.host, args.port
-    );
+    let url = config.url();
+    println!("Starting development server at {}", url);
     println!("Press Ctrl+C to stop");
 
     // Open browser if requested
     if !args.no_open {
-        let url = format!("http://{}:{}", args.host, args.port);
         if verbose {
             println!("Opening browser at {}", url);
         }
@@ -88,7 +85,7 @@ pub fn run_serve(args: ServeArgs, work_dir: &Path, verbose: bool) -> Result<()>
         server
             .run()
             .await
-            .map_err(|e| CliError::Server(format!("Server error: {}", e)))
+            .map_err(|e| CliError::Server(e.to_string()))
     })
 }
 
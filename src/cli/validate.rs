@@ -1,21 +1,47 @@
+use super::report::{self, OutputFormat};
 use super::{CliError, Result};
-use crate::parser::{Parser, Resolver};
+use crate::parser::lint::Level;
+use crate::parser::locations::SourceError;
+use crate::parser::rules::check_rules;
+use crate::parser::writer::FileChange;
+use crate::parser::{resolver, Linter, Parser, Resolver, SchemaValidator, Writer};
 use clap::Args;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 #[derive(Args, Debug)]
 pub struct ValidateArgs {
-    /// Specific files to validate
+    /// Only report problems in these files; the whole workspace is still
+    /// parsed to resolve references
     pub files: Vec<String>,
 
     /// Treat warnings as errors
     #[arg(long)]
     pub strict: bool,
 
-    /// Output results as JSON
-    #[arg(long)]
+    /// Output results as JSON (same as `--format json`)
+    #[arg(long, conflicts_with = "format")]
     pub json: bool,
+
+    /// Output format: human, json, sarif, junit, github-annotations
+    #[arg(long = "format", default_value = "human")]
+    pub format: String,
+
+    /// Replace unresolved references with the suggested element before
+    /// validating
+    #[arg(long)]
+    pub fix: bool,
+
+    /// Show the changes --fix would make without writing them
+    #[arg(long, requires = "fix")]
+    pub dry_run: bool,
+
+    /// Schema directory or data file schema to validate against, instead of
+    /// the mod file's `schema` or the bundled schemas
+    #[arg(long, value_name = "PATH")]
+    pub schema: Option<PathBuf>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,12 +61,22 @@ pub struct ValidationError {
     pub file: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    /// Lint rule that reported the problem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// Closest matching elements for an unresolved reference
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
 }
 
-impl std::fmt::Display for ValidationError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl ValidationError {
+    fn fmt_location(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(file) = &self.file {
-            if let Some(line) = self.line {
+            if let (Some(line), Some(column)) = (self.line, self.column) {
+                write!(f, "{}:{}:{}: {}", file, line, column, self.message)
+            } else if let Some(line) = self.line {
                 write!(f, "{}:{}: {}", file, line, self.message)
             } else {
                 write!(f, "{}: {}", file, self.message)
@@ -49,6 +85,55 @@ impl std::fmt::Display for ValidationError {
             write!(f, "{}", self.message)
         }
     }
+
+    /// Converts a parser error, keeping its location when it has one
+    fn from_parse_error(err: &anyhow::Error) -> Self {
+        match err.downcast_ref::<SourceError>() {
+            Some(e) => ValidationError {
+                message: e.message.clone(),
+                file: Some(e.location.file.clone()),
+                line: Some(e.location.line),
+                column: Some(e.location.column),
+                rule: None,
+                suggestions: vec![],
+            },
+            None => ValidationError {
+                message: format!("{:#}", err),
+                file: None,
+                line: None,
+                column: None,
+                rule: None,
+                suggestions: vec![],
+            },
+        }
+    }
+}
+
+impl From<&resolver::ValidationError> for ValidationError {
+    fn from(e: &resolver::ValidationError) -> Self {
+        ValidationError {
+            message: e.message.clone(),
+            file: if e.file.is_empty() {
+                None
+            } else {
+                Some(e.file.clone())
+            },
+            line: if e.line == 0 { None } else { Some(e.line) },
+            column: if e.column == 0 { None } else { Some(e.column) },
+            rule: None,
+            suggestions: e.suggestions.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_location(f)?;
+        if let Some(rule) = &self.rule {
+            write!(f, " [{}]", rule)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -75,14 +160,48 @@ pub fn run_validate(args: ValidateArgs, work_dir: &PathBuf, verbose: bool) -> Re
         ));
     }
 
+    let format = if args.json {
+        OutputFormat::Json
+    } else {
+        args.format.parse().map_err(CliError::Validation)?
+    };
+
+    if args.fix {
+        let (changes, skipped) = fix_references(work_dir, &args)?;
+        let mut summary = String::new();
+        for change in &changes {
+            summary.push_str(&render_diff(change));
+        }
+        for error in &skipped {
+            summary.push_str(&format!("Not fixed: {}\n", error));
+        }
+        summary.push_str(&match (changes.len(), args.dry_run) {
+            (0, _) => "No references to fix\n".to_string(),
+            (n, true) => format!("Would fix references in {} files (dry run)\n", n),
+            (n, false) => format!("Fixed references in {} files\n", n),
+        });
+        // Keep stdout parseable for machine-readable formats
+        if format == OutputFormat::Human {
+            println!("{}", summary);
+        } else {
+            eprintln!("{}", summary);
+        }
+    }
+
     let result = validate_workspace(work_dir, &args)?;
 
-    if args.json {
-        let json = serde_json::to_string_pretty(&result)
-            .map_err(|e| CliError::Validation(format!("JSON serialization failed: {}", e)))?;
-        println!("{}", json);
-    } else {
-        print_human_readable(&result);
+    match format {
+        OutputFormat::Human => print_human_readable(&result),
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&result)
+                .map_err(|e| CliError::Validation(format!("JSON serialization failed: {}", e)))?;
+            println!("{}", json);
+        }
+        OutputFormat::Sarif => println!("{}", report::render_sarif(&result)),
+        OutputFormat::Junit => print!("{}", report::render_junit(&result, args.strict)),
+        OutputFormat::GithubAnnotations => {
+            print!("{}", report::render_github_annotations(&result))
+        }
     }
 
     if !result.valid {
@@ -102,29 +221,31 @@ pub fn run_validate(args: ValidateArgs, work_dir: &PathBuf, verbose: bool) -> Re
     Ok(())
 }
 
-fn validate_workspace(work_dir: &PathBuf, _args: &ValidateArgs) -> Result<ValidationResult> {
-    let mut parser = Parser::new(work_dir);
+fn validate_workspace(work_dir: &PathBuf, args: &ValidateArgs) -> Result<ValidationResult> {
+    let files = file_filter(work_dir, &args.files)?;
+    let schema = load_schema(work_dir, args.schema.as_deref())?;
+    let mut parser = Parser::new(work_dir).with_schema(schema);
 
     let model = match parser.parse() {
         Ok(m) => m,
         Err(e) => {
-            let mut errors = vec![ValidationError {
-                message: e.to_string(),
-                file: None,
-                line: None,
-            }];
+            // The summary counts errors in every file, so it is left out
+            // when only some files are reported
+            let mut errors = match &files {
+                Some(_) => vec![],
+                None => vec![ValidationError::from_parse_error(&e)],
+            };
 
             // Include individual parse errors
             for err in parser.errors() {
-                errors.push(ValidationError {
-                    message: err.to_string(),
-                    file: None,
-                    line: None,
-                });
+                errors.push(ValidationError::from_parse_error(err));
+            }
+            if let Some(files) = &files {
+                retain_files(&mut errors, files);
             }
 
             return Ok(ValidationResult {
-                valid: false,
+                valid: errors.is_empty(),
                 errors,
                 warnings: vec![],
                 stats: ValidationStats::default(),
@@ -136,19 +257,36 @@ fn validate_workspace(work_dir: &PathBuf, _args: &ValidateArgs) -> Result<Valida
     let mut resolver = Resolver::new(&model);
     let resolution_errors = resolver.resolve();
 
-    let errors: Vec<ValidationError> = resolution_errors
+    let mut errors: Vec<ValidationError> = resolution_errors
         .iter()
-        .map(|e| ValidationError {
-            message: e.message.clone(),
-            file: if e.file.is_empty() {
-                None
-            } else {
-                Some(e.file.clone())
-            },
-            line: if e.line == 0 { None } else { Some(e.line) },
-        })
+        .map(ValidationError::from)
         .collect();
 
+    // Run lint rules at the levels configured in the mod file, then the
+    // workspace's architecture rules
+    let (levels, rules) = parser
+        .get_mod_file()
+        .map(|m| (m.lint.clone(), m.rules.clone()))
+        .unwrap_or_default();
+    let mut diagnostics = Linter::new(&model, &levels).lint();
+    diagnostics.extend(check_rules(&model, &rules));
+
+    let mut warnings = Vec::new();
+    for diagnostic in diagnostics {
+        let mut error = ValidationError::from(&diagnostic.error);
+        error.message = format!("{}: {}", diagnostic.error.path, error.message);
+        error.rule = Some(diagnostic.rule);
+        match diagnostic.level {
+            Level::Error => errors.push(error),
+            _ => warnings.push(error),
+        }
+    }
+
+    if let Some(files) = &files {
+        retain_files(&mut errors, files);
+        retain_files(&mut warnings, files);
+    }
+
     let stats = ValidationStats {
         persons: model.persons.len(),
         systems: model.systems.len(),
@@ -162,14 +300,143 @@ fn validate_workspace(work_dir: &PathBuf, _args: &ValidateArgs) -> Result<Valida
     Ok(ValidationResult {
         valid: errors.is_empty(),
         errors,
-        warnings: vec![],
+        warnings,
         stats,
     })
 }
 
+/// Applies the suggestions for unresolved references in the reported files,
+/// returning the changes and the references that have suggestions but no
+/// fix, because none is best or it would connect an element to itself. A workspace that fails to parse has
+/// nothing to fix; validation reports why.
+fn fix_references(
+    work_dir: &Path,
+    args: &ValidateArgs,
+) -> Result<(Vec<FileChange>, Vec<resolver::ValidationError>)> {
+    let files = file_filter(work_dir, &args.files)?;
+    let mut parser = Parser::new(work_dir);
+    let model = match parser.parse() {
+        Ok(model) => model,
+        Err(_) => return Ok((vec![], vec![])),
+    };
+
+    let mut errors = Resolver::new(&model).resolve();
+    if let Some(files) = &files {
+        errors.retain(|e| files.contains(&e.file));
+    }
+
+    let changes = Writer::new(&parser)
+        .apply_fixes(&errors, args.dry_run)
+        .map_err(|e| CliError::Validation(format!("failed to apply fixes: {:#}", e)))?;
+    let skipped = errors
+        .into_iter()
+        .filter(|e| e.fix.is_none() && !e.suggestions.is_empty())
+        .collect();
+    Ok((changes, skipped))
+}
+
+/// Renders a change as a unified diff without context lines. Fixes only
+/// rewrite text within lines, so both versions have the same line count.
+fn render_diff(change: &FileChange) -> String {
+    let before: Vec<&str> = change.before.split('\n').collect();
+    let after: Vec<&str> = change.after.split('\n').collect();
+
+    let mut diff = format!("--- a/{0}\n+++ b/{0}\n", change.file);
+    let mut i = 0;
+    while i < before.len() {
+        if before[i] == after.get(i).copied().unwrap_or_default() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < before.len() && before[i] != after.get(i).copied().unwrap_or_default() {
+            i += 1;
+        }
+        let count = i - start;
+        diff.push_str(&format!("@@ -{0},{1} +{0},{1} @@\n", start + 1, count));
+        for line in &before[start..i] {
+            diff.push_str(&format!("-{}\n", line));
+        }
+        for line in &after[start..i] {
+            diff.push_str(&format!("+{}\n", line));
+        }
+    }
+    diff
+}
+
+/// Workspace-relative paths of the files given on the command line, or None
+/// to report on every file. Paths are resolved against the current
+/// directory, falling back to the workspace.
+fn file_filter(work_dir: &Path, files: &[String]) -> Result<Option<HashSet<String>>> {
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let root = fs::canonicalize(work_dir)?;
+    let cwd = std::env::current_dir()?;
+
+    let mut filter = HashSet::new();
+    for file in files {
+        let path = [cwd.join(file), work_dir.join(file)]
+            .into_iter()
+            .find(|p| p.is_file())
+            .ok_or_else(|| CliError::Validation(format!("file not found: {}", file)))?;
+        let path = fs::canonicalize(&path)?;
+        let rel = path
+            .strip_prefix(&root)
+            .map_err(|_| CliError::Validation(format!("{} is not in the workspace", file)))?;
+        let rel: Vec<String> = rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        filter.insert(rel.join("/"));
+    }
+
+    Ok(Some(filter))
+}
+
+/// Keeps problems located in one of `files`. Problems with no file concern
+/// the workspace as a whole, so they are dropped like the parse summary and
+/// never fail a run limited to some files.
+fn retain_files(errors: &mut Vec<ValidationError>, files: &HashSet<String>) {
+    errors.retain(|e| e.file.as_ref().is_some_and(|f| files.contains(f)));
+}
+
+/// Picks the schemas to validate against: the `--schema` override, then the
+/// mod file's `schema` directory, then the bundled schemas
+fn load_schema(work_dir: &Path, schema: Option<&Path>) -> Result<SchemaValidator> {
+    let path = match schema {
+        Some(path) => Some(path.to_path_buf()),
+        None => {
+            // A mod file that fails to load is reported by the parser
+            let mut parser = Parser::new(work_dir);
+            parser
+                .load_mod_file(&work_dir.join("c4.mod.yaml"))
+                .ok()
+                .and_then(|_| parser.get_mod_file()?.schema.clone())
+                .filter(|s| !s.contains("://"))
+                .map(|s| work_dir.join(s))
+        }
+    };
+
+    let validator = match &path {
+        Some(path) => SchemaValidator::from_path(path),
+        None => SchemaValidator::bundled(),
+    };
+    validator.map_err(|e| CliError::Validation(format!("failed to load schema: {:#}", e)))
+}
+
 fn print_human_readable(result: &ValidationResult) {
-    if result.valid && result.warnings.is_empty() {
-        println!("Validation passed");
+    if result.valid {
+        if result.warnings.is_empty() {
+            println!("Validation passed");
+        } else {
+            println!("Validation passed with {} warnings", result.warnings.len());
+            println!();
+            for warning in &result.warnings {
+                println!("  WARN:  {}", warning);
+            }
+        }
         println!();
         println!("Model statistics:");
         println!("  Persons:       {}", result.stats.persons);
@@ -183,16 +450,11 @@ fn print_human_readable(result: &ValidationResult) {
         println!("Validation failed");
         println!();
 
-        if !result.errors.is_empty() {
-            for error in &result.errors {
-                println!("  ERROR: {}", error);
-            }
+        for error in &result.errors {
+            println!("  ERROR: {}", error);
         }
-
-        if !result.warnings.is_empty() {
-            for warning in &result.warnings {
-                println!("  WARN:  {}", warning);
-            }
+        for warning in &result.warnings {
+            println!("  WARN:  {}", warning);
         }
     }
 }
@@ -231,18 +493,39 @@ mod tests {
             message: "test error".to_string(),
             file: Some("test.yaml".to_string()),
             line: Some(42),
+            column: None,
+            rule: None,
+            suggestions: vec![],
         };
 
         let display = format!("{}", error);
         assert_eq!(display, "test.yaml:42: test error");
     }
 
+    #[test]
+    fn test_validation_error_display_with_column() {
+        let error = ValidationError {
+            message: "test error".to_string(),
+            file: Some("systems/order/containers.yaml".to_string()),
+            line: Some(42),
+            column: Some(5),
+            rule: None,
+            suggestions: vec![],
+        };
+
+        let display = format!("{}", error);
+        assert_eq!(display, "systems/order/containers.yaml:42:5: test error");
+    }
+
     #[test]
     fn test_validation_error_display_no_line() {
         let error = ValidationError {
             message: "test error".to_string(),
             file: Some("test.yaml".to_string()),
             line: None,
+            column: None,
+            rule: None,
+            suggestions: vec![],
         };
 
         let display = format!("{}", error);
@@ -255,6 +538,9 @@ mod tests {
             message: "test error".to_string(),
             file: None,
             line: None,
+            column: None,
+            rule: None,
+            suggestions: vec![],
         };
 
         let display = format!("{}", error);
@@ -268,6 +554,10 @@ mod tests {
             files: vec![],
             strict: false,
             json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
         };
 
         let result = run_validate(args, &dir.path().to_path_buf(), false);
@@ -291,6 +581,10 @@ mod tests {
             files: vec![],
             strict: false,
             json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
         };
 
         let result = run_validate(args, &dir.path().to_path_buf(), false);
@@ -310,12 +604,51 @@ mod tests {
             files: vec![],
             strict: false,
             json: true,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
         };
 
         let result = run_validate(args, &dir.path().to_path_buf(), false);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validate_output_formats() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\n",
+        )
+        .unwrap();
+
+        for format in ["human", "json", "sarif", "junit", "github-annotations"] {
+            let args = ValidateArgs {
+                files: vec![],
+                strict: false,
+                json: false,
+                format: format.to_string(),
+                fix: false,
+                dry_run: false,
+                schema: None,
+            };
+            assert!(run_validate(args, &dir.path().to_path_buf(), false).is_ok());
+        }
+
+        let args = ValidateArgs {
+            files: vec![],
+            strict: false,
+            json: false,
+            format: "xml".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        let err = run_validate(args, &dir.path().to_path_buf(), false).unwrap_err();
+        assert!(err.to_string().contains("invalid output format 'xml'"));
+    }
+
     #[test]
     fn test_validate_verbose_output() {
         let dir = TempDir::new().unwrap();
@@ -329,6 +662,10 @@ mod tests {
             files: vec![],
             strict: false,
             json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
         };
 
         let result = run_validate(args, &dir.path().to_path_buf(), true);
@@ -353,12 +690,545 @@ mod tests {
             files: vec![],
             strict: false,
             json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
         };
 
         let result = run_validate(args, &dir.path().to_path_buf(), false);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_validate_reports_source_locations() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"systems/*/*.yaml\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("systems/order")).unwrap();
+        fs::write(
+            dir.path().join("systems/order/system.yaml"),
+            "systems:\n  - id: order\n    name: Order\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("systems/order/containers.yaml"),
+            r#"containers:
+  - id: api
+    name: API
+
+relationships:
+  - from: order.api
+    to: order.db
+"#,
+        )
+        .unwrap();
+
+        let args = ValidateArgs {
+            files: vec![],
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        let result = validate_workspace(&dir.path().to_path_buf(), &args).unwrap();
+
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(
+            result.errors[0].to_string(),
+            "systems/order/containers.yaml:6:5: unresolved reference \"order.db\""
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_yaml_error_location() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "systems:\n  - id: shop\n    name: [unclosed\n",
+        )
+        .unwrap();
+
+        let args = ValidateArgs {
+            files: vec![],
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        let result = validate_workspace(&dir.path().to_path_buf(), &args).unwrap();
+
+        let located: Vec<&ValidationError> =
+            result.errors.iter().filter(|e| e.file.is_some()).collect();
+        assert_eq!(located.len(), 1);
+        assert_eq!(located[0].file.as_deref(), Some("model.yaml"));
+        assert!(located[0].line.is_some());
+    }
+
+    #[test]
+    fn test_validate_reports_schema_errors() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "persons:\n  - id: customer\n    nmae: Customer\n",
+        )
+        .unwrap();
+
+        let args = ValidateArgs {
+            files: vec![],
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        let result = validate_workspace(&dir.path().to_path_buf(), &args).unwrap();
+
+        let messages: Vec<String> = result
+            .errors
+            .iter()
+            .filter(|e| e.file.is_some())
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "model.yaml:2:5: persons[0]: missing required field \"name\"",
+                "model.yaml:3:5: persons[0]: unknown field \"nmae\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_with_custom_schema() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\nschema: schema\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "persons:\n  - id: customer\n    name: Customer\n",
+        )
+        .unwrap();
+        // The workspace schema requires every person to have tags
+        fs::create_dir_all(dir.path().join("schema")).unwrap();
+        fs::write(
+            dir.path().join("schema/person.schema.json"),
+            r#"{"type": "object", "required": ["id", "name", "tags"]}"#,
+        )
+        .unwrap();
+
+        let mut args = ValidateArgs {
+            files: vec![],
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        let result = validate_workspace(&dir.path().to_path_buf(), &args).unwrap();
+        assert!(!result.valid);
+
+        // --schema takes precedence over the mod file
+        args.schema = Some(dir.path().join("missing"));
+        assert!(validate_workspace(&dir.path().to_path_buf(), &args).is_err());
+    }
+
+    #[test]
+    fn test_validate_strict_fails_on_lint_warnings() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "systems:\n  - id: shop\n    name: Shop\n",
+        )
+        .unwrap();
+
+        let mut args = ValidateArgs {
+            files: vec![],
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        let result = validate_workspace(&dir.path().to_path_buf(), &args).unwrap();
+        assert!(result.valid);
+        let warnings: Vec<String> = result.warnings.iter().map(|w| w.to_string()).collect();
+        assert_eq!(
+            warnings,
+            vec![
+                "model.yaml:2:5: shop: element has no relationships [orphan-element]",
+                "model.yaml:2:5: shop: software system has no description [missing-description]",
+            ]
+        );
+
+        assert!(run_validate(args, &dir.path().to_path_buf(), false).is_ok());
+        args = ValidateArgs {
+            files: vec![],
+            strict: true,
+            json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        assert!(run_validate(args, &dir.path().to_path_buf(), false).is_err());
+    }
+
+    #[test]
+    fn test_validate_lint_levels_from_mod_file() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\nlint:\n  orphan-element: off\n  missing-description: error\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            "systems:\n  - id: shop\n    name: Shop\n",
+        )
+        .unwrap();
+
+        let args = ValidateArgs {
+            files: vec![],
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        let result = validate_workspace(&dir.path().to_path_buf(), &args).unwrap();
+        assert!(!result.valid);
+        assert!(result.warnings.is_empty());
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(
+            result.errors[0].rule.as_deref(),
+            Some("missing-description")
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_architecture_rule_violations() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            r#"version: "1.0"
+name: test
+include:
+  - "*.yaml"
+lint:
+  missing-description: off
+rules:
+  - id: gateway-only
+    description: External systems are reached through the gateway
+    only:
+      from: { tags: [gateway] }
+      to: { external: true }
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("model.yaml"),
+            r#"systems:
+  - id: shop
+    name: Shop
+  - id: stripe
+    name: Stripe
+    external: true
+containers:
+  - id: api
+    name: API
+    systemId: shop
+relationships:
+  - from: shop.api
+    to: stripe
+    technology: HTTPS
+"#,
+        )
+        .unwrap();
+
+        let args = ValidateArgs {
+            files: vec![],
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        let result = validate_workspace(&dir.path().to_path_buf(), &args).unwrap();
+
+        assert!(!result.valid);
+        assert_eq!(
+            result.errors[0].to_string(),
+            "model.yaml:12:5: relationship(shop.api -> stripe): \
+             External systems are reached through the gateway [gateway-only]"
+        );
+
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains("\"rule\":\"gateway-only\""));
+    }
+
+    #[test]
+    fn test_validate_only_listed_files() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\nlint:\n  orphan-element: off\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("systems.yaml"),
+            "systems:\n  - id: shop\n    name: Shop\n    description: Shop\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("good.yaml"),
+            "relationships:\n  - from: shop\n    to: shop\n    technology: HTTPS\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("bad.yaml"),
+            "relationships:\n  - from: shop\n    to: nope\n    technology: HTTPS\n",
+        )
+        .unwrap();
+
+        let args = |files: Vec<&str>| ValidateArgs {
+            files: files
+                .into_iter()
+                .map(|f| dir.path().join(f).to_string_lossy().to_string())
+                .collect(),
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        let work_dir = dir.path().to_path_buf();
+
+        // References to elements in other files still resolve
+        let result = validate_workspace(&work_dir, &args(vec!["good.yaml"])).unwrap();
+        assert!(result.valid);
+
+        let result = validate_workspace(&work_dir, &args(vec!["bad.yaml", "good.yaml"])).unwrap();
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].file.as_deref(), Some("bad.yaml"));
+
+        assert!(run_validate(args(vec!["good.yaml"]), &work_dir, false).is_ok());
+        assert!(run_validate(args(vec!["bad.yaml"]), &work_dir, false).is_err());
+        assert!(validate_workspace(&work_dir, &args(vec!["missing.yaml"])).is_err());
+    }
+
+    #[test]
+    fn test_retain_files_drops_problems_without_file() {
+        let error = |file: Option<&str>| ValidationError {
+            message: "problem".to_string(),
+            file: file.map(String::from),
+            line: None,
+            column: None,
+            rule: None,
+            suggestions: vec![],
+        };
+        let mut errors = vec![error(None), error(Some("a.yaml")), error(Some("b.yaml"))];
+
+        retain_files(&mut errors, &HashSet::from(["a.yaml".to_string()]));
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].file.as_deref(), Some("a.yaml"));
+    }
+
+    #[test]
+    fn test_validate_listed_files_with_parse_errors_elsewhere() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("good.yaml"),
+            "systems:\n  - id: shop\n    name: Shop\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("bad.yaml"), "systems: [\n").unwrap();
+
+        let args = ValidateArgs {
+            files: vec![dir.path().join("good.yaml").to_string_lossy().to_string()],
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: false,
+            dry_run: false,
+            schema: None,
+        };
+        let result = validate_workspace(&dir.path().to_path_buf(), &args).unwrap();
+        assert!(result.valid);
+        assert!(result.errors.is_empty());
+    }
+
+    #[test]
+    fn test_validate_fix() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        let content = "systems:\n  - id: shop\n    name: Shop\n  - id: payments\n    name: Payments\nrelationships:\n  - from: shop\n    to: paymnts # typo\n";
+        fs::write(dir.path().join("model.yaml"), content).unwrap();
+
+        let args = |dry_run: bool| ValidateArgs {
+            files: vec![],
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: true,
+            dry_run,
+            schema: None,
+        };
+        let work_dir = dir.path().to_path_buf();
+
+        let result = validate_workspace(&work_dir, &args(true)).unwrap();
+        assert_eq!(result.errors[0].suggestions, vec!["payments"]);
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains("\"suggestions\":[\"payments\"]"));
+
+        // A dry run reports the fix but validation still fails
+        assert!(run_validate(args(true), &work_dir, false).is_err());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("model.yaml")).unwrap(),
+            content
+        );
+
+        assert!(run_validate(args(false), &work_dir, false).is_ok());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("model.yaml")).unwrap(),
+            content.replace("to: paymnts", "to: payments")
+        );
+    }
+
+    #[test]
+    fn test_validate_fix_skips_tied_suggestions() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        let content = "systems:\n  - id: order\n    name: Order\n  - id: payment\n    name: Payment\ncontainers:\n  - id: api\n    name: Order API\n    systemId: order\n  - id: api\n    name: Payment API\n    systemId: payment\nrelationships:\n  - from: order.api\n    to: api\n";
+        fs::write(dir.path().join("model.yaml"), content).unwrap();
+
+        let args = ValidateArgs {
+            files: vec![],
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: true,
+            dry_run: false,
+            schema: None,
+        };
+        let (changes, skipped) = fix_references(dir.path(), &args).unwrap();
+
+        assert!(changes.is_empty());
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].suggestions, vec!["order.api", "payment.api"]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("model.yaml")).unwrap(),
+            content
+        );
+    }
+
+    #[test]
+    fn test_validate_fix_leaves_files_outside_workspace() {
+        let dir = TempDir::new().unwrap();
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        let other = "relationships:\n  - from: shop\n    to: paymnts # typo\n";
+        fs::write(outside.join("model.yaml"), other).unwrap();
+
+        let ws = dir.path().join("ws");
+        fs::create_dir_all(&ws).unwrap();
+        fs::write(
+            ws.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n  - \"../outside/*.yaml\"\n",
+        )
+        .unwrap();
+        let content = "systems:\n  - id: shop\n    name: Shop\n  - id: payments\n    name: Payments\nrelationships:\n  - from: payments\n    to: shp # typo\n";
+        fs::write(ws.join("model.yaml"), content).unwrap();
+
+        let args = ValidateArgs {
+            files: vec![],
+            strict: false,
+            json: false,
+            format: "human".to_string(),
+            fix: true,
+            dry_run: false,
+            schema: None,
+        };
+        let result = validate_workspace(&ws, &args).unwrap();
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.file.as_deref() == Some("../outside/model.yaml")));
+
+        let (changes, _) = fix_references(&ws, &args).unwrap();
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].file, "model.yaml");
+        assert_eq!(
+            fs::read_to_string(ws.join("model.yaml")).unwrap(),
+            content.replace("to: shp", "to: shop")
+        );
+        assert_eq!(
+            fs::read_to_string(outside.join("model.yaml")).unwrap(),
+            other
+        );
+    }
+
+    #[test]
+    fn test_render_diff() {
+        let change = FileChange {
+            file: "model.yaml".to_string(),
+            before: "a\nb\nc\nd\ne\n".to_string(),
+            after: "a\nB\nC\nd\nE\n".to_string(),
+        };
+        assert_eq!(
+            render_diff(&change),
+            "--- a/model.yaml\n+++ b/model.yaml\n\
+             @@ -2,2 +2,2 @@\n-b\n-c\n+B\n+C\n\
+             @@ -5,1 +5,1 @@\n-e\n+E\n"
+        );
+    }
+
     #[test]
     fn test_validation_stats_default() {
         let stats = ValidationStats::default();
@@ -379,6 +1249,9 @@ mod tests {
                 message: "test error".to_string(),
                 file: Some("test.yaml".to_string()),
                 line: Some(10),
+                column: None,
+                rule: None,
+                suggestions: vec![],
             }],
             warnings: vec![],
             stats: ValidationStats::default(),
@@ -432,11 +1305,17 @@ mod tests {
                 message: "test error".to_string(),
                 file: None,
                 line: None,
+                column: None,
+                rule: None,
+                suggestions: vec![],
             }],
             warnings: vec![ValidationError {
                 message: "test warning".to_string(),
                 file: None,
                 line: None,
+                column: None,
+                rule: None,
+                suggestions: vec![],
             }],
             stats: ValidationStats::default(),
         };
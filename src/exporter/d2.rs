@@ -0,0 +1,224 @@
+use crate::exporter::graph::{
+    edge_label, edge_style, level_name, node_label, node_style, Dash, Graph, Shape, LEVELS,
+};
+use crate::exporter::views::ViewElement;
+use crate::exporter::{ExporterError, Result};
+use crate::model;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Writes the relationship graph at every level as D2 into
+/// `<output_dir>/d2/<level>.d2` and returns the written paths
+pub fn export_d2(model: &model::Model, output_dir: &str) -> Result<Vec<PathBuf>> {
+    let dir = Path::new(output_dir).join("d2");
+    fs::create_dir_all(&dir)
+        .map_err(|e| ExporterError::D2Export(format!("Failed to create d2 directory: {}", e)))?;
+
+    let mut written = Vec::new();
+    for level in LEVELS {
+        let path = dir.join(format!("{}.d2", level_name(level)));
+        fs::write(&path, render_d2(&Graph::build(model, level))).map_err(|e| {
+            ExporterError::D2Export(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Renders a graph as D2, with systems and containers as containers holding
+/// their children. Connections refer to elements by their nested keys.
+pub fn render_d2(graph: &Graph) -> String {
+    let mut out = String::from("direction: down\n");
+
+    write_children(&mut out, graph, None, 0);
+
+    if !graph.edges.is_empty() {
+        out.push('\n');
+    }
+    for rel in &graph.edges {
+        let style = edge_style(rel);
+        let label = edge_label(rel);
+        let _ = write!(out, "{} -> {}", key(&rel.from), key(&rel.to));
+        if !label.is_empty() {
+            let _ = write!(out, ": {}", quote(&label.join("\n")));
+        }
+        out.push_str(" {\n");
+        let _ = writeln!(out, "  style.stroke: {}", quote(style.color));
+        if let Some(dash) = style.dash {
+            let _ = writeln!(out, "  style.stroke-dash: {}", stroke_dash(dash));
+        }
+        out.push_str("}\n");
+    }
+
+    out
+}
+
+fn write_children(out: &mut String, graph: &Graph, parent: Option<&str>, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let (clusters, nodes) = graph.children(parent);
+
+    for node in nodes {
+        let _ = writeln!(
+            out,
+            "{}{}: {} {{",
+            indent,
+            segment(&node.id),
+            quote(&node_label(node).join("\n"))
+        );
+        write_node_style(out, node, &indent);
+        let _ = writeln!(out, "{}}}", indent);
+    }
+
+    for cluster in clusters {
+        let _ = writeln!(
+            out,
+            "{}{}: {} {{",
+            indent,
+            segment(&cluster.id),
+            quote(&node_label(cluster)[..2].join("\n"))
+        );
+        let _ = writeln!(out, "{}  style.stroke: \"#444444\"", indent);
+        let _ = writeln!(out, "{}  style.stroke-dash: 3", indent);
+        let _ = writeln!(out, "{}  style.fill: \"#ffffff\"", indent);
+        write_children(out, graph, Some(&cluster.id), depth + 1);
+        let _ = writeln!(out, "{}}}", indent);
+    }
+}
+
+fn write_node_style(out: &mut String, element: &ViewElement, indent: &str) {
+    let style = node_style(element);
+    let shape = match style.shape {
+        Shape::Box => "rectangle",
+        Shape::Person => "person",
+        Shape::Cylinder => "cylinder",
+        Shape::Queue => "queue",
+    };
+    let _ = writeln!(out, "{}  shape: {}", indent, shape);
+    let _ = writeln!(out, "{}  style.fill: {}", indent, quote(style.palette.fill));
+    let _ = writeln!(
+        out,
+        "{}  style.stroke: {}",
+        indent,
+        quote(style.palette.stroke)
+    );
+    let _ = writeln!(
+        out,
+        "{}  style.font-color: {}",
+        indent,
+        quote(style.palette.text)
+    );
+}
+
+fn stroke_dash(dash: Dash) -> u8 {
+    match dash {
+        Dash::Dashed => 5,
+        Dash::Dotted => 2,
+    }
+}
+
+/// The full D2 key of an element, one quoted segment per nesting level
+fn key(id: &str) -> String {
+    id.split('.').map(quote).collect::<Vec<_>>().join(".")
+}
+
+/// The key of an element within its parent
+fn segment(id: &str) -> String {
+    quote(id.rsplit('.').next().unwrap_or(id))
+}
+
+fn quote(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ElementType;
+    use crate::parser::DataFile;
+    use tempfile::TempDir;
+
+    fn create_test_model() -> model::Model {
+        let data: DataFile = serde_yaml::from_str(
+            r#"
+persons:
+  - id: customer
+    name: Customer
+systems:
+  - id: shop
+    name: Shop
+containers:
+  - id: api
+    name: API
+    systemId: shop
+  - id: events
+    name: Events
+    systemId: shop
+    tags: [messaging]
+components:
+  - id: orders
+    name: Orders
+    systemId: shop
+    containerId: api
+relationships:
+  - from: customer
+    to: shop.api.orders
+    description: Orders
+    technology: gRPC
+  - from: shop.api.orders
+    to: shop.events
+"#,
+        )
+        .unwrap();
+
+        let mut m = model::Model::new();
+        m.persons = data.persons;
+        m.systems = data.systems;
+        m.containers = data.containers;
+        m.components = data.components;
+        m.relationships = data.relationships;
+        m.build_indexes().unwrap();
+        m
+    }
+
+    #[test]
+    fn test_render_container_level() {
+        let d2 = render_d2(&Graph::build(&create_test_model(), ElementType::Container));
+
+        assert!(d2.starts_with(
+            "direction: down\n\"customer\": \"Customer\\n[Person]\" {\n  shape: person\n"
+        ));
+        assert!(
+            d2.contains("\"shop\": \"Shop\\n[Software System]\" {\n  style.stroke: \"#444444\"\n")
+        );
+        assert!(d2.contains("  \"events\": \"Events\\n[Container]\" {\n    shape: queue\n"));
+        assert!(d2.contains(
+            "\"customer\" -> \"shop\".\"api\": \"Orders\\n[gRPC]\" {\n  style.stroke: \"#c084fc\"\n  style.stroke-dash: 2\n}\n"
+        ));
+        assert!(d2.contains("\"shop\".\"api\" -> \"shop\".\"events\" {\n"));
+    }
+
+    #[test]
+    fn test_render_component_level_nests_clusters() {
+        let d2 = render_d2(&Graph::build(&create_test_model(), ElementType::Component));
+
+        assert!(d2.contains("  \"api\": \"API\\n[Container]\" {\n    style.stroke: \"#444444\"\n"));
+        assert!(d2.contains("    \"orders\": \"Orders\\n[Component]\" {\n      shape: rectangle\n"));
+        assert!(d2.contains("\"customer\" -> \"shop\".\"api\".\"orders\""));
+    }
+
+    #[test]
+    fn test_export_d2_writes_file_per_level() {
+        let temp_dir = TempDir::new().unwrap();
+        let written = export_d2(&create_test_model(), temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(written.len(), 3);
+        assert!(temp_dir.path().join("d2/component.d2").exists());
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::{HashMap, HashSet};
+
+/// Hands out the aliases of one diagram. An alias is the element path with
+/// every character other than letters and digits turned into `_`; when that
+/// is already taken by another path it is numbered `_2`, `_3` and so on.
+#[derive(Debug, Default)]
+pub struct Aliases {
+    by_path: HashMap<String, String>,
+    used: HashSet<String>,
+}
+
+impl Aliases {
+    pub fn get(&mut self, path: &str) -> String {
+        self.get_for(path, path)
+    }
+
+    /// Like `get`, for a key that differs from the name the alias is made of
+    pub fn get_for(&mut self, key: &str, name: &str) -> String {
+        if let Some(alias) = self.by_path.get(key) {
+            return alias.clone();
+        }
+        let base = sanitize(name);
+        let mut alias = base.clone();
+        let mut n = 1;
+        while !self.used.insert(alias.clone()) {
+            n += 1;
+            alias = format!("{}_{}", base, n);
+        }
+        self.by_path.insert(key.to_string(), alias.clone());
+        alias
+    }
+}
+
+fn sanitize(path: &str) -> String {
+    path.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Quotes a label on one line. Neither format can escape double quotes, so
+/// they become single quotes.
+pub fn quote(s: &str) -> String {
+    let s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!("\"{}\"", s.replace('"', "'"))
+}
+
+/// Quotes macro arguments, dropping trailing empty ones
+pub fn args(values: &[&str]) -> String {
+    let len = values
+        .iter()
+        .rposition(|v| !v.trim().is_empty())
+        .map_or(1, |i| i + 1);
+    values[..len]
+        .iter()
+        .map(|v| quote(v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliases() {
+        let mut aliases = Aliases::default();
+        assert_eq!(aliases.get("shop.api"), "shop_api");
+        assert_eq!(aliases.get("api-gateway"), "api_gateway");
+        assert_eq!(aliases.get("shop-api"), "shop_api_2");
+        assert_eq!(aliases.get("shop_api.2"), "shop_api_2_2");
+        // A path keeps its alias
+        assert_eq!(aliases.get("shop.api"), "shop_api");
+        assert_eq!(aliases.get("shop-api"), "shop_api_2");
+        assert_eq!(aliases.get_for("deployment shop", "shop"), "shop");
+        assert_eq!(aliases.get_for("system shop", "shop"), "shop_2");
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("Says \"hi\"\n  twice"), "\"Says 'hi' twice\"");
+    }
+
+    #[test]
+    fn test_args_drops_trailing_empty() {
+        assert_eq!(args(&["A", "", "B", "", " "]), "\"A\", \"\", \"B\"");
+        assert_eq!(args(&["", ""]), "\"\"");
+    }
+}
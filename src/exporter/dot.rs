@@ -0,0 +1,253 @@
+use crate::exporter::graph::{
+    edge_label, edge_style, level_name, node_label, node_style, Dash, Graph, Shape, LEVELS,
+};
+use crate::exporter::views::ViewElement;
+use crate::exporter::{ExporterError, Result};
+use crate::model;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Writes the relationship graph at every level as Graphviz DOT into
+/// `<output_dir>/dot/<level>.dot` and returns the written paths
+pub fn export_dot(model: &model::Model, output_dir: &str) -> Result<Vec<PathBuf>> {
+    let dir = Path::new(output_dir).join("dot");
+    fs::create_dir_all(&dir)
+        .map_err(|e| ExporterError::DotExport(format!("Failed to create dot directory: {}", e)))?;
+
+    let mut written = Vec::new();
+    for level in LEVELS {
+        let path = dir.join(format!("{}.dot", level_name(level)));
+        fs::write(&path, render_dot(&Graph::build(model, level))).map_err(|e| {
+            ExporterError::DotExport(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Renders a graph as a DOT digraph, with systems and containers as clusters
+pub fn render_dot(graph: &Graph) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "digraph {} {{", quote(level_name(graph.level)));
+    out.push_str("    compound=true\n");
+    out.push_str("    fontname=\"Helvetica\"\n");
+    out.push_str("    node [fontname=\"Helvetica\" fontsize=11 style=\"filled\"]\n");
+    out.push_str("    edge [fontname=\"Helvetica\" fontsize=9]\n");
+
+    write_children(&mut out, graph, None, 1);
+
+    if !graph.edges.is_empty() {
+        out.push('\n');
+    }
+    for rel in &graph.edges {
+        let mut attrs = Vec::new();
+        // Edges cannot end at a cluster, so they are drawn to a node inside
+        // it and clipped at the cluster border
+        let from = match anchor(graph, &rel.from) {
+            Some(node) => {
+                attrs.push(format!("ltail={}", quote(&cluster_id(&rel.from))));
+                node
+            }
+            None => rel.from.as_str(),
+        };
+        let to = match anchor(graph, &rel.to) {
+            Some(node) => {
+                attrs.push(format!("lhead={}", quote(&cluster_id(&rel.to))));
+                node
+            }
+            None => rel.to.as_str(),
+        };
+
+        let label = edge_label(rel);
+        if !label.is_empty() {
+            attrs.insert(0, format!("label={}", quote(&label.join("\n"))));
+        }
+        let style = edge_style(rel);
+        attrs.push(format!("color={}", quote(style.color)));
+        if let Some(dash) = style.dash {
+            attrs.push(format!("style={}", dash_style(dash)));
+        }
+
+        let _ = writeln!(
+            out,
+            "    {} -> {} [{}]",
+            quote(from),
+            quote(to),
+            attrs.join(" ")
+        );
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+fn write_children(out: &mut String, graph: &Graph, parent: Option<&str>, depth: usize) {
+    let indent = "    ".repeat(depth);
+    let (clusters, nodes) = graph.children(parent);
+
+    for node in nodes {
+        let _ = writeln!(out, "{}{} [{}]", indent, quote(&node.id), node_attrs(node));
+    }
+
+    for cluster in clusters {
+        let _ = writeln!(
+            out,
+            "{}subgraph {} {{",
+            indent,
+            quote(&cluster_id(&cluster.id))
+        );
+        let _ = writeln!(
+            out,
+            "{}    label={}",
+            indent,
+            quote(&node_label(cluster)[..2].join("\n"))
+        );
+        let _ = writeln!(out, "{}    style=\"dashed,rounded\"", indent);
+        let _ = writeln!(out, "{}    color=\"#444444\"", indent);
+        write_children(out, graph, Some(&cluster.id), depth + 1);
+        let _ = writeln!(out, "{}}}", indent);
+    }
+}
+
+fn node_attrs(element: &ViewElement) -> String {
+    let style = node_style(element);
+    let (shape, extra) = match style.shape {
+        Shape::Box => ("box", " style=\"rounded,filled\""),
+        Shape::Person => ("box", " style=\"rounded,filled\" penwidth=2"),
+        Shape::Cylinder => ("cylinder", ""),
+        Shape::Queue => ("cds", ""),
+    };
+    format!(
+        "label={} shape={}{} fillcolor={} color={} fontcolor={}",
+        quote(&node_label(element).join("\n")),
+        shape,
+        extra,
+        quote(style.palette.fill),
+        quote(style.palette.stroke),
+        quote(style.palette.text)
+    )
+}
+
+/// The first node inside a cluster, used as the end of edges to the cluster
+fn anchor<'a>(graph: &'a Graph, id: &str) -> Option<&'a str> {
+    if !graph.is_cluster(id) {
+        return None;
+    }
+    let prefix = format!("{}.", id);
+    graph
+        .nodes
+        .iter()
+        .find(|n| n.id.starts_with(&prefix))
+        .map(|n| n.id.as_str())
+}
+
+fn cluster_id(id: &str) -> String {
+    format!("cluster_{}", id)
+}
+
+fn dash_style(dash: Dash) -> &'static str {
+    match dash {
+        Dash::Dashed => "dashed",
+        Dash::Dotted => "dotted",
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ElementType;
+    use crate::parser::DataFile;
+    use tempfile::TempDir;
+
+    fn create_test_model() -> model::Model {
+        let data: DataFile = serde_yaml::from_str(
+            r#"
+persons:
+  - id: customer
+    name: Customer
+    description: Buys "things"
+systems:
+  - id: shop
+    name: Shop
+containers:
+  - id: api
+    name: API
+    systemId: shop
+    technology: Rust
+  - id: db
+    name: Database
+    systemId: shop
+    tags: [database]
+relationships:
+  - from: customer
+    to: shop
+    description: Orders
+    technology: HTTPS
+  - from: shop.api
+    to: shop.db
+    tags: [async]
+"#,
+        )
+        .unwrap();
+
+        let mut m = model::Model::new();
+        m.persons = data.persons;
+        m.systems = data.systems;
+        m.containers = data.containers;
+        m.relationships = data.relationships;
+        m.build_indexes().unwrap();
+        m
+    }
+
+    #[test]
+    fn test_render_system_level() {
+        let dot = render_dot(&Graph::build(&create_test_model(), ElementType::System));
+
+        assert!(dot.starts_with("digraph \"system\" {\n    compound=true\n"));
+        assert!(dot.contains(
+            "    \"customer\" [label=\"Customer\\n[Person]\\n\\nBuys \\\"things\\\"\" shape=box"
+        ));
+        assert!(dot.contains(
+            "    \"customer\" -> \"shop\" [label=\"Orders\\n[HTTPS]\" color=\"#60a5fa\"]\n"
+        ));
+        assert!(!dot.contains("subgraph"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_render_container_level_with_clusters() {
+        let dot = render_dot(&Graph::build(&create_test_model(), ElementType::Container));
+
+        assert!(dot.contains(
+            "    subgraph \"cluster_shop\" {\n        label=\"Shop\\n[Software System]\"\n"
+        ));
+        assert!(
+            dot.contains("        \"shop.db\" [label=\"Database\\n[Container]\" shape=cylinder")
+        );
+        assert!(dot.contains("    \"shop.api\" -> \"shop.db\" [color=\"#fbbf24\" style=dashed]\n"));
+        // The edge to the system cluster ends at its first container
+        assert!(dot.contains(
+            "    \"customer\" -> \"shop.api\" [label=\"Orders\\n[HTTPS]\" lhead=\"cluster_shop\""
+        ));
+    }
+
+    #[test]
+    fn test_export_dot_writes_file_per_level() {
+        let temp_dir = TempDir::new().unwrap();
+        let written = export_dot(&create_test_model(), temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(written.len(), 3);
+        assert!(temp_dir.path().join("dot/container.dot").exists());
+    }
+}
@@ -0,0 +1,318 @@
+use crate::exporter::graph::{node_style, Shape};
+use crate::exporter::images::{type_label, wrap, DESCRIPTION_LINES, DESCRIPTION_WRAP};
+use crate::exporter::layout::{layout_view, Layout, Rect};
+use crate::exporter::views::{build_views, View, ViewElement};
+use crate::exporter::{ExporterError, Result};
+use crate::model::{self, ElementType};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "workspace.drawio";
+
+/// Writes every view as a page of `<output_dir>/workspace.drawio` and returns
+/// the written path
+pub fn export_drawio(model: &model::Model, output_dir: &str) -> Result<PathBuf> {
+    let path = Path::new(output_dir).join(FILE_NAME);
+    fs::write(&path, render_drawio(&build_views(model))).map_err(|e| {
+        ExporterError::DrawioExport(format!("Failed to write {}: {}", path.display(), e))
+    })?;
+    Ok(path)
+}
+
+/// Renders views as an uncompressed draw.io file with one page per view,
+/// positioned by the same layout as the images
+pub fn render_drawio(views: &[View]) -> String {
+    let mut out = String::from("<mxfile host=\"c4\">\n");
+    for view in views {
+        render_page(&mut out, view, &layout_view(view));
+    }
+    out.push_str("</mxfile>\n");
+    out
+}
+
+fn render_page(out: &mut String, view: &View, layout: &Layout) {
+    let _ = writeln!(
+        out,
+        "  <diagram id=\"{}\" name=\"{}\">",
+        escape(&view.key),
+        escape(&view.title)
+    );
+    let _ = writeln!(
+        out,
+        "    <mxGraphModel grid=\"1\" gridSize=\"10\" guides=\"1\" tooltips=\"1\" connect=\"1\" arrows=\"1\" fold=\"1\" page=\"1\" pageScale=\"1\" pageWidth=\"{}\" pageHeight=\"{}\" math=\"0\" shadow=\"0\">",
+        layout.width.ceil(),
+        layout.height.ceil()
+    );
+    out.push_str("      <root>\n");
+    out.push_str("        <mxCell id=\"0\"/>\n");
+    out.push_str("        <mxCell id=\"1\" parent=\"0\"/>\n");
+
+    write_vertex(
+        out,
+        "title",
+        &format!("<b>{}</b>", html(&view.title)),
+        "text;html=1;fontSize=22;align=left;verticalAlign=middle;strokeColor=none;fillColor=none;",
+        &Rect {
+            x: 40.0,
+            y: 40.0,
+            width: layout.width - 80.0,
+            height: 30.0,
+        },
+    );
+
+    if let (Some(scope), Some(b)) = (&view.scope, &layout.boundary) {
+        let label = format!(
+            "<b>{}</b><div style=\"font-size:11px\">{}</div>",
+            html(&scope.name),
+            html(&type_label(scope))
+        );
+        write_object(
+            out,
+            "boundary",
+            &label,
+            scope,
+            "rounded=1;arcSize=4;absoluteArcSize=1;html=1;whiteSpace=wrap;dashed=1;dashPattern=8 4;fillColor=none;strokeColor=#444444;fontColor=#444444;fontSize=14;align=left;verticalAlign=top;spacingLeft=10;spacingTop=4;connectable=0;",
+            b,
+        );
+    }
+
+    for element in &view.elements {
+        if let Some(rect) = layout.nodes.get(&element.id) {
+            write_object(
+                out,
+                &node_id(&element.id),
+                &node_label(element),
+                element,
+                &node_cell_style(element),
+                rect,
+            );
+        }
+    }
+
+    for (i, rel) in view.relationships.iter().enumerate() {
+        if !layout.nodes.contains_key(&rel.from) || !layout.nodes.contains_key(&rel.to) {
+            continue;
+        }
+        let mut label = html(&rel.description);
+        if !rel.technology.is_empty() {
+            if !label.is_empty() {
+                label.push_str("<br>");
+            }
+            let _ = write!(label, "[{}]", html(&rel.technology));
+        }
+        let _ = writeln!(
+            out,
+            "        <mxCell id=\"edge-{}\" value=\"{}\" style=\"endArrow=blockThin;endFill=1;endSize=12;html=1;rounded=0;dashed=1;dashPattern=6 4;strokeColor=#707070;strokeWidth=1.5;fontSize=11;fontColor=#444444;labelBackgroundColor=#ffffff;\" edge=\"1\" parent=\"1\" source=\"{}\" target=\"{}\">",
+            i,
+            escape(&label),
+            escape(&node_id(&rel.from)),
+            escape(&node_id(&rel.to))
+        );
+        out.push_str("          <mxGeometry relative=\"1\" as=\"geometry\"/>\n");
+        out.push_str("        </mxCell>\n");
+    }
+
+    out.push_str("      </root>\n");
+    out.push_str("    </mxGraphModel>\n");
+    out.push_str("  </diagram>\n");
+}
+
+/// Writes an element cell wrapped in an object carrying its C4 properties,
+/// as the draw.io C4 shape library does
+fn write_object(
+    out: &mut String,
+    id: &str,
+    label: &str,
+    element: &ViewElement,
+    style: &str,
+    rect: &Rect,
+) {
+    let kind = match element.element_type {
+        ElementType::Person => "Person",
+        ElementType::System => "Software System",
+        ElementType::Container => "Container",
+        ElementType::Component => "Component",
+    };
+    let _ = writeln!(
+        out,
+        "        <object id=\"{}\" label=\"{}\" c4Name=\"{}\" c4Type=\"{}\" c4Technology=\"{}\" c4Description=\"{}\">",
+        escape(id),
+        escape(label),
+        escape(&element.name),
+        kind,
+        escape(&element.technology),
+        escape(&element.description)
+    );
+    let _ = writeln!(
+        out,
+        "          <mxCell style=\"{}\" vertex=\"1\" parent=\"1\">",
+        style
+    );
+    write_geometry(out, rect, "            ");
+    out.push_str("          </mxCell>\n");
+    out.push_str("        </object>\n");
+}
+
+fn write_vertex(out: &mut String, id: &str, label: &str, style: &str, rect: &Rect) {
+    let _ = writeln!(
+        out,
+        "        <mxCell id=\"{}\" value=\"{}\" style=\"{}\" vertex=\"1\" parent=\"1\">",
+        id,
+        escape(label),
+        style
+    );
+    write_geometry(out, rect, "          ");
+    out.push_str("        </mxCell>\n");
+}
+
+fn write_geometry(out: &mut String, rect: &Rect, indent: &str) {
+    let _ = writeln!(
+        out,
+        "{}<mxGeometry x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" as=\"geometry\"/>",
+        indent, rect.x, rect.y, rect.width, rect.height
+    );
+}
+
+/// HTML label of an element: bold name, type and technology, then the
+/// wrapped description
+fn node_label(element: &ViewElement) -> String {
+    let mut label = format!(
+        "<b style=\"font-size:16px\">{}</b><div style=\"font-size:11px\">{}</div>",
+        html(&element.name),
+        html(&type_label(element))
+    );
+    let lines = wrap(&element.description, DESCRIPTION_WRAP, DESCRIPTION_LINES);
+    if !lines.is_empty() {
+        let lines: Vec<String> = lines.iter().map(|l| html(l)).collect();
+        let _ = write!(
+            label,
+            "<br><div style=\"font-size:12px\">{}</div>",
+            lines.join("<br>")
+        );
+    }
+    label
+}
+
+fn node_cell_style(element: &ViewElement) -> String {
+    let style = node_style(element);
+    let shape = match style.shape {
+        Shape::Box => "rounded=1;arcSize=8;",
+        Shape::Person => "shape=mxgraph.c4.person2;verticalAlign=bottom;spacingBottom=10;",
+        Shape::Cylinder => "shape=cylinder3;size=15;boundedLbl=1;",
+        Shape::Queue => "shape=cylinder3;size=15;direction=south;boundedLbl=1;",
+    };
+    format!(
+        "{}html=1;whiteSpace=wrap;align=center;fillColor={};strokeColor={};fontColor={};",
+        shape, style.palette.fill, style.palette.stroke, style.palette.text
+    )
+}
+
+fn node_id(id: &str) -> String {
+    format!("node-{}", id)
+}
+
+/// Escapes text for use inside an HTML label
+fn html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escapes text for use in an XML attribute
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DataFile;
+    use tempfile::TempDir;
+
+    fn create_test_model() -> model::Model {
+        let data: DataFile = serde_yaml::from_str(
+            r#"
+persons:
+  - id: customer
+    name: Customer
+    description: Buys <things> & more
+systems:
+  - id: shop
+    name: Shop
+containers:
+  - id: api
+    name: API
+    systemId: shop
+    technology: Rust
+  - id: db
+    name: Database
+    systemId: shop
+    tags: [database]
+relationships:
+  - from: customer
+    to: shop.api
+    description: Places orders
+    technology: HTTPS
+  - from: shop.api
+    to: shop.db
+"#,
+        )
+        .unwrap();
+
+        let mut m = model::Model::new();
+        m.persons = data.persons;
+        m.systems = data.systems;
+        m.containers = data.containers;
+        m.relationships = data.relationships;
+        m.build_indexes().unwrap();
+        m
+    }
+
+    #[test]
+    fn test_render_page_per_view() {
+        let xml = render_drawio(&build_views(&create_test_model()));
+
+        assert!(xml.starts_with("<mxfile host=\"c4\">\n"));
+        assert!(xml.contains("<diagram id=\"context-shop\" name=\"System Context: Shop\">"));
+        assert!(xml.contains("<diagram id=\"containers-shop\" name=\"Containers: Shop\">"));
+        assert_eq!(xml.matches("<diagram ").count(), 2);
+        assert!(xml.ends_with("</mxfile>\n"));
+    }
+
+    #[test]
+    fn test_render_elements_and_edges() {
+        let model = create_test_model();
+        let views = build_views(&model);
+        let view = views.iter().find(|v| v.key == "containers-shop").unwrap();
+        let xml = render_drawio(std::slice::from_ref(view));
+
+        assert!(xml.contains("<object id=\"boundary\" label=\"&lt;b&gt;Shop&lt;/b&gt;"));
+        assert!(xml.contains(
+            "<object id=\"node-shop.api\" label=\"&lt;b style=&quot;font-size:16px&quot;&gt;API&lt;/b&gt;&lt;div style=&quot;font-size:11px&quot;&gt;[Container: Rust]&lt;/div&gt;\" c4Name=\"API\" c4Type=\"Container\" c4Technology=\"Rust\""
+        ));
+        assert!(xml.contains("shape=cylinder3;size=15;boundedLbl=1;"));
+        assert!(xml.contains("shape=mxgraph.c4.person2;"));
+        // Descriptions are escaped once for HTML and once for XML
+        assert!(xml.contains("Buys &amp;lt;things&amp;gt; &amp;amp; more"));
+        assert!(xml.contains("c4Description=\"Buys &lt;things&gt; &amp; more\""));
+        assert!(
+            xml.contains("value=\"Places orders&lt;br&gt;[HTTPS]\" style=\"endArrow=blockThin;")
+        );
+        assert!(xml.contains("source=\"node-customer\" target=\"node-shop.api\""));
+        assert!(xml.contains("<mxGeometry x=\""));
+    }
+
+    #[test]
+    fn test_export_drawio_writes_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = export_drawio(&create_test_model(), temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(path, temp_dir.path().join("workspace.drawio"));
+        assert!(fs::read_to_string(path).unwrap().contains("<mxGraphModel"));
+    }
+}
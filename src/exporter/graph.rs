@@ -0,0 +1,394 @@
+use crate::exporter::images::{
+    palette, type_label, wrap, Palette, DESCRIPTION_LINES, DESCRIPTION_WRAP,
+};
+use crate::exporter::views::{
+    lookup, project_relationships, truncate, ViewElement, ViewRelationship,
+};
+use crate::model::{self, Element, ElementType};
+
+/// Levels a graph can be drawn at, from the coarsest
+pub const LEVELS: [ElementType; 3] = [
+    ElementType::System,
+    ElementType::Container,
+    ElementType::Component,
+];
+
+/// Name of a level as used in file names and on the command line
+pub fn level_name(level: ElementType) -> &'static str {
+    match level {
+        ElementType::System => "system",
+        ElementType::Container => "container",
+        ElementType::Component => "component",
+        ElementType::Person => "person",
+    }
+}
+
+/// Parses a level name such as `container`
+pub fn parse_level(s: &str) -> Option<ElementType> {
+    LEVELS.into_iter().find(|l| level_name(*l) == s)
+}
+
+/// The relationship graph of the whole model at one C4 level. Relationships
+/// of nested elements are rolled up to the level, and systems and containers
+/// with children at the level become clusters around them.
+#[derive(Debug, Clone)]
+pub struct Graph {
+    pub level: ElementType,
+    /// Systems and containers drawn as boundaries, parents before children
+    pub clusters: Vec<ViewElement>,
+    pub nodes: Vec<ViewElement>,
+    pub edges: Vec<ViewRelationship>,
+}
+
+impl Graph {
+    pub fn build(model: &model::Model, level: ElementType) -> Self {
+        let depth = depth(level);
+        let mut graph = Graph {
+            level,
+            clusters: Vec::new(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+
+        let containers = model.containers.iter().map(|c| c.get_full_path());
+        let components = model.components.iter().map(|c| c.get_full_path());
+        let paths = model
+            .persons
+            .iter()
+            .map(|p| p.get_full_path())
+            .chain(model.systems.iter().map(|s| s.get_full_path()))
+            .chain(containers.clone().filter(|_| depth >= 2))
+            .chain(components.clone().filter(|_| depth >= 3));
+        for path in paths {
+            let Some(element) = lookup(model, &path) else {
+                continue;
+            };
+            let prefix = format!("{}.", path);
+            let has_children = match element.element_type {
+                ElementType::System => {
+                    depth >= 2 && containers.clone().any(|c| c.starts_with(&prefix))
+                }
+                ElementType::Container => {
+                    depth >= 3 && components.clone().any(|c| c.starts_with(&prefix))
+                }
+                _ => false,
+            };
+            if has_children {
+                graph.clusters.push(element);
+            } else {
+                graph.nodes.push(element);
+            }
+        }
+
+        graph.edges = project_relationships(model, |path| Some(truncate(path, depth)))
+            .into_iter()
+            .filter(|r| graph.contains(&r.from) && graph.contains(&r.to))
+            .collect();
+        graph
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.is_cluster(id) || self.nodes.iter().any(|n| n.id == id)
+    }
+
+    pub fn is_cluster(&self, id: &str) -> bool {
+        self.clusters.iter().any(|c| c.id == id)
+    }
+
+    /// ID of the cluster an element is drawn in
+    pub fn parent(&self, id: &str) -> Option<&str> {
+        let (parent, _) = id.rsplit_once('.')?;
+        self.clusters
+            .iter()
+            .find(|c| c.id == parent)
+            .map(|c| c.id.as_str())
+    }
+
+    /// Clusters and nodes drawn directly inside `parent`, or at the top level
+    /// for None
+    pub fn children(&self, parent: Option<&str>) -> (Vec<&ViewElement>, Vec<&ViewElement>) {
+        let clusters = self
+            .clusters
+            .iter()
+            .filter(|c| self.parent(&c.id) == parent)
+            .collect();
+        let nodes = self
+            .nodes
+            .iter()
+            .filter(|n| self.parent(&n.id) == parent)
+            .collect();
+        (clusters, nodes)
+    }
+}
+
+fn depth(level: ElementType) -> usize {
+    match level {
+        ElementType::Person | ElementType::System => 1,
+        ElementType::Container => 2,
+        ElementType::Component => 3,
+    }
+}
+
+/// Lines of a node label: name, type and technology, then the wrapped
+/// description after a blank line
+pub fn node_label(element: &ViewElement) -> Vec<String> {
+    let mut lines = vec![element.name.clone(), type_label(element)];
+    if !element.description.is_empty() {
+        lines.push(String::new());
+        lines.extend(wrap(
+            &element.description,
+            DESCRIPTION_WRAP,
+            DESCRIPTION_LINES,
+        ));
+    }
+    lines
+}
+
+/// Lines of an edge label: description, then technology in brackets
+pub fn edge_label(rel: &ViewRelationship) -> Vec<String> {
+    let mut lines = Vec::new();
+    if !rel.description.is_empty() {
+        lines.push(rel.description.clone());
+    }
+    if !rel.technology.is_empty() {
+        lines.push(format!("[{}]", rel.technology));
+    }
+    lines
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Box,
+    Person,
+    Cylinder,
+    Queue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dash {
+    Dashed,
+    Dotted,
+}
+
+/// How a graph node is drawn
+pub struct NodeStyle {
+    pub palette: Palette,
+    pub shape: Shape,
+}
+
+/// How a graph edge is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgeStyle {
+    pub color: &'static str,
+    pub dash: Option<Dash>,
+}
+
+/// Styles an element by type, with `database` and `queue` tags picking the
+/// shape and the `external` tag the colors
+pub fn node_style(element: &ViewElement) -> NodeStyle {
+    let has_tag = |names: &[&str]| {
+        element
+            .tags
+            .iter()
+            .any(|t| names.contains(&t.to_lowercase().as_str()))
+    };
+
+    let mut colored = element.clone();
+    colored.external = element.external || has_tag(&["external"]);
+    let shape = if element.element_type == ElementType::Person {
+        Shape::Person
+    } else if has_tag(&["database", "db"]) {
+        Shape::Cylinder
+    } else if has_tag(&["queue", "messaging", "topic"]) {
+        Shape::Queue
+    } else {
+        Shape::Box
+    };
+
+    NodeStyle {
+        palette: palette(&colored),
+        shape,
+    }
+}
+
+/// Styles a relationship from its tags and technology, matching the edge
+/// colors of the web view
+pub fn edge_style(rel: &ViewRelationship) -> EdgeStyle {
+    let words: Vec<String> = rel
+        .tags
+        .iter()
+        .map(|t| t.to_lowercase())
+        .chain(
+            rel.technology
+                .split(',')
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| !t.is_empty()),
+        )
+        .collect();
+    let any = |needles: &[&str]| words.iter().any(|w| needles.iter().any(|n| w.contains(n)));
+
+    let (color, dash) = if any(&["async", "event", "queue", "kafka", "rabbitmq"]) {
+        ("#fbbf24", Some(Dash::Dashed))
+    } else if any(&["grpc", "rpc"]) {
+        ("#c084fc", Some(Dash::Dotted))
+    } else if any(&["rest", "http", "api"]) {
+        ("#60a5fa", None)
+    } else if any(&["db", "database", "sql", "postgres", "mysql"]) {
+        ("#34d399", Some(Dash::Dashed))
+    } else if any(&["file", "s3", "storage"]) {
+        ("#22d3ee", Some(Dash::Dotted))
+    } else {
+        ("#94a3b8", None)
+    };
+    EdgeStyle { color, dash }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DataFile;
+
+    fn create_test_model() -> model::Model {
+        let data: DataFile = serde_yaml::from_str(
+            r#"
+persons:
+  - id: customer
+    name: Customer
+systems:
+  - id: shop
+    name: Shop
+  - id: payments
+    name: Payments
+    external: true
+containers:
+  - id: web
+    name: Web
+    systemId: shop
+  - id: api
+    name: API
+    systemId: shop
+    technology: Rust
+  - id: db
+    name: Database
+    systemId: shop
+    tags: [database]
+components:
+  - id: checkout
+    name: Checkout
+    systemId: shop
+    containerId: api
+relationships:
+  - from: customer
+    to: shop.web
+    description: Browses
+    technology: HTTPS
+  - from: shop.web
+    to: shop.api.checkout
+    description: Submits orders
+    tags: [async]
+  - from: shop.api.checkout
+    to: shop.db
+    technology: SQL
+  - from: shop.api.checkout
+    to: payments
+  - from: customer
+    to: shop
+    description: Complains
+"#,
+        )
+        .unwrap();
+
+        let mut m = model::Model::new();
+        m.persons = data.persons;
+        m.systems = data.systems;
+        m.containers = data.containers;
+        m.components = data.components;
+        m.relationships = data.relationships;
+        m.build_indexes().unwrap();
+        m
+    }
+
+    fn ids(elements: &[ViewElement]) -> Vec<&str> {
+        elements.iter().map(|e| e.id.as_str()).collect()
+    }
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str)> {
+        graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_system_level() {
+        let graph = Graph::build(&create_test_model(), ElementType::System);
+
+        assert!(graph.clusters.is_empty());
+        assert_eq!(ids(&graph.nodes), vec!["customer", "shop", "payments"]);
+        assert_eq!(
+            edges(&graph),
+            vec![("customer", "shop"), ("shop", "payments")]
+        );
+    }
+
+    #[test]
+    fn test_container_level() {
+        let graph = Graph::build(&create_test_model(), ElementType::Container);
+
+        assert_eq!(ids(&graph.clusters), vec!["shop"]);
+        assert_eq!(
+            ids(&graph.nodes),
+            vec!["customer", "payments", "shop.web", "shop.api", "shop.db"]
+        );
+        assert_eq!(
+            edges(&graph),
+            vec![
+                ("customer", "shop.web"),
+                ("shop.web", "shop.api"),
+                ("shop.api", "shop.db"),
+                ("shop.api", "payments"),
+                ("customer", "shop"),
+            ]
+        );
+        assert_eq!(graph.parent("shop.web"), Some("shop"));
+        assert_eq!(graph.parent("customer"), None);
+    }
+
+    #[test]
+    fn test_component_level() {
+        let graph = Graph::build(&create_test_model(), ElementType::Component);
+
+        assert_eq!(ids(&graph.clusters), vec!["shop", "shop.api"]);
+        let (clusters, nodes) = graph.children(Some("shop"));
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(
+            nodes.iter().map(|n| n.id.as_str()).collect::<Vec<_>>(),
+            vec!["shop.web", "shop.db"]
+        );
+        assert!(edges(&graph).contains(&("shop.web", "shop.api.checkout")));
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("component"), Some(ElementType::Component));
+        assert_eq!(parse_level("person"), None);
+    }
+
+    #[test]
+    fn test_styles() {
+        let graph = Graph::build(&create_test_model(), ElementType::Container);
+        let node = |id: &str| graph.nodes.iter().find(|n| n.id == id).unwrap();
+
+        assert_eq!(node_style(node("customer")).shape, Shape::Person);
+        assert_eq!(node_style(node("shop.db")).shape, Shape::Cylinder);
+        assert_eq!(node_style(node("payments")).palette.fill, "#999999");
+
+        let styles: Vec<EdgeStyle> = graph.edges.iter().map(edge_style).collect();
+        assert_eq!(styles[0].dash, None);
+        assert_eq!(styles[0].color, "#60a5fa");
+        assert_eq!(styles[1].dash, Some(Dash::Dashed));
+        assert_eq!(styles[2].color, "#34d399");
+        assert_eq!(styles[3].color, "#94a3b8");
+    }
+}
@@ -1,39 +1,612 @@
-use crate::exporter::Result;
-use crate::model;
+use crate::exporter::layout::{layout_view, Layout, Rect};
+use crate::exporter::views::{build_views, View, ViewElement};
+use crate::exporter::{ExporterError, Result};
+use crate::model::{self, ElementType};
+use resvg::tiny_skia;
+use resvg::usvg::{self, fontdb};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
 
-pub fn export_images(_model: &model::Model, output_dir: &str) -> Result<()> {
-    eprintln!("Image export requires Playwright integration");
-    eprintln!("This feature is not yet implemented");
-    eprintln!("Output directory: {}", output_dir);
-    Ok(())
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "png" => Ok(ImageFormat::Png),
+            "svg" => Ok(ImageFormat::Svg),
+            _ => Err(format!(
+                "invalid image format '{}'. Must be 'png' or 'svg'",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageOptions {
+    pub format: ImageFormat,
+    /// Pixel multiplier applied when rasterizing; 1.0 renders at 96 DPI
+    pub scale: f32,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            format: ImageFormat::Png,
+            scale: 1.0,
+        }
+    }
+}
+
+impl ImageOptions {
+    pub fn new(format: ImageFormat) -> Self {
+        Self {
+            format,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the scale from a target resolution, relative to the 96 DPI
+    /// the SVG coordinates are expressed in
+    pub fn with_dpi(mut self, dpi: u32) -> Self {
+        self.scale = dpi as f32 / BASE_DPI;
+        self
+    }
+}
+
+const BASE_DPI: f32 = 96.0;
+
+/// DejaVu Sans, so labels still render on hosts without system fonts
+const FALLBACK_FONT: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+
+/// Renders every view of the model into `<output_dir>/images/<view>.<ext>`
+/// and returns the written paths
+pub fn export_images(
+    model: &model::Model,
+    output_dir: &str,
+    options: &ImageOptions,
+) -> Result<Vec<PathBuf>> {
+    if !(options.scale.is_finite() && options.scale > 0.0) {
+        return Err(ExporterError::ImageExport(format!(
+            "invalid scale {}: must be a positive number",
+            options.scale
+        )));
+    }
+
+    let fontdb = if options.format == ImageFormat::Png {
+        let mut db = fontdb::Database::new();
+        db.load_system_fonts();
+        db.load_font_data(FALLBACK_FONT.to_vec());
+        Some(Arc::new(db))
+    } else {
+        None
+    };
+
+    let images_dir = Path::new(output_dir).join("images");
+    fs::create_dir_all(&images_dir).map_err(|e| {
+        ExporterError::ImageExport(format!("Failed to create images directory: {}", e))
+    })?;
+
+    let mut written = Vec::new();
+    for view in build_views(model) {
+        let svg = render_svg(&view, &layout_view(&view));
+        let path = images_dir.join(format!("{}.{}", view.key, options.format.extension()));
+        let data = match &fontdb {
+            Some(db) => rasterize(&svg, db.clone(), options.scale)?,
+            None => svg.into_bytes(),
+        };
+        fs::write(&path, data).map_err(|e| {
+            ExporterError::ImageExport(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Rasterizes an SVG document to PNG bytes in-process
+pub fn rasterize(svg: &str, fontdb: Arc<fontdb::Database>, scale: f32) -> Result<Vec<u8>> {
+    let opt = usvg::Options {
+        fontdb,
+        ..usvg::Options::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &opt)
+        .map_err(|e| ExporterError::ImageExport(format!("Failed to parse SVG: {}", e)))?;
+
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or_else(|| ExporterError::ImageExport(format!("invalid image scale {}", scale)))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height()).ok_or_else(|| {
+        ExporterError::ImageExport(format!(
+            "Failed to allocate {}x{} image",
+            size.width(),
+            size.height()
+        ))
+    })?;
+
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    pixmap
+        .encode_png()
+        .map_err(|e| ExporterError::ImageExport(format!("Failed to encode PNG: {}", e)))
+}
+
+const FONT_FAMILY: &str = "Helvetica, Arial, DejaVu Sans, Liberation Sans, sans-serif";
+pub const DESCRIPTION_WRAP: usize = 32;
+pub const DESCRIPTION_LINES: usize = 3;
+
+/// C4 colors of an element
+pub struct Palette {
+    pub fill: &'static str,
+    pub stroke: &'static str,
+    pub text: &'static str,
+}
+
+pub fn palette(element: &ViewElement) -> Palette {
+    if element.external {
+        return Palette {
+            fill: "#999999",
+            stroke: "#8a8a8a",
+            text: "#ffffff",
+        };
+    }
+    match element.element_type {
+        ElementType::Person => Palette {
+            fill: "#08427b",
+            stroke: "#073b6f",
+            text: "#ffffff",
+        },
+        ElementType::System => Palette {
+            fill: "#1168bd",
+            stroke: "#0b4884",
+            text: "#ffffff",
+        },
+        ElementType::Container => Palette {
+            fill: "#438dd5",
+            stroke: "#3c7fc0",
+            text: "#ffffff",
+        },
+        ElementType::Component => Palette {
+            fill: "#85bbf0",
+            stroke: "#78a8d8",
+            text: "#000000",
+        },
+    }
+}
+
+/// Type and technology line of an element, such as `[Container: Rust]`
+pub fn type_label(element: &ViewElement) -> String {
+    let kind = match element.element_type {
+        ElementType::Person => "Person",
+        ElementType::System => "Software System",
+        ElementType::Container => "Container",
+        ElementType::Component => "Component",
+    };
+    if element.technology.is_empty() {
+        format!("[{}]", kind)
+    } else {
+        format!("[{}: {}]", kind, element.technology)
+    }
+}
+
+/// Renders a laid out view as a standalone SVG document
+pub fn render_svg(view: &View, layout: &Layout) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="{font}">"#,
+        w = layout.width,
+        h = layout.height,
+        font = FONT_FAMILY
+    );
+    out.push_str(
+        r##"  <defs>
+    <marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto-start-reverse">
+      <path d="M 0 0 L 10 5 L 0 10 z" fill="#707070"/>
+    </marker>
+  </defs>
+"##,
+    );
+    let _ = writeln!(
+        out,
+        r##"  <rect x="0" y="0" width="{}" height="{}" fill="#ffffff"/>"##,
+        layout.width, layout.height
+    );
+    let _ = writeln!(
+        out,
+        r##"  <text x="40" y="60" font-size="22" font-weight="bold" fill="#000000">{}</text>"##,
+        escape(&view.title)
+    );
+
+    if let (Some(scope), Some(b)) = (&view.scope, &layout.boundary) {
+        let _ = writeln!(
+            out,
+            r##"  <rect x="{}" y="{}" width="{}" height="{}" rx="4" fill="none" stroke="#444444" stroke-width="1.5" stroke-dasharray="8 4"/>"##,
+            b.x, b.y, b.width, b.height
+        );
+        let _ = writeln!(
+            out,
+            r##"  <text x="{}" y="{}" font-size="14" font-weight="bold" fill="#444444">{} {}</text>"##,
+            b.x + 12.0,
+            b.y + 22.0,
+            escape(&scope.name),
+            escape(&type_label(scope))
+        );
+    }
+
+    let edges: Vec<_> = view
+        .relationships
+        .iter()
+        .filter_map(|rel| {
+            let from = layout.nodes.get(&rel.from)?;
+            let to = layout.nodes.get(&rel.to)?;
+            Some((rel, edge_endpoints(from, to)))
+        })
+        .collect();
+
+    for (_, (x1, y1, x2, y2)) in &edges {
+        let _ = writeln!(
+            out,
+            r##"  <line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#707070" stroke-width="1.5" stroke-dasharray="6 4" marker-end="url(#arrow)"/>"##,
+            x1, y1, x2, y2
+        );
+    }
+
+    for element in &view.elements {
+        if let Some(rect) = layout.nodes.get(&element.id) {
+            render_node(&mut out, element, rect);
+        }
+    }
+
+    // Labels go last so they stay readable where edges pass behind nodes
+    for (rel, (x1, y1, x2, y2)) in &edges {
+        render_edge_label(
+            &mut out,
+            (x1 + x2) / 2.0,
+            (y1 + y2) / 2.0,
+            &rel.description,
+            &rel.technology,
+        );
+    }
+
+    out.push_str("</svg>\n");
+    out
+}
+
+fn render_node(out: &mut String, element: &ViewElement, rect: &Rect) {
+    let colors = palette(element);
+    let (cx, _) = rect.center();
+    let mut y = rect.y + 34.0;
+
+    let _ = writeln!(out, r#"  <g id="{}">"#, escape(&element.id));
+    if element.element_type == ElementType::Person {
+        let _ = writeln!(
+            out,
+            r#"    <rect x="{}" y="{}" width="{}" height="{}" rx="40" fill="{}" stroke="{}"/>"#,
+            rect.x,
+            rect.y + 30.0,
+            rect.width,
+            rect.height - 30.0,
+            colors.fill,
+            colors.stroke
+        );
+        let _ = writeln!(
+            out,
+            r#"    <circle cx="{}" cy="{}" r="28" fill="{}" stroke="{}"/>"#,
+            cx,
+            rect.y + 22.0,
+            colors.fill,
+            colors.stroke
+        );
+        y += 30.0;
+    } else {
+        let _ = writeln!(
+            out,
+            r#"    <rect x="{}" y="{}" width="{}" height="{}" rx="8" fill="{}" stroke="{}"/>"#,
+            rect.x, rect.y, rect.width, rect.height, colors.fill, colors.stroke
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        r#"    <text x="{}" y="{}" text-anchor="middle" font-size="16" font-weight="bold" fill="{}">{}</text>"#,
+        cx,
+        y,
+        colors.text,
+        escape(&element.name)
+    );
+    y += 18.0;
+    let _ = writeln!(
+        out,
+        r#"    <text x="{}" y="{}" text-anchor="middle" font-size="11" fill="{}">{}</text>"#,
+        cx,
+        y,
+        colors.text,
+        escape(&type_label(element))
+    );
+    y += 22.0;
+
+    for line in wrap(&element.description, DESCRIPTION_WRAP, DESCRIPTION_LINES) {
+        let _ = writeln!(
+            out,
+            r#"    <text x="{}" y="{}" text-anchor="middle" font-size="12" fill="{}">{}</text>"#,
+            cx,
+            y,
+            colors.text,
+            escape(&line)
+        );
+        y += 15.0;
+    }
+    out.push_str("  </g>\n");
+}
+
+fn edge_endpoints(from: &Rect, to: &Rect) -> (f64, f64, f64, f64) {
+    let (tx, ty) = to.center();
+    let (fx, fy) = from.center();
+    let (x1, y1) = from.clip(tx, ty);
+    let (x2, y2) = to.clip(fx, fy);
+    (x1, y1, x2, y2)
+}
+
+fn render_edge_label(out: &mut String, mx: f64, my: f64, description: &str, technology: &str) {
+    let mut lines = wrap(description, DESCRIPTION_WRAP, 2);
+    if !technology.is_empty() {
+        lines.push(format!("[{}]", technology));
+    }
+    let top = my - (lines.len() as f64 - 1.0) * 7.0;
+    for (i, line) in lines.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            r##"  <text x="{}" y="{}" text-anchor="middle" font-size="11" fill="#444444" paint-order="stroke" stroke="#ffffff" stroke-width="3">{}</text>"##,
+            mx,
+            top + i as f64 * 14.0,
+            escape(line)
+        );
+    }
+}
+
+/// Word-wraps text into at most `max_lines` lines, ending in an ellipsis
+/// when truncated
+pub fn wrap(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            last.push_str("...");
+        }
+    }
+    lines
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::TempDir;
 
     fn create_test_model() -> model::Model {
-        model::Model::new()
+        let mut m = model::Model::new();
+        m.persons.push(model::Person {
+            base: model::BaseElement {
+                id: "user".to_string(),
+                name: "User".to_string(),
+                description: Some("A <user>".to_string()),
+                tags: None,
+                properties: None,
+            },
+            element_type: ElementType::Person,
+        });
+        m.systems.push(model::SoftwareSystem {
+            base: model::BaseElement {
+                id: "app".to_string(),
+                name: "Application".to_string(),
+                description: Some("The app".to_string()),
+                tags: None,
+                properties: None,
+            },
+            element_type: ElementType::System,
+            external: None,
+        });
+        m.containers.push(model::Container {
+            base: model::BaseElement {
+                id: "web".to_string(),
+                name: "Web".to_string(),
+                description: None,
+                tags: None,
+                properties: None,
+            },
+            element_type: ElementType::Container,
+            technology: Some(model::Technology::new(vec!["React".to_string()])),
+            system_id: "app".to_string(),
+        });
+        m.relationships.push(model::Relationship {
+            from: "user".to_string(),
+            to: "app.web".to_string(),
+            description: Some("Uses".to_string()),
+            technology: Some(model::Technology::new(vec!["HTTPS".to_string()])),
+            tags: None,
+            properties: None,
+            implied: false,
+        });
+        m.build_indexes().unwrap();
+        m
     }
 
     #[test]
-    fn test_export_images_returns_ok() {
+    fn test_image_format_from_str() {
+        assert_eq!("svg".parse::<ImageFormat>(), Ok(ImageFormat::Svg));
+        assert_eq!("png".parse::<ImageFormat>(), Ok(ImageFormat::Png));
+        assert!("gif".parse::<ImageFormat>().is_err());
+    }
+
+    #[test]
+    fn test_export_images_writes_svg_per_view() {
         let model = create_test_model();
-        let result = export_images(&model, "/tmp/test");
-        assert!(result.is_ok());
+        let temp_dir = TempDir::new().unwrap();
+
+        let written = export_images(
+            &model,
+            temp_dir.path().to_str().unwrap(),
+            &ImageOptions::new(ImageFormat::Svg),
+        )
+        .unwrap();
+
+        assert_eq!(written.len(), 2);
+        let images = temp_dir.path().join("images");
+        assert!(images.join("context-app.svg").exists());
+        assert!(images.join("containers-app.svg").exists());
     }
 
     #[test]
     fn test_export_images_handles_empty_model() {
+        let model = model::Model::new();
+        let temp_dir = TempDir::new().unwrap();
+
+        let written = export_images(
+            &model,
+            temp_dir.path().to_str().unwrap(),
+            &ImageOptions::new(ImageFormat::Svg),
+        )
+        .unwrap();
+        assert!(written.is_empty());
+    }
+
+    #[test]
+    fn test_export_images_writes_png() {
+        let model = create_test_model();
+        let temp_dir = TempDir::new().unwrap();
+
+        let written = export_images(
+            &model,
+            temp_dir.path().to_str().unwrap(),
+            &ImageOptions::new(ImageFormat::Png),
+        )
+        .unwrap();
+
+        assert_eq!(written.len(), 2);
+        let data = fs::read(temp_dir.path().join("images/context-app.png")).unwrap();
+        assert_eq!(&data[1..4], b"PNG");
+    }
+
+    #[test]
+    fn test_export_images_rejects_invalid_scale() {
         let model = create_test_model();
-        let result = export_images(&model, "/tmp/test");
-        assert!(result.is_ok());
+        let temp_dir = TempDir::new().unwrap();
+
+        let result = export_images(
+            &model,
+            temp_dir.path().to_str().unwrap(),
+            &ImageOptions::new(ImageFormat::Png).with_scale(0.0),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_rasterize_applies_scale() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="20"></svg>"#;
+        let db = Arc::new(fontdb::Database::new());
+
+        let png = rasterize(svg, db, 2.0).unwrap();
+        let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+        assert_eq!((width, height), (20, 40));
+    }
+
+    #[test]
+    fn test_rasterize_renders_text_with_fallback_font() {
+        let svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="40" font-family="{}"><text x="10" y="30" font-size="20">Label</text></svg>"#,
+            FONT_FAMILY
+        );
+        let blank = rasterize(&svg, Arc::new(fontdb::Database::new()), 1.0).unwrap();
+
+        let mut db = fontdb::Database::new();
+        db.load_font_data(FALLBACK_FONT.to_vec());
+        let png = rasterize(&svg, Arc::new(db), 1.0).unwrap();
+
+        assert_ne!(png, blank);
+    }
+
+    #[test]
+    fn test_image_options_dpi() {
+        let options = ImageOptions::new(ImageFormat::Png).with_dpi(192);
+        assert_eq!(options.scale, 2.0);
     }
 
     #[test]
-    fn test_export_images_invalid_path() {
+    fn test_render_svg_content() {
         let model = create_test_model();
-        let result = export_images(&model, "/invalid/path/that/does/not/exist");
-        assert!(result.is_ok());
+        let view = crate::exporter::views::container_view(&model, "app");
+        let svg = render_svg(&view, &layout_view(&view));
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("Containers: Application"));
+        assert!(svg.contains("[Container: React]"));
+        assert!(svg.contains("A &lt;user&gt;"));
+        assert!(svg.contains("[HTTPS]"));
+        assert!(svg.contains("stroke-dasharray=\"8 4\""));
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("", 10, 2), Vec::<String>::new());
+        assert_eq!(wrap("one two three", 7, 3), vec!["one two", "three"]);
+        assert_eq!(wrap("a b c d", 1, 2), vec!["a", "b..."]);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a & <b> \"c\""), "a &amp; &lt;b&gt; &quot;c&quot;");
     }
 }
@@ -0,0 +1,397 @@
+use crate::exporter::views::View;
+use std::collections::HashMap;
+
+pub const NODE_WIDTH: f64 = 240.0;
+pub const NODE_HEIGHT: f64 = 150.0;
+const H_GAP: f64 = 80.0;
+const V_GAP: f64 = 120.0;
+const MARGIN: f64 = 40.0;
+const TITLE_HEIGHT: f64 = 50.0;
+const BOUNDARY_PADDING: f64 = 30.0;
+const BOUNDARY_LABEL_HEIGHT: f64 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    /// Returns the point where the line from the center towards (tx, ty)
+    /// crosses the rectangle's border
+    pub fn clip(&self, tx: f64, ty: f64) -> (f64, f64) {
+        let (cx, cy) = self.center();
+        let (dx, dy) = (tx - cx, ty - cy);
+        if dx == 0.0 && dy == 0.0 {
+            return (cx, cy);
+        }
+        let sx = if dx != 0.0 {
+            (self.width / 2.0) / dx.abs()
+        } else {
+            f64::INFINITY
+        };
+        let sy = if dy != 0.0 {
+            (self.height / 2.0) / dy.abs()
+        } else {
+            f64::INFINITY
+        };
+        let s = sx.min(sy);
+        (cx + dx * s, cy + dy * s)
+    }
+}
+
+/// Node positions for a view
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub nodes: HashMap<String, Rect>,
+    pub boundary: Option<Rect>,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Computes a layered top-down layout. Views with a scope get their scoped
+/// elements laid out inside a boundary, with callers of the scope above it
+/// and everything else below it.
+pub fn layout_view(view: &View) -> Layout {
+    let ids: Vec<&str> = view.elements.iter().map(|e| e.id.as_str()).collect();
+    let index: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let edges: Vec<(usize, usize)> = view
+        .relationships
+        .iter()
+        .filter_map(|r| Some((*index.get(r.from.as_str())?, *index.get(r.to.as_str())?)))
+        .collect();
+
+    let rows: Vec<Vec<usize>>;
+    let mut inner_rows = 0..0;
+
+    if view.scope.is_some() {
+        let inside: Vec<bool> = view.elements.iter().map(|e| view.in_scope(e)).collect();
+        let inner: Vec<usize> = (0..ids.len()).filter(|&i| inside[i]).collect();
+        let inner_edges: Vec<(usize, usize)> = edges
+            .iter()
+            .filter(|(a, b)| inside[*a] && inside[*b])
+            .copied()
+            .collect();
+
+        let callers: Vec<usize> = (0..ids.len())
+            .filter(|&i| !inside[i] && edges.iter().any(|(a, b)| *a == i && inside[*b]))
+            .collect();
+        let others: Vec<usize> = (0..ids.len())
+            .filter(|&i| !inside[i] && !callers.contains(&i))
+            .collect();
+
+        let mut all = Vec::new();
+        if !callers.is_empty() {
+            all.push(callers);
+        }
+        let start = all.len();
+        all.extend(layer(&inner, &inner_edges));
+        inner_rows = start..all.len();
+        if !others.is_empty() {
+            all.push(others);
+        }
+        rows = all;
+    } else {
+        let all: Vec<usize> = (0..ids.len()).collect();
+        rows = layer(&all, &edges);
+    }
+
+    let row_width = |row: &Vec<usize>| {
+        row.len() as f64 * NODE_WIDTH + (row.len().saturating_sub(1)) as f64 * H_GAP
+    };
+    let inner_width = rows[inner_rows.clone()]
+        .iter()
+        .map(row_width)
+        .fold(0.0, f64::max);
+    let content_width = rows
+        .iter()
+        .map(row_width)
+        .fold(0.0, f64::max)
+        .max(if inner_rows.is_empty() {
+            0.0
+        } else {
+            inner_width + 2.0 * BOUNDARY_PADDING
+        })
+        .max(NODE_WIDTH);
+    let center_x = MARGIN + content_width / 2.0;
+
+    let mut nodes = HashMap::new();
+    let mut boundary = None;
+    let mut y = MARGIN + TITLE_HEIGHT;
+
+    for (r, row) in rows.iter().enumerate() {
+        if !inner_rows.is_empty() && r == inner_rows.start {
+            boundary = Some(Rect {
+                x: center_x - inner_width / 2.0 - BOUNDARY_PADDING,
+                y,
+                width: inner_width + 2.0 * BOUNDARY_PADDING,
+                height: 0.0,
+            });
+            y += BOUNDARY_LABEL_HEIGHT + BOUNDARY_PADDING;
+        }
+
+        let mut x = center_x - row_width(row) / 2.0;
+        for &i in row {
+            nodes.insert(
+                ids[i].to_string(),
+                Rect {
+                    x,
+                    y,
+                    width: NODE_WIDTH,
+                    height: NODE_HEIGHT,
+                },
+            );
+            x += NODE_WIDTH + H_GAP;
+        }
+        y += NODE_HEIGHT;
+
+        if !inner_rows.is_empty() && r + 1 == inner_rows.end {
+            y += BOUNDARY_PADDING;
+            if let Some(b) = boundary.as_mut() {
+                b.height = y - b.y;
+            }
+        }
+        y += V_GAP;
+    }
+
+    Layout {
+        nodes,
+        boundary,
+        width: content_width + 2.0 * MARGIN,
+        height: (y - V_GAP + MARGIN).max(MARGIN * 2.0 + TITLE_HEIGHT),
+    }
+}
+
+/// Assigns nodes to rows by longest path (ignoring back edges of cycles)
+/// and orders each row with a few barycenter sweeps to reduce crossings
+fn layer(nodes: &[usize], edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    if nodes.is_empty() {
+        return Vec::new();
+    }
+
+    let pos: HashMap<usize, usize> = nodes.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+    let n = nodes.len();
+    let mut adj: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (a, b) in edges {
+        if let (Some(&a), Some(&b)) = (pos.get(a), pos.get(b)) {
+            if a != b && !adj[a].contains(&b) {
+                adj[a].push(b);
+            }
+        }
+    }
+
+    // Drop back edges found by DFS so the remaining graph is acyclic
+    let mut state = vec![0u8; n];
+    let mut dag: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for start in 0..n {
+        if state[start] != 0 {
+            continue;
+        }
+        let mut stack = vec![(start, 0usize)];
+        state[start] = 1;
+        while let Some(top) = stack.last_mut() {
+            let node = top.0;
+            if top.1 < adj[node].len() {
+                let target = adj[node][top.1];
+                top.1 += 1;
+                match state[target] {
+                    0 => {
+                        dag[node].push(target);
+                        state[target] = 1;
+                        stack.push((target, 0));
+                    }
+                    2 => dag[node].push(target),
+                    _ => {}
+                }
+            } else {
+                state[node] = 2;
+                stack.pop();
+            }
+        }
+    }
+
+    // Longest path ranking in topological order
+    let mut indegree = vec![0usize; n];
+    for targets in &dag {
+        for &t in targets {
+            indegree[t] += 1;
+        }
+    }
+    let mut queue: Vec<usize> = (0..n).filter(|&i| indegree[i] == 0).collect();
+    let mut rank = vec![0usize; n];
+    let mut head = 0;
+    while head < queue.len() {
+        let node = queue[head];
+        head += 1;
+        for &t in &dag[node] {
+            rank[t] = rank[t].max(rank[node] + 1);
+            indegree[t] -= 1;
+            if indegree[t] == 0 {
+                queue.push(t);
+            }
+        }
+    }
+
+    let depth = rank.iter().max().copied().unwrap_or(0) + 1;
+    let mut rows: Vec<Vec<usize>> = vec![Vec::new(); depth];
+    for i in 0..n {
+        rows[rank[i]].push(i);
+    }
+
+    for sweep in 0..4 {
+        let downward = sweep % 2 == 0;
+        let order: Vec<usize> = if downward {
+            (1..depth).collect()
+        } else {
+            (0..depth.saturating_sub(1)).rev().collect()
+        };
+        for r in order {
+            let reference = if downward { r - 1 } else { r + 1 };
+            let ref_pos: HashMap<usize, usize> = rows[reference]
+                .iter()
+                .enumerate()
+                .map(|(i, n)| (*n, i))
+                .collect();
+            let mut keyed: Vec<(f64, usize, usize)> = rows[r]
+                .iter()
+                .enumerate()
+                .map(|(i, &node)| {
+                    let neighbours: Vec<usize> = dag
+                        .iter()
+                        .enumerate()
+                        .flat_map(|(from, targets)| targets.iter().map(move |&to| (from, to)))
+                        .filter_map(|(from, to)| {
+                            if to == node {
+                                ref_pos.get(&from).copied()
+                            } else if from == node {
+                                ref_pos.get(&to).copied()
+                            } else {
+                                None
+                            }
+                        })
+                        .collect();
+                    let key = if neighbours.is_empty() {
+                        i as f64
+                    } else {
+                        neighbours.iter().sum::<usize>() as f64 / neighbours.len() as f64
+                    };
+                    (key, i, node)
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            rows[r] = keyed.into_iter().map(|(_, _, node)| node).collect();
+        }
+    }
+
+    rows.into_iter()
+        .map(|row| row.into_iter().map(|i| nodes[i]).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exporter::views::{ViewElement, ViewKind, ViewRelationship};
+    use crate::model::ElementType;
+
+    fn element(id: &str, element_type: ElementType) -> ViewElement {
+        ViewElement {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            technology: String::new(),
+            element_type,
+            external: false,
+            tags: Vec::new(),
+        }
+    }
+
+    fn relationship(from: &str, to: &str) -> ViewRelationship {
+        ViewRelationship {
+            from: from.to_string(),
+            to: to.to_string(),
+            description: String::new(),
+            technology: String::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_layer_chain() {
+        let rows = layer(&[0, 1, 2], &[(0, 1), (1, 2)]);
+        assert_eq!(rows, vec![vec![0], vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn test_layer_cycle() {
+        let rows = layer(&[0, 1], &[(0, 1), (1, 0)]);
+        assert_eq!(rows, vec![vec![0], vec![1]]);
+    }
+
+    #[test]
+    fn test_layout_context_view() {
+        let view = View {
+            key: "context-shop".to_string(),
+            title: "Shop".to_string(),
+            kind: ViewKind::SystemContext,
+            scope: None,
+            elements: vec![
+                element("user", ElementType::Person),
+                element("shop", ElementType::System),
+            ],
+            relationships: vec![relationship("user", "shop")],
+        };
+
+        let layout = layout_view(&view);
+        assert!(layout.boundary.is_none());
+        assert!(layout.nodes["user"].y < layout.nodes["shop"].y);
+    }
+
+    #[test]
+    fn test_layout_scoped_view() {
+        let view = View {
+            key: "containers-shop".to_string(),
+            title: "Shop".to_string(),
+            kind: ViewKind::Container,
+            scope: Some(element("shop", ElementType::System)),
+            elements: vec![
+                element("shop.web", ElementType::Container),
+                element("shop.api", ElementType::Container),
+                element("user", ElementType::Person),
+                element("payments", ElementType::System),
+            ],
+            relationships: vec![
+                relationship("user", "shop.web"),
+                relationship("shop.web", "shop.api"),
+                relationship("shop.api", "payments"),
+            ],
+        };
+
+        let layout = layout_view(&view);
+        let boundary = layout.boundary.unwrap();
+        for id in ["shop.web", "shop.api"] {
+            let node = layout.nodes[id];
+            assert!(node.y > boundary.y && node.y + node.height < boundary.y + boundary.height);
+        }
+        assert!(layout.nodes["user"].y + NODE_HEIGHT < boundary.y);
+        assert!(layout.nodes["payments"].y > boundary.y + boundary.height);
+    }
+
+    #[test]
+    fn test_rect_clip() {
+        let rect = Rect {
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 50.0,
+        };
+        assert_eq!(rect.clip(50.0, 200.0), (50.0, 50.0));
+        assert_eq!(rect.clip(200.0, 25.0), (100.0, 25.0));
+    }
+}
@@ -1,10 +1,20 @@
+pub mod d2;
+pub mod diagram;
+pub mod dot;
+pub mod drawio;
+pub mod graph;
 pub mod html;
 pub mod images;
 pub mod json;
+pub mod layout;
+pub mod mermaid;
+pub mod plantuml;
+pub mod structurizr;
+pub mod views;
 
 use crate::model;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -20,6 +30,24 @@ pub enum ExporterError {
 
     #[error("Image export error: {0}")]
     ImageExport(String),
+
+    #[error("Structurizr export error: {0}")]
+    StructurizrExport(String),
+
+    #[error("PlantUML export error: {0}")]
+    PlantumlExport(String),
+
+    #[error("Mermaid export error: {0}")]
+    MermaidExport(String),
+
+    #[error("DOT export error: {0}")]
+    DotExport(String),
+
+    #[error("D2 export error: {0}")]
+    D2Export(String),
+
+    #[error("draw.io export error: {0}")]
+    DrawioExport(String),
 }
 
 pub type Result<T> = std::result::Result<T, ExporterError>;
@@ -47,9 +75,45 @@ impl<'a> Exporter<'a> {
         json::export_json(self.model, &self.output_dir)
     }
 
-    pub fn export_images(&self) -> Result<()> {
+    pub fn export_images(&self, options: &images::ImageOptions) -> Result<Vec<PathBuf>> {
+        self.ensure_output_dir()?;
+        images::export_images(self.model, &self.output_dir, options)
+    }
+
+    /// Writes the model as a Structurizr DSL `workspace.dsl` named `name`
+    pub fn export_structurizr(&self, name: &str) -> Result<()> {
+        self.ensure_output_dir()?;
+        structurizr::export_structurizr(self.model, &self.output_dir, name)
+    }
+
+    /// Writes a C4-PlantUML diagram per view into `plantuml/`
+    pub fn export_plantuml(&self) -> Result<Vec<PathBuf>> {
+        self.ensure_output_dir()?;
+        plantuml::export_plantuml(self.model, &self.output_dir)
+    }
+
+    /// Writes a Mermaid C4 diagram per view into `mermaid/`
+    pub fn export_mermaid(&self) -> Result<Vec<PathBuf>> {
+        self.ensure_output_dir()?;
+        mermaid::export_mermaid(self.model, &self.output_dir)
+    }
+
+    /// Writes a Graphviz DOT graph per level into `dot/`
+    pub fn export_dot(&self) -> Result<Vec<PathBuf>> {
+        self.ensure_output_dir()?;
+        dot::export_dot(self.model, &self.output_dir)
+    }
+
+    /// Writes a D2 graph per level into `d2/`
+    pub fn export_d2(&self) -> Result<Vec<PathBuf>> {
+        self.ensure_output_dir()?;
+        d2::export_d2(self.model, &self.output_dir)
+    }
+
+    /// Writes every view as a page of `workspace.drawio`
+    pub fn export_drawio(&self) -> Result<PathBuf> {
         self.ensure_output_dir()?;
-        images::export_images(self.model, &self.output_dir)
+        drawio::export_drawio(self.model, &self.output_dir)
     }
 
     fn ensure_output_dir(&self) -> Result<()> {
@@ -153,8 +217,9 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let exporter = Exporter::new(&model, temp_dir.path().to_str().unwrap());
 
-        let result = exporter.export_images();
+        let result = exporter.export_images(&images::ImageOptions::new(images::ImageFormat::Svg));
         assert!(result.is_ok());
+        assert!(temp_dir.path().join("images/context-app.svg").exists());
     }
 
     #[test]
@@ -189,7 +254,9 @@ mod tests {
         let exporter = Exporter::new(&model, nested_dir.to_str().unwrap());
 
         assert!(!nested_dir.exists());
-        exporter.export_images().unwrap();
+        exporter
+            .export_images(&images::ImageOptions::new(images::ImageFormat::Svg))
+            .unwrap();
         assert!(nested_dir.exists());
     }
 }
@@ -0,0 +1,483 @@
+use crate::exporter::diagram::{args, quote, Aliases};
+use crate::exporter::views::{
+    self, build_views, instance_relationships, lookup, technology_label, View, ViewElement,
+    ViewKind,
+};
+use crate::exporter::{ExporterError, Result};
+use crate::model::{self, DeploymentNode, ElementType};
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Writes every view of the model as a C4-PlantUML diagram into
+/// `<output_dir>/plantuml/<view>.puml` and returns the written paths
+pub fn export_plantuml(model: &model::Model, output_dir: &str) -> Result<Vec<PathBuf>> {
+    let dir = Path::new(output_dir).join("plantuml");
+    fs::create_dir_all(&dir).map_err(|e| {
+        ExporterError::PlantumlExport(format!("Failed to create plantuml directory: {}", e))
+    })?;
+
+    let mut written = Vec::new();
+    for (key, puml) in render_diagrams(model) {
+        let path = dir.join(format!("{}.puml", key));
+        fs::write(&path, puml).map_err(|e| {
+            ExporterError::PlantumlExport(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Renders the system context, container and component views followed by a
+/// deployment diagram per deployment and a dynamic diagram per flow, each
+/// paired with its view key
+pub fn render_diagrams(model: &model::Model) -> Vec<(String, String)> {
+    let mut diagrams: Vec<(String, String)> = build_views(model)
+        .iter()
+        .map(|view| (view.key.clone(), render_view(view)))
+        .collect();
+
+    for (deployment, key) in model.deployments.iter().zip(views::deployment_keys(model)) {
+        let puml = render_deployment(model, deployment, &key);
+        diagrams.push((key, puml));
+    }
+
+    for (flow, key) in model.flows.iter().zip(views::flow_keys(model)) {
+        let puml = render_flow(model, flow, &key);
+        diagrams.push((key, puml));
+    }
+
+    diagrams
+}
+
+/// Renders a system context, container or component view
+pub fn render_view(view: &View) -> String {
+    let library = match view.kind {
+        ViewKind::SystemContext => "C4_Context",
+        ViewKind::Container => "C4_Container",
+        ViewKind::Component => "C4_Component",
+    };
+    let mut w = PumlWriter::new(&view.key, library, &view.title);
+
+    for element in view.elements.iter().filter(|e| !view.in_scope(e)) {
+        w.element(element);
+    }
+
+    if let Some(scope) = &view.scope {
+        let boundary = match view.kind {
+            ViewKind::Component => "Container_Boundary",
+            _ => "System_Boundary",
+        };
+        let scope_alias = w.alias(&scope.id);
+        w.open(&format!(
+            "{}({}, {})",
+            boundary,
+            scope_alias,
+            quote(&scope.name)
+        ));
+        for element in view.elements.iter().filter(|e| view.in_scope(e)) {
+            w.element(element);
+        }
+        w.close();
+    }
+
+    if !view.relationships.is_empty() {
+        w.blank();
+    }
+    for rel in &view.relationships {
+        let (from, to) = (w.alias(&rel.from), w.alias(&rel.to));
+        w.rel(&from, &to, &rel.description, &rel.technology);
+    }
+
+    w.finish()
+}
+
+/// Renders a deployment as nested deployment nodes holding container
+/// instances, with the relationships between the deployed containers
+pub fn render_deployment(
+    model: &model::Model,
+    deployment: &model::Deployment,
+    key: &str,
+) -> String {
+    let mut w = PumlWriter::new(
+        key,
+        "C4_Deployment",
+        &format!("Deployment: {}", deployment.name),
+    );
+
+    // Container path and alias of every instance, in node order
+    let mut instances: Vec<(String, String)> = Vec::new();
+    for node in deployment.nodes.as_deref().unwrap_or_default() {
+        write_node(&mut w, model, node, &deployment.id, &mut instances);
+    }
+
+    let rels = instance_relationships(model, &instances);
+    if !rels.is_empty() {
+        w.blank();
+    }
+    for (from, to, rel) in rels {
+        w.rel(
+            &from,
+            &to,
+            rel.description.as_deref().unwrap_or_default(),
+            &technology_label(rel.technology.as_ref()),
+        );
+    }
+
+    w.finish()
+}
+
+fn write_node(
+    w: &mut PumlWriter,
+    model: &model::Model,
+    node: &DeploymentNode,
+    parent: &str,
+    instances: &mut Vec<(String, String)>,
+) {
+    let node_path = format!("{}.{}", parent, node.id);
+    let node_alias = w.alias(&node_path);
+    w.open(&format!(
+        "Deployment_Node({}, {})",
+        node_alias,
+        args(&[&node.name, &technology_label(node.technology.as_ref())])
+    ));
+
+    for child in node.children.as_deref().unwrap_or_default() {
+        write_node(w, model, child, &node_path, instances);
+    }
+
+    for instance in node.instances.as_deref().unwrap_or_default() {
+        let Some(mut element) = lookup(model, &instance.container) else {
+            continue;
+        };
+        element.id = format!("{}.{}", node_path, instance.container);
+        if let Some(replicas) = instance.replicas {
+            w.line(&format!(
+                "AddProperty({}, {})",
+                quote("replicas"),
+                quote(&replicas.to_string())
+            ));
+        }
+        let element_alias = w.alias(&element.id);
+        instances.push((instance.container.clone(), element_alias));
+        w.element(&element);
+    }
+
+    w.close();
+}
+
+/// Renders a flow as a dynamic diagram with its steps in sequence order
+pub fn render_flow(model: &model::Model, flow: &model::Flow, key: &str) -> String {
+    let mut w = PumlWriter::new(key, "C4_Dynamic", &flow.name);
+
+    let mut steps: Vec<&model::FlowStep> = flow.steps.iter().collect();
+    steps.sort_by_key(|s| s.seq);
+
+    let mut drawn = HashSet::new();
+    for step in &steps {
+        for end in [&step.from, &step.to] {
+            if drawn.insert(end.as_str()) {
+                if let Some(element) = lookup(model, end) {
+                    w.element(&element);
+                }
+            }
+        }
+    }
+
+    if !steps.is_empty() {
+        w.blank();
+    }
+    for step in steps {
+        let (from, to) = (w.alias(&step.from), w.alias(&step.to));
+        w.rel(
+            &from,
+            &to,
+            step.description.as_deref().unwrap_or_default(),
+            &technology_label(step.technology.as_ref()),
+        );
+    }
+
+    w.finish()
+}
+
+/// The C4-PlantUML macro for an element
+fn macro_name(element: &ViewElement) -> &'static str {
+    match (element.element_type, element.external) {
+        (ElementType::Person, false) => "Person",
+        (ElementType::Person, true) => "Person_Ext",
+        (ElementType::System, false) => "System",
+        (ElementType::System, true) => "System_Ext",
+        (ElementType::Container, false) => "Container",
+        (ElementType::Container, true) => "Container_Ext",
+        (ElementType::Component, false) => "Component",
+        (ElementType::Component, true) => "Component_Ext",
+    }
+}
+
+/// Accumulates the lines of one diagram
+struct PumlWriter {
+    out: String,
+    depth: usize,
+    aliases: Aliases,
+}
+
+impl PumlWriter {
+    fn new(key: &str, library: &str, title: &str) -> Self {
+        let mut w = Self {
+            out: String::new(),
+            depth: 0,
+            aliases: Aliases::default(),
+        };
+        w.line(&format!("@startuml {}", key));
+        w.line(&format!("!include <C4/{}>", library));
+        w.blank();
+        w.line(&format!("title {}", title));
+        w.blank();
+        w
+    }
+
+    fn line(&mut self, text: &str) {
+        let _ = writeln!(self.out, "{}{}", "    ".repeat(self.depth), text);
+    }
+
+    fn blank(&mut self) {
+        self.out.push('\n');
+    }
+
+    fn open(&mut self, text: &str) {
+        self.line(&format!("{} {{", text));
+        self.depth += 1;
+    }
+
+    fn close(&mut self) {
+        self.depth -= 1;
+        self.line("}");
+    }
+
+    fn alias(&mut self, path: &str) -> String {
+        self.aliases.get(path)
+    }
+
+    fn element(&mut self, element: &ViewElement) {
+        let values = match element.element_type {
+            ElementType::Container | ElementType::Component => vec![
+                element.name.as_str(),
+                element.technology.as_str(),
+                element.description.as_str(),
+            ],
+            _ => vec![element.name.as_str(), element.description.as_str()],
+        };
+        let alias = self.alias(&element.id);
+        self.line(&format!(
+            "{}({}, {})",
+            macro_name(element),
+            alias,
+            args(&values)
+        ));
+    }
+
+    fn rel(&mut self, from: &str, to: &str, description: &str, technology: &str) {
+        self.line(&format!(
+            "Rel({}, {}, {})",
+            from,
+            to,
+            args(&[description, technology])
+        ));
+    }
+
+    fn finish(mut self) -> String {
+        self.blank();
+        self.line("@enduml");
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DataFile;
+    use tempfile::TempDir;
+
+    fn create_test_model() -> model::Model {
+        let data: DataFile = serde_yaml::from_str(
+            r#"
+persons:
+  - id: customer
+    name: Customer
+    description: Buys things
+systems:
+  - id: shop
+    name: Shop
+  - id: payments
+    name: "Payments \"PSP\""
+    external: true
+containers:
+  - id: web
+    name: Web
+    systemId: shop
+  - id: api
+    name: API
+    systemId: shop
+    technology: [Rust, Axum]
+    description: Serves orders
+components:
+  - id: checkout
+    name: Checkout
+    systemId: shop
+    containerId: api
+relationships:
+  - from: customer
+    to: shop.web
+    description: Places orders
+    technology: HTTPS
+  - from: shop.web
+    to: shop.api.checkout
+    description: Submits orders
+  - from: shop.api.checkout
+    to: payments
+flows:
+  - id: order
+    name: Place order
+    steps:
+      - seq: 2
+        from: shop.web
+        to: shop.api
+        description: Submits order
+      - seq: 1
+        from: customer
+        to: shop.web
+deployments:
+  - id: prod
+    name: Production
+    nodes:
+      - id: aws
+        name: AWS
+        children:
+          - id: eks
+            name: EKS
+            technology: Kubernetes
+            instances:
+              - container: shop.web
+              - container: shop.api
+                replicas: 3
+"#,
+        )
+        .unwrap();
+
+        let mut m = model::Model::new();
+        m.persons = data.persons;
+        m.systems = data.systems;
+        m.containers = data.containers;
+        m.components = data.components;
+        m.relationships = data.relationships;
+        m.flows = data.flows;
+        m.deployments = data.deployments;
+        m.build_indexes().unwrap();
+        m
+    }
+
+    fn diagram(model: &model::Model, key: &str) -> String {
+        render_diagrams(model)
+            .into_iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, puml)| puml)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_render_diagram_per_view() {
+        let keys: Vec<String> = render_diagrams(&create_test_model())
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "context-shop",
+                "containers-shop",
+                "components-shop-api",
+                "deployment-prod",
+                "flow-order"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_context_view() {
+        let puml = diagram(&create_test_model(), "context-shop");
+
+        assert!(puml.starts_with(
+            "@startuml context-shop\n!include <C4/C4_Context>\n\ntitle System Context: Shop\n"
+        ));
+        assert!(puml.contains("Person(customer, \"Customer\", \"Buys things\")\n"));
+        assert!(puml.contains("System(shop, \"Shop\")\n"));
+        assert!(puml.contains("System_Ext(payments, \"Payments 'PSP'\")\n"));
+        assert!(puml.contains("Rel(customer, shop, \"Places orders\", \"HTTPS\")\n"));
+        assert!(puml.ends_with("\n@enduml\n"));
+    }
+
+    #[test]
+    fn test_render_container_view_with_boundary() {
+        let puml = diagram(&create_test_model(), "containers-shop");
+
+        assert!(puml.contains("!include <C4/C4_Container>\n"));
+        assert!(puml.contains(
+            "System_Boundary(shop, \"Shop\") {\n    Container(shop_web, \"Web\")\n    \
+             Container(shop_api, \"API\", \"Rust, Axum\", \"Serves orders\")\n}\n"
+        ));
+        assert!(puml.contains("Rel(shop_web, shop_api, \"Submits orders\")\n"));
+        assert!(puml.contains("Rel(shop_api, payments, \"\")\n"));
+    }
+
+    #[test]
+    fn test_render_component_view() {
+        let puml = diagram(&create_test_model(), "components-shop-api");
+
+        assert!(puml.contains("!include <C4/C4_Component>\n"));
+        assert!(puml.contains(
+            "Container_Boundary(shop_api, \"API\") {\n    Component(shop_api_checkout, \"Checkout\")\n}\n"
+        ));
+        assert!(puml.contains("Container(shop_web, \"Web\")\n"));
+    }
+
+    #[test]
+    fn test_render_deployment() {
+        let puml = diagram(&create_test_model(), "deployment-prod");
+
+        assert!(puml.contains("!include <C4/C4_Deployment>\n"));
+        assert!(puml.contains(
+            "Deployment_Node(prod_aws, \"AWS\") {\n    \
+             Deployment_Node(prod_aws_eks, \"EKS\", \"Kubernetes\") {\n        \
+             Container(prod_aws_eks_shop_web, \"Web\")\n        \
+             AddProperty(\"replicas\", \"3\")\n        \
+             Container(prod_aws_eks_shop_api, \"API\", \"Rust, Axum\", \"Serves orders\")\n"
+        ));
+        assert!(puml
+            .contains("Rel(prod_aws_eks_shop_web, prod_aws_eks_shop_api, \"Submits orders\")\n"));
+    }
+
+    #[test]
+    fn test_render_flow_in_sequence() {
+        let puml = diagram(&create_test_model(), "flow-order");
+
+        assert!(puml.contains("!include <C4/C4_Dynamic>\n\ntitle Place order\n"));
+        let first = puml.find("Rel(customer, shop_web, \"\")").unwrap();
+        let second = puml
+            .find("Rel(shop_web, shop_api, \"Submits order\")")
+            .unwrap();
+        assert!(first < second, "steps are ordered by seq");
+    }
+
+    #[test]
+    fn test_export_plantuml_writes_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let written =
+            export_plantuml(&create_test_model(), temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(written.len(), 5);
+        assert!(temp_dir.path().join("plantuml/context-shop.puml").exists());
+        assert!(temp_dir.path().join("plantuml/flow-order.puml").exists());
+    }
+}
@@ -0,0 +1,814 @@
+use crate::exporter::diagram::Aliases;
+use crate::exporter::views::{deployment_keys, flow_keys, technology_label, unique_keys};
+use crate::exporter::{ExporterError, Result};
+use crate::model::{self, ContainerInstance, DeploymentNode, Element};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Tag added to external software systems so they can be styled
+const EXTERNAL_TAG: &str = "External";
+
+pub fn export_structurizr(model: &model::Model, output_dir: &str, name: &str) -> Result<()> {
+    let dsl = render_workspace(model, name);
+
+    let dsl_path = Path::new(output_dir).join("workspace.dsl");
+    fs::write(&dsl_path, dsl.as_bytes()).map_err(|e| {
+        ExporterError::StructurizrExport(format!("Failed to write DSL file: {}", e))
+    })?;
+
+    Ok(())
+}
+
+/// Renders the model as a Structurizr DSL workspace with a system context,
+/// container and component view per element, a dynamic view per flow and a
+/// deployment view per deployment
+pub fn render_workspace(model: &model::Model, name: &str) -> String {
+    let mut w = DslWriter::default();
+    let (refs, environments) = identifiers(model);
+
+    w.open(&format!("workspace {}", quote(name)));
+    w.line("!identifiers hierarchical");
+    w.blank();
+    w.open("model");
+    write_elements(&mut w, model, &refs);
+    write_relationships(&mut w, model, &refs);
+    write_deployments(&mut w, model, &refs, &environments);
+    w.close();
+    w.blank();
+    w.open("views");
+    write_views(&mut w, model, &refs, &environments);
+    write_styles(&mut w);
+    w.close();
+    w.close();
+
+    w.out
+}
+
+/// Maps every element path to its hierarchical DSL identifier, and lists
+/// the identifiers of the deployment environments. Identifiers only have to
+/// be unique among their siblings, so each parent numbers its own.
+fn identifiers(model: &model::Model) -> (HashMap<String, String>, Vec<String>) {
+    let mut top = Aliases::default();
+    let mut scopes: HashMap<String, Aliases> = HashMap::new();
+    let mut refs = HashMap::new();
+    for p in &model.persons {
+        refs.insert(p.get_full_path(), top.get(&p.base.id));
+    }
+    for s in &model.systems {
+        refs.insert(s.get_full_path(), top.get(&s.base.id));
+    }
+    for c in &model.containers {
+        let id = scopes
+            .entry(c.system_id.clone())
+            .or_default()
+            .get(&c.base.id);
+        let parent = &refs[&c.system_id];
+        refs.insert(c.get_full_path(), format!("{}.{}", parent, id));
+    }
+    for c in &model.components {
+        let container = format!("{}.{}", c.system_id, c.container_id);
+        let id = scopes.entry(container.clone()).or_default().get(&c.base.id);
+        let parent = &refs[&container];
+        refs.insert(c.get_full_path(), format!("{}.{}", parent, id));
+    }
+    let environments = model
+        .deployments
+        .iter()
+        .map(|d| top.get_for(&format!("deployment {}", d.id), &d.id))
+        .collect();
+    (refs, environments)
+}
+
+/// The last part of an element's hierarchical identifier
+fn local_ident<'a>(refs: &'a HashMap<String, String>, path: &str) -> &'a str {
+    let ident = &refs[path];
+    ident.rsplit('.').next().unwrap_or(ident)
+}
+
+fn write_elements(w: &mut DslWriter, model: &model::Model, refs: &HashMap<String, String>) {
+    for p in &model.persons {
+        let decl = format!(
+            "{} = person {}",
+            local_ident(refs, &p.get_full_path()),
+            args(&[p.get_name(), p.get_description(), &tags(p.get_tags())])
+        );
+        w.element(&decl, p.get_properties());
+    }
+
+    for s in &model.systems {
+        let mut system_tags = s.get_tags().to_vec();
+        if s.external.unwrap_or(false) {
+            system_tags.insert(0, EXTERNAL_TAG.to_string());
+        }
+        let decl = format!(
+            "{} = softwareSystem {}",
+            local_ident(refs, &s.get_full_path()),
+            args(&[s.get_name(), s.get_description(), &system_tags.join(",")])
+        );
+
+        let system_path = s.get_full_path();
+        let containers: Vec<&model::Container> = model
+            .containers
+            .iter()
+            .filter(|c| c.system_id == system_path)
+            .collect();
+        if containers.is_empty() {
+            w.element(&decl, s.get_properties());
+            continue;
+        }
+
+        w.open(&decl);
+        w.properties(s.get_properties());
+        for c in containers {
+            write_container(w, model, refs, c);
+        }
+        w.close();
+    }
+}
+
+fn write_container(
+    w: &mut DslWriter,
+    model: &model::Model,
+    refs: &HashMap<String, String>,
+    c: &model::Container,
+) {
+    let decl = format!(
+        "{} = container {}",
+        local_ident(refs, &c.get_full_path()),
+        args(&[
+            c.get_name(),
+            c.get_description(),
+            &technology_label(c.technology.as_ref()),
+            &tags(c.get_tags()),
+        ])
+    );
+
+    let container_path = c.get_full_path();
+    let components: Vec<&model::Component> = model
+        .components
+        .iter()
+        .filter(|comp| format!("{}.{}", comp.system_id, comp.container_id) == container_path)
+        .collect();
+    if components.is_empty() {
+        w.element(&decl, c.get_properties());
+        return;
+    }
+
+    w.open(&decl);
+    w.properties(c.get_properties());
+    for comp in components {
+        let decl = format!(
+            "{} = component {}",
+            local_ident(refs, &comp.get_full_path()),
+            args(&[
+                comp.get_name(),
+                comp.get_description(),
+                &technology_label(comp.technology.as_ref()),
+                &tags(comp.get_tags()),
+            ])
+        );
+        w.element(&decl, comp.get_properties());
+    }
+    w.close();
+}
+
+fn write_relationships(w: &mut DslWriter, model: &model::Model, refs: &HashMap<String, String>) {
+    if model.relationships.is_empty() {
+        return;
+    }
+
+    w.blank();
+    for rel in &model.relationships {
+        let (Some(from), Some(to)) = (refs.get(&rel.from), refs.get(&rel.to)) else {
+            continue;
+        };
+        // Structurizr rejects relationships between an element and its parent
+        if is_nested(&rel.from, &rel.to) {
+            continue;
+        }
+        let decl = format!(
+            "{} -> {} {}",
+            from,
+            to,
+            args(&[
+                rel.description.as_deref().unwrap_or_default(),
+                &technology_label(rel.technology.as_ref()),
+                &tags(rel.tags.as_deref().unwrap_or_default()),
+            ])
+        );
+        let properties = rel.properties.clone().unwrap_or_default();
+        w.element(decl.trim_end(), &properties);
+    }
+}
+
+fn write_deployments(
+    w: &mut DslWriter,
+    model: &model::Model,
+    refs: &HashMap<String, String>,
+    environments: &[String],
+) {
+    for (deployment, environment) in model.deployments.iter().zip(environments) {
+        w.blank();
+        w.open(&format!(
+            "{} = deploymentEnvironment {}",
+            environment,
+            quote(&deployment.name)
+        ));
+        let mut nodes = Aliases::default();
+        for node in deployment.nodes.as_deref().unwrap_or_default() {
+            write_node(w, node, refs, &mut nodes);
+        }
+        w.close();
+    }
+}
+
+fn write_node(
+    w: &mut DslWriter,
+    node: &DeploymentNode,
+    refs: &HashMap<String, String>,
+    siblings: &mut Aliases,
+) {
+    let instances: Vec<&ContainerInstance> = node
+        .instances
+        .iter()
+        .flatten()
+        .filter(|i| refs.contains_key(&i.container))
+        .collect();
+
+    // Structurizr counts instances per deployment node, so a node without
+    // children takes the replicas of its container instances when they agree
+    let has_children = node.children.as_ref().is_some_and(|c| !c.is_empty());
+    let counts: HashSet<i32> = instances.iter().map(|i| i.replicas.unwrap_or(1)).collect();
+    let count = match counts.iter().next() {
+        Some(&count) if !has_children && counts.len() == 1 => count,
+        _ => 1,
+    };
+    let count_arg = if count > 1 {
+        count.to_string()
+    } else {
+        String::new()
+    };
+
+    w.open(&format!(
+        "{} = deploymentNode {}",
+        siblings.get(&node.id),
+        args(&[
+            &node.name,
+            "",
+            &technology_label(node.technology.as_ref()),
+            "",
+            &count_arg
+        ])
+    ));
+    if let Some(properties) = &node.properties {
+        w.properties(properties);
+    }
+
+    let mut children = Aliases::default();
+    for child in node.children.as_deref().unwrap_or_default() {
+        write_node(w, child, refs, &mut children);
+    }
+
+    for instance in instances {
+        let replicas = instance.replicas.unwrap_or(1);
+        if replicas == count {
+            write_instance(w, instance, refs);
+        } else {
+            // Other replica counts need a node of their own
+            w.open(&format!(
+                "deploymentNode {}",
+                args(&[&instance.container, "", "", "", &replicas.to_string()])
+            ));
+            write_instance(w, instance, refs);
+            w.close();
+        }
+    }
+    w.close();
+}
+
+fn write_instance(w: &mut DslWriter, instance: &ContainerInstance, refs: &HashMap<String, String>) {
+    let properties = instance.properties.clone().unwrap_or_default();
+    w.element(
+        &format!("containerInstance {}", refs[&instance.container]),
+        &properties,
+    );
+}
+
+fn write_views(
+    w: &mut DslWriter,
+    model: &model::Model,
+    refs: &HashMap<String, String>,
+    environments: &[String],
+) {
+    // The same views in the same order as `build_views`, so repeated keys
+    // are numbered alike
+    let mut statics: Vec<(String, String)> = Vec::new();
+    for s in model
+        .systems
+        .iter()
+        .filter(|s| !s.external.unwrap_or(false))
+    {
+        let path = s.get_full_path();
+        statics.push((
+            format!("systemContext {}", refs[&path]),
+            view_key("context", &path),
+        ));
+
+        if model.containers.iter().any(|c| c.system_id == path) {
+            statics.push((
+                format!("container {}", refs[&path]),
+                view_key("containers", &path),
+            ));
+        }
+    }
+
+    for c in &model.containers {
+        let path = c.get_full_path();
+        if model
+            .components
+            .iter()
+            .any(|comp| format!("{}.{}", comp.system_id, comp.container_id) == path)
+        {
+            statics.push((
+                format!("component {}", refs[&path]),
+                view_key("components", &path),
+            ));
+        }
+    }
+
+    let keys = unique_keys(statics.iter().map(|(_, key)| key.clone()));
+    for ((view, _), key) in statics.iter().zip(keys) {
+        write_static_view(w, &format!("{} {}", view, quote(&key)));
+    }
+
+    for (flow, key) in model.flows.iter().zip(flow_keys(model)) {
+        let mut steps: Vec<&model::FlowStep> = flow.steps.iter().collect();
+        steps.sort_by_key(|s| s.seq);
+
+        let ends: Vec<&str> = steps
+            .iter()
+            .flat_map(|s| [s.from.as_str(), s.to.as_str()])
+            .collect();
+        let scope = dynamic_scope(model, refs, &ends);
+
+        w.open(&format!("dynamic {} {}", scope, quote(&key)));
+        w.line(&format!("title {}", quote(&flow.name)));
+        if let Some(description) = &flow.description {
+            w.line(&format!("description {}", quote(description)));
+        }
+        for step in steps {
+            let (Some(from), Some(to)) = (refs.get(&step.from), refs.get(&step.to)) else {
+                continue;
+            };
+            if !step_allowed(model, &step.from, &step.to) {
+                w.line(&format!(
+                    "// Step {} skipped: no relationship between {} and {}",
+                    step.seq, step.from, step.to
+                ));
+                continue;
+            }
+            let decl = format!(
+                "{} -> {} {}",
+                from,
+                to,
+                args(&[
+                    step.description.as_deref().unwrap_or_default(),
+                    &technology_label(step.technology.as_ref()),
+                ])
+            );
+            w.line(decl.trim_end());
+        }
+        w.line("autoLayout lr");
+        w.close();
+    }
+
+    for (environment, key) in environments.iter().zip(deployment_keys(model)) {
+        w.open(&format!("deployment * {} {}", environment, quote(&key)));
+        w.line("include *");
+        w.line("autoLayout");
+        w.close();
+    }
+}
+
+/// Whether one element contains the other
+fn is_nested(a: &str, b: &str) -> bool {
+    contains(a, b) || contains(b, a)
+}
+
+/// Whether `path` is `ancestor` or one of its descendants
+fn contains(ancestor: &str, path: &str) -> bool {
+    path == ancestor
+        || path
+            .strip_prefix(ancestor)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// Whether Structurizr accepts a dynamic view step from `from` to `to`. It
+/// needs a relationship between the two, or one it implies from their
+/// descendants, in either direction.
+fn step_allowed(model: &model::Model, from: &str, to: &str) -> bool {
+    if is_nested(from, to) {
+        return false;
+    }
+    model.relationships.iter().any(|rel| {
+        !is_nested(&rel.from, &rel.to)
+            && ((contains(from, &rel.from) && contains(to, &rel.to))
+                || (contains(to, &rel.from) && contains(from, &rel.to)))
+    })
+}
+
+fn write_static_view(w: &mut DslWriter, decl: &str) {
+    w.open(decl);
+    w.line("include *");
+    w.line("autoLayout");
+    w.close();
+}
+
+/// Picks the dynamic view scope for a flow: the container when every
+/// component in it shares one, the software system when every container in
+/// it shares one, and the whole workspace otherwise
+fn dynamic_scope(model: &model::Model, refs: &HashMap<String, String>, ends: &[&str]) -> String {
+    let components: HashSet<String> = model
+        .components
+        .iter()
+        .filter(|c| ends.contains(&c.get_full_path().as_str()))
+        .map(|c| format!("{}.{}", c.system_id, c.container_id))
+        .collect();
+    let systems: HashSet<String> = model
+        .containers
+        .iter()
+        .filter(|c| ends.contains(&c.get_full_path().as_str()))
+        .map(|c| c.system_id.clone())
+        .collect();
+
+    let parent = match (components.len(), systems.len()) {
+        (1, _) => components.into_iter().next(),
+        (0, 1) => systems.into_iter().next(),
+        _ => None,
+    };
+    parent
+        .and_then(|p| refs.get(&p).cloned())
+        .unwrap_or_else(|| "*".to_string())
+}
+
+fn write_styles(w: &mut DslWriter) {
+    w.blank();
+    w.open("styles");
+    w.open("element \"Person\"");
+    w.line("shape Person");
+    w.close();
+    w.open(&format!("element {}", quote(EXTERNAL_TAG)));
+    w.line("background #999999");
+    w.line("color #ffffff");
+    w.close();
+    w.close();
+}
+
+/// Builds a view key such as `containers-shop`, matching the keys used for
+/// the HTML and image exports
+fn view_key(prefix: &str, path: &str) -> String {
+    format!("{}-{}", prefix, crate::exporter::views::view_key(path))
+}
+
+fn quote(s: &str) -> String {
+    let s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quotes positional arguments, dropping trailing empty ones
+fn args(values: &[&str]) -> String {
+    let len = values
+        .iter()
+        .rposition(|v| !v.trim().is_empty())
+        .map_or(0, |i| i + 1);
+    values[..len]
+        .iter()
+        .map(|v| quote(v))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn tags(tags: &[String]) -> String {
+    tags.join(",")
+}
+
+/// Accumulates indented DSL lines
+#[derive(Default)]
+struct DslWriter {
+    out: String,
+    depth: usize,
+}
+
+impl DslWriter {
+    fn line(&mut self, text: &str) {
+        let _ = writeln!(self.out, "{}{}", "    ".repeat(self.depth), text);
+    }
+
+    fn blank(&mut self) {
+        self.out.push('\n');
+    }
+
+    fn open(&mut self, text: &str) {
+        self.line(&format!("{} {{", text));
+        self.depth += 1;
+    }
+
+    fn close(&mut self) {
+        self.depth -= 1;
+        self.line("}");
+    }
+
+    /// Writes a declaration, with a block only when it has properties
+    fn element(&mut self, decl: &str, properties: &HashMap<String, serde_json::Value>) {
+        if properties.is_empty() {
+            self.line(decl);
+        } else {
+            self.open(decl);
+            self.properties(properties);
+            self.close();
+        }
+    }
+
+    fn properties(&mut self, properties: &HashMap<String, serde_json::Value>) {
+        if properties.is_empty() {
+            return;
+        }
+        let mut keys: Vec<&String> = properties.keys().collect();
+        keys.sort();
+
+        self.open("properties");
+        for key in keys {
+            let value = match &properties[key] {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            self.line(&format!("{} {}", quote(key), quote(&value)));
+        }
+        self.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DataFile;
+    use tempfile::TempDir;
+
+    fn create_test_model() -> model::Model {
+        let data: DataFile = serde_yaml::from_str(
+            r#"
+persons:
+  - id: customer
+    name: Customer
+    description: Buys things
+systems:
+  - id: shop
+    name: Shop
+    tags: [core]
+  - id: payments
+    name: "Payments \"PSP\""
+    external: true
+containers:
+  - id: api
+    name: API
+    systemId: shop
+    technology: [Rust, Axum]
+    properties:
+      port: 8080
+components:
+  - id: checkout
+    name: Checkout
+    systemId: shop
+    containerId: api
+relationships:
+  - from: customer
+    to: shop.api
+    description: Places orders
+    technology: HTTPS
+  - from: shop.api.checkout
+    to: payments
+flows:
+  - id: order
+    name: Place order
+    steps:
+      - seq: 2
+        from: shop.api
+        to: payments
+        description: Charges card
+      - seq: 1
+        from: customer
+        to: shop.api
+deployments:
+  - id: prod
+    name: Production
+    nodes:
+      - id: aws
+        name: AWS
+        children:
+          - id: eks
+            name: EKS
+            technology: Kubernetes
+            instances:
+              - container: shop.api
+                replicas: 3
+"#,
+        )
+        .unwrap();
+
+        let mut m = model::Model::new();
+        m.persons = data.persons;
+        m.systems = data.systems;
+        m.containers = data.containers;
+        m.components = data.components;
+        m.relationships = data.relationships;
+        m.flows = data.flows;
+        m.deployments = data.deployments;
+        m
+    }
+
+    #[test]
+    fn test_render_elements_nested_by_parent() {
+        let dsl = render_workspace(&create_test_model(), "Example");
+
+        assert!(dsl.starts_with("workspace \"Example\" {\n    !identifiers hierarchical\n"));
+        assert!(dsl.contains("customer = person \"Customer\" \"Buys things\"\n"));
+        assert!(dsl.contains(
+            "        shop = softwareSystem \"Shop\" \"\" \"core\" {\n            \
+             api = container \"API\" \"\" \"Rust, Axum\" {\n"
+        ));
+        assert!(dsl.contains("                checkout = component \"Checkout\"\n"));
+        assert!(dsl.contains("\"port\" \"8080\""));
+        assert!(
+            dsl.contains("payments = softwareSystem \"Payments \\\"PSP\\\"\" \"\" \"External\"\n")
+        );
+    }
+
+    #[test]
+    fn test_identifiers_do_not_collide() {
+        let mut model = create_test_model();
+        for id in ["shop-api", "shop_api"] {
+            let mut system = model.systems[0].clone();
+            system.base.id = id.to_string();
+            model.systems.push(system);
+        }
+        let mut deployment = model.deployments[0].clone();
+        deployment.id = "shop".to_string();
+        model.deployments.push(deployment);
+
+        let (refs, environments) = identifiers(&model);
+
+        assert_eq!(refs["shop-api"], "shop_api");
+        assert_eq!(refs["shop_api"], "shop_api_2");
+        assert_eq!(refs["shop.api"], "shop.api");
+        assert_eq!(environments, ["prod", "shop_2"]);
+    }
+
+    #[test]
+    fn test_render_relationships() {
+        let dsl = render_workspace(&create_test_model(), "Example");
+
+        assert!(dsl.contains("customer -> shop.api \"Places orders\" \"HTTPS\"\n"));
+        assert!(dsl.contains("shop.api.checkout -> payments\n"));
+    }
+
+    #[test]
+    fn test_render_deployment_nodes_and_replicas() {
+        let dsl = render_workspace(&create_test_model(), "Example");
+
+        assert!(dsl.contains("prod = deploymentEnvironment \"Production\" {"));
+        assert!(dsl.contains("aws = deploymentNode \"AWS\" {"));
+        // Replicas become the instance count of the node
+        assert!(dsl.contains(
+            "eks = deploymentNode \"EKS\" \"\" \"Kubernetes\" \"\" \"3\" {\n                    containerInstance shop.api\n"
+        ));
+        assert!(!dsl.contains("replicas"));
+        assert!(dsl.contains("deployment * prod \"deployment-prod\" {"));
+    }
+
+    #[test]
+    fn test_render_differing_replicas_as_nested_nodes() {
+        let mut model = create_test_model();
+        let mut web = model.containers[0].clone();
+        web.base.id = "web".to_string();
+        model.containers.push(web);
+        let eks = &mut model.deployments[0].nodes.as_mut().unwrap()[0]
+            .children
+            .as_mut()
+            .unwrap()[0];
+        let mut instance = eks.instances.as_ref().unwrap()[0].clone();
+        instance.container = "shop.web".to_string();
+        instance.replicas = None;
+        eks.instances.as_mut().unwrap().push(instance);
+
+        let dsl = render_workspace(&model, "Example");
+
+        assert!(dsl.contains(
+            "eks = deploymentNode \"EKS\" \"\" \"Kubernetes\" {\n                    \
+             deploymentNode \"shop.api\" \"\" \"\" \"\" \"3\" {\n                        \
+             containerInstance shop.api\n                    }\n                    \
+             containerInstance shop.web\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_skips_relationships_within_an_element() {
+        let mut model = create_test_model();
+        let mut rel = model.relationships[0].clone();
+        rel.from = "shop.api".to_string();
+        rel.to = "shop.api.checkout".to_string();
+        model.relationships.push(rel);
+
+        let dsl = render_workspace(&model, "Example");
+
+        assert!(!dsl.contains("shop.api -> shop.api.checkout"));
+    }
+
+    #[test]
+    fn test_render_skips_flow_steps_without_relationship() {
+        let mut model = create_test_model();
+        let mut step = model.flows[0].steps[0].clone();
+        step.seq = 3;
+        step.from = "customer".to_string();
+        step.to = "payments".to_string();
+        model.flows[0].steps.push(step);
+        // A relationship in the other direction is allowed as a response
+        let mut step = model.flows[0].steps[0].clone();
+        step.seq = 4;
+        step.from = "payments".to_string();
+        step.to = "shop".to_string();
+        model.flows[0].steps.push(step);
+
+        let dsl = render_workspace(&model, "Example");
+
+        assert!(dsl.contains("// Step 3 skipped: no relationship between customer and payments\n"));
+        assert!(!dsl.contains("customer -> payments"));
+        assert!(dsl.contains("payments -> shop \"Charges card\"\n"));
+    }
+
+    #[test]
+    fn test_render_views() {
+        let dsl = render_workspace(&create_test_model(), "Example");
+
+        assert!(dsl.contains("systemContext shop \"context-shop\" {"));
+        assert!(dsl.contains("container shop \"containers-shop\" {"));
+        assert!(dsl.contains("component shop.api \"components-shop-api\" {"));
+        // External systems get no views of their own
+        assert!(!dsl.contains("systemContext payments"));
+    }
+
+    #[test]
+    fn test_render_flow_as_dynamic_view() {
+        let dsl = render_workspace(&create_test_model(), "Example");
+
+        let start = dsl
+            .find("dynamic shop \"flow-order\" {\n            title \"Place order\"\n")
+            .unwrap();
+        let view = &dsl[start..];
+        let first = view.find("customer -> shop.api").unwrap();
+        let second = view.find("shop.api -> payments \"Charges card\"").unwrap();
+        assert!(first < second, "steps are ordered by seq");
+    }
+
+    #[test]
+    fn test_dynamic_scope() {
+        let model = create_test_model();
+        let (refs, _) = identifiers(&model);
+
+        assert_eq!(dynamic_scope(&model, &refs, &["customer", "payments"]), "*");
+        assert_eq!(
+            dynamic_scope(&model, &refs, &["customer", "shop.api"]),
+            "shop"
+        );
+        assert_eq!(
+            dynamic_scope(&model, &refs, &["shop.api.checkout", "payments"]),
+            "shop.api"
+        );
+
+        let mut model = model;
+        let mut other = model.containers[0].clone();
+        other.system_id = "payments".to_string();
+        model.containers.push(other);
+        let (refs, _) = identifiers(&model);
+        assert_eq!(
+            dynamic_scope(&model, &refs, &["shop.api", "payments.api"]),
+            "*"
+        );
+    }
+
+    #[test]
+    fn test_export_structurizr_writes_file() {
+        let temp_dir = TempDir::new().unwrap();
+        export_structurizr(
+            &create_test_model(),
+            temp_dir.path().to_str().unwrap(),
+            "Example",
+        )
+        .unwrap();
+
+        let dsl = fs::read_to_string(temp_dir.path().join("workspace.dsl")).unwrap();
+        assert!(dsl.ends_with("}\n"));
+        assert_eq!(dsl.matches('{').count(), dsl.matches('}').count());
+    }
+}
@@ -0,0 +1,575 @@
+use crate::model::{self, Element, ElementType, Technology};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewKind {
+    SystemContext,
+    Container,
+    Component,
+}
+
+#[derive(Debug, Clone)]
+pub struct ViewElement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub technology: String,
+    pub element_type: ElementType,
+    pub external: bool,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ViewRelationship {
+    pub from: String,
+    pub to: String,
+    pub description: String,
+    pub technology: String,
+    pub tags: Vec<String>,
+}
+
+/// A single diagram derived from the model
+#[derive(Debug, Clone)]
+pub struct View {
+    pub key: String,
+    pub title: String,
+    pub kind: ViewKind,
+    /// Element drawn as a boundary around its children (container and component views)
+    pub scope: Option<ViewElement>,
+    pub elements: Vec<ViewElement>,
+    pub relationships: Vec<ViewRelationship>,
+}
+
+impl View {
+    /// Returns true if the element is drawn inside the view's boundary
+    pub fn in_scope(&self, element: &ViewElement) -> bool {
+        match &self.scope {
+            Some(scope) => element.id.starts_with(&format!("{}.", scope.id)),
+            None => false,
+        }
+    }
+
+    pub fn get_element(&self, id: &str) -> Option<&ViewElement> {
+        self.elements.iter().find(|e| e.id == id)
+    }
+}
+
+/// Builds the system context, container and component views for every
+/// internal system in the model
+pub fn build_views(model: &model::Model) -> Vec<View> {
+    let mut views = Vec::new();
+
+    for system in model
+        .systems
+        .iter()
+        .filter(|s| !s.external.unwrap_or(false))
+    {
+        views.push(system_context_view(model, &system.base.id));
+
+        if model
+            .containers
+            .iter()
+            .any(|c| c.system_id == system.base.id)
+        {
+            views.push(container_view(model, &system.base.id));
+        }
+    }
+
+    for container in &model.containers {
+        let path = container.get_full_path();
+        if model
+            .components
+            .iter()
+            .any(|c| format!("{}.{}", c.system_id, c.container_id) == path)
+        {
+            views.push(component_view(model, &path));
+        }
+    }
+
+    let keys = unique_keys(views.iter().map(|v| v.key.clone()));
+    for (view, key) in views.iter_mut().zip(keys) {
+        view.key = key;
+    }
+    views
+}
+
+/// Builds the context view of a system with all relationships rolled up to
+/// the top level
+pub fn system_context_view(model: &model::Model, system_id: &str) -> View {
+    let mut view = View {
+        key: format!("context-{}", view_key(system_id)),
+        title: String::new(),
+        kind: ViewKind::SystemContext,
+        scope: None,
+        elements: Vec::new(),
+        relationships: Vec::new(),
+    };
+
+    let focus = lookup(model, system_id);
+    view.title = format!(
+        "System Context: {}",
+        focus.as_ref().map(|e| e.name.as_str()).unwrap_or(system_id)
+    );
+    if let Some(focus) = focus {
+        view.elements.push(focus);
+    }
+
+    let rels = project_relationships(model, |path| Some(truncate(path, 1)))
+        .into_iter()
+        .filter(|r| r.from == system_id || r.to == system_id);
+    add_relationships(model, &mut view, rels);
+
+    view
+}
+
+/// Builds the container view of a system. Elements outside the system are
+/// shown at the top level.
+pub fn container_view(model: &model::Model, system_id: &str) -> View {
+    let scope = lookup(model, system_id);
+    let mut view = View {
+        key: format!("containers-{}", view_key(system_id)),
+        title: format!(
+            "Containers: {}",
+            scope.as_ref().map(|e| e.name.as_str()).unwrap_or(system_id)
+        ),
+        kind: ViewKind::Container,
+        scope,
+        elements: Vec::new(),
+        relationships: Vec::new(),
+    };
+
+    for container in model.containers.iter().filter(|c| c.system_id == system_id) {
+        if let Some(e) = lookup(model, &container.get_full_path()) {
+            view.elements.push(e);
+        }
+    }
+
+    add_scoped_relationships(model, &mut view, system_id);
+    view
+}
+
+/// Builds the component view of a container. Sibling containers are shown
+/// as containers, everything else at the top level.
+pub fn component_view(model: &model::Model, container_path: &str) -> View {
+    let scope = lookup(model, container_path);
+    let mut view = View {
+        key: format!("components-{}", view_key(container_path)),
+        title: format!(
+            "Components: {}",
+            scope
+                .as_ref()
+                .map(|e| e.name.as_str())
+                .unwrap_or(container_path)
+        ),
+        kind: ViewKind::Component,
+        scope,
+        elements: Vec::new(),
+        relationships: Vec::new(),
+    };
+
+    for component in &model.components {
+        if format!("{}.{}", component.system_id, component.container_id) == container_path {
+            if let Some(e) = lookup(model, &component.get_full_path()) {
+                view.elements.push(e);
+            }
+        }
+    }
+
+    add_scoped_relationships(model, &mut view, container_path);
+    view
+}
+
+fn add_scoped_relationships(model: &model::Model, view: &mut View, scope: &str) {
+    let prefix = format!("{}.", scope);
+    let rels = project_relationships(model, |path| project(path, scope))
+        .into_iter()
+        .filter(|r| r.from.starts_with(&prefix) || r.to.starts_with(&prefix));
+    add_relationships(model, view, rels);
+}
+
+fn add_relationships(
+    model: &model::Model,
+    view: &mut View,
+    rels: impl Iterator<Item = ViewRelationship>,
+) {
+    for rel in rels {
+        for end in [&rel.from, &rel.to] {
+            if view.get_element(end).is_none() {
+                if let Some(e) = lookup(model, end) {
+                    view.elements.push(e);
+                }
+            }
+        }
+        if view.get_element(&rel.from).is_some() && view.get_element(&rel.to).is_some() {
+            view.relationships.push(rel);
+        }
+    }
+}
+
+/// Relationships between deployed container instances, given as
+/// `(container path, instance id)` pairs. Relationships of components count
+/// as relationships of their container, and each pair of containers is
+/// connected once per pair of instances.
+pub fn instance_relationships<'a>(
+    model: &'a model::Model,
+    instances: &[(String, String)],
+) -> Vec<(String, String, &'a model::Relationship)> {
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+
+    for rel in &model.relationships {
+        let (from, to) = (truncate(&rel.from, 2), truncate(&rel.to, 2));
+        if from == to || !seen.insert((from.clone(), to.clone())) {
+            continue;
+        }
+        for (_, from_id) in instances.iter().filter(|(c, _)| *c == from) {
+            for (_, to_id) in instances.iter().filter(|(c, _)| *c == to) {
+                result.push((from_id.clone(), to_id.clone(), rel));
+            }
+        }
+    }
+
+    result
+}
+
+/// Maps every relationship end through `map`, dropping self references and
+/// duplicates created by the projection
+pub fn project_relationships<F>(model: &model::Model, map: F) -> Vec<ViewRelationship>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+
+    for rel in &model.relationships {
+        let (Some(from), Some(to)) = (map(&rel.from), map(&rel.to)) else {
+            continue;
+        };
+        if from == to || !seen.insert((from.clone(), to.clone())) {
+            continue;
+        }
+        result.push(ViewRelationship {
+            from,
+            to,
+            description: rel.description.clone().unwrap_or_default(),
+            technology: technology_label(rel.technology.as_ref()),
+            tags: rel.tags.clone().unwrap_or_default(),
+        });
+    }
+
+    result
+}
+
+/// Projects an element path onto the level shown around `scope`: children of
+/// the scope stay one level below it, everything else is cut to the level it
+/// shares with the scope. Returns None for the scope and its ancestors.
+fn project(path: &str, scope: &str) -> Option<String> {
+    let segs: Vec<&str> = path.split('.').collect();
+    let scope_segs: Vec<&str> = scope.split('.').collect();
+    let common = segs
+        .iter()
+        .zip(&scope_segs)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    if common == segs.len() {
+        return None;
+    }
+    Some(segs[..=common].join("."))
+}
+
+/// The first `depth` segments of an element path
+pub fn truncate(path: &str, depth: usize) -> String {
+    path.split('.').take(depth).collect::<Vec<_>>().join(".")
+}
+
+/// Converts an element path into a string usable in file names. Different
+/// paths can convert alike, so keys pass through `unique_keys`.
+pub fn view_key(path: &str) -> String {
+    path.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Numbers repeated keys `-2`, `-3` and so on in order, since different
+/// paths such as `a.b` and `a-b` convert to the same key
+pub fn unique_keys<I: IntoIterator<Item = String>>(keys: I) -> Vec<String> {
+    let mut used = HashSet::new();
+    keys.into_iter()
+        .map(|key| {
+            let mut unique = key.clone();
+            let mut n = 1;
+            while !used.insert(unique.clone()) {
+                n += 1;
+                unique = format!("{}-{}", key, n);
+            }
+            unique
+        })
+        .collect()
+}
+
+/// Keys of the deployment diagrams, in model order
+pub fn deployment_keys(model: &model::Model) -> Vec<String> {
+    unique_keys(
+        model
+            .deployments
+            .iter()
+            .map(|d| format!("deployment-{}", view_key(&d.id))),
+    )
+}
+
+/// Keys of the flow diagrams, in model order
+pub fn flow_keys(model: &model::Model) -> Vec<String> {
+    unique_keys(
+        model
+            .flows
+            .iter()
+            .map(|f| format!("flow-{}", view_key(&f.id))),
+    )
+}
+
+pub fn technology_label(tech: Option<&Technology>) -> String {
+    tech.map(|t| t.as_slice().join(", ")).unwrap_or_default()
+}
+
+/// Looks up an element by full path and flattens it for rendering
+pub fn lookup(model: &model::Model, path: &str) -> Option<ViewElement> {
+    if let Some(p) = model.persons.iter().find(|p| p.base.id == path) {
+        return Some(ViewElement {
+            id: path.to_string(),
+            name: p.get_name().to_string(),
+            description: p.get_description().to_string(),
+            technology: String::new(),
+            element_type: ElementType::Person,
+            external: p.get_tags().iter().any(|t| t == "external"),
+            tags: p.get_tags().to_vec(),
+        });
+    }
+    if let Some(s) = model.systems.iter().find(|s| s.base.id == path) {
+        return Some(ViewElement {
+            id: path.to_string(),
+            name: s.get_name().to_string(),
+            description: s.get_description().to_string(),
+            technology: String::new(),
+            element_type: ElementType::System,
+            external: s.external.unwrap_or(false),
+            tags: s.get_tags().to_vec(),
+        });
+    }
+    if let Some(c) = model.containers.iter().find(|c| c.get_full_path() == path) {
+        return Some(ViewElement {
+            id: path.to_string(),
+            name: c.get_name().to_string(),
+            description: c.get_description().to_string(),
+            technology: technology_label(c.technology.as_ref()),
+            element_type: ElementType::Container,
+            external: false,
+            tags: c.get_tags().to_vec(),
+        });
+    }
+    if let Some(c) = model.components.iter().find(|c| c.get_full_path() == path) {
+        return Some(ViewElement {
+            id: path.to_string(),
+            name: c.get_name().to_string(),
+            description: c.get_description().to_string(),
+            technology: technology_label(c.technology.as_ref()),
+            element_type: ElementType::Component,
+            external: false,
+            tags: c.get_tags().to_vec(),
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{BaseElement, Component, Container, Person, Relationship, SoftwareSystem};
+
+    fn base(id: &str) -> BaseElement {
+        BaseElement {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            tags: None,
+            properties: None,
+        }
+    }
+
+    fn rel(from: &str, to: &str) -> Relationship {
+        Relationship {
+            from: from.to_string(),
+            to: to.to_string(),
+            description: Some(format!("{} calls {}", from, to)),
+            technology: None,
+            tags: None,
+            properties: None,
+            implied: false,
+        }
+    }
+
+    fn create_model() -> model::Model {
+        let mut m = model::Model::new();
+        m.persons.push(Person {
+            base: base("user"),
+            element_type: ElementType::Person,
+        });
+        for (id, external) in [("shop", false), ("payments", true)] {
+            m.systems.push(SoftwareSystem {
+                base: base(id),
+                element_type: ElementType::System,
+                external: Some(external),
+            });
+        }
+        for id in ["web", "api"] {
+            m.containers.push(Container {
+                base: base(id),
+                element_type: ElementType::Container,
+                technology: None,
+                system_id: "shop".to_string(),
+            });
+        }
+        m.components.push(Component {
+            base: base("handler"),
+            element_type: ElementType::Component,
+            technology: None,
+            system_id: "shop".to_string(),
+            container_id: "api".to_string(),
+        });
+        m.relationships.push(rel("user", "shop.web"));
+        m.relationships.push(rel("shop.web", "shop.api"));
+        m.relationships.push(rel("shop.api.handler", "payments"));
+        m.build_indexes().unwrap();
+        m
+    }
+
+    #[test]
+    fn test_build_views() {
+        let model = create_model();
+        let keys: Vec<String> = build_views(&model).into_iter().map(|v| v.key).collect();
+        assert_eq!(
+            keys,
+            vec!["context-shop", "containers-shop", "components-shop-api"]
+        );
+    }
+
+    #[test]
+    fn test_system_context_rolls_up_relationships() {
+        let model = create_model();
+        let view = system_context_view(&model, "shop");
+
+        let ids: Vec<&str> = view.elements.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["shop", "user", "payments"]);
+        assert_eq!(view.relationships.len(), 2);
+        assert!(view
+            .relationships
+            .iter()
+            .any(|r| r.from == "shop" && r.to == "payments"));
+    }
+
+    #[test]
+    fn test_container_view() {
+        let model = create_model();
+        let view = container_view(&model, "shop");
+
+        assert_eq!(view.scope.as_ref().unwrap().id, "shop");
+        assert!(view.in_scope(view.get_element("shop.web").unwrap()));
+        assert!(!view.in_scope(view.get_element("user").unwrap()));
+        assert!(view
+            .relationships
+            .iter()
+            .any(|r| r.from == "shop.api" && r.to == "payments"));
+    }
+
+    #[test]
+    fn test_component_view() {
+        let model = create_model();
+        let view = component_view(&model, "shop.api");
+
+        let ids: Vec<&str> = view.elements.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["shop.api.handler", "payments"]);
+        assert_eq!(view.relationships.len(), 1);
+    }
+
+    #[test]
+    fn test_instance_relationships() {
+        let model = create_model();
+        let instances = vec![
+            ("shop.web".to_string(), "a-web".to_string()),
+            ("shop.api".to_string(), "a-api".to_string()),
+            ("shop.api".to_string(), "b-api".to_string()),
+        ];
+
+        let rels: Vec<(String, String)> = instance_relationships(&model, &instances)
+            .into_iter()
+            .map(|(from, to, _)| (from, to))
+            .collect();
+        assert_eq!(
+            rels,
+            vec![
+                ("a-web".to_string(), "a-api".to_string()),
+                ("a-web".to_string(), "b-api".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_project() {
+        assert_eq!(project("shop.api.handler", "shop"), Some("shop.api".into()));
+        assert_eq!(project("user", "shop"), Some("user".into()));
+        assert_eq!(project("shop.web", "shop.api"), Some("shop.web".into()));
+        assert_eq!(project("shop", "shop.api"), None);
+        assert_eq!(project("shop.api", "shop.api"), None);
+    }
+
+    #[test]
+    fn test_view_key() {
+        assert_eq!(view_key("shop.api"), "shop-api");
+        assert_eq!(view_key("platform/auth"), "platform-auth");
+    }
+
+    #[test]
+    fn test_unique_keys() {
+        let keys = unique_keys(["a-b", "a-b", "a-b-2", "c"].map(String::from));
+        assert_eq!(keys, vec!["a-b", "a-b-2", "a-b-2-2", "c"]);
+    }
+
+    #[test]
+    fn test_build_views_keys_do_not_collide() {
+        let mut model = create_model();
+        for (system, container) in [("a", "b-c"), ("a-b", "c")] {
+            model.systems.push(SoftwareSystem {
+                base: base(system),
+                element_type: ElementType::System,
+                external: Some(false),
+            });
+            model.containers.push(Container {
+                base: base(container),
+                element_type: ElementType::Container,
+                technology: None,
+                system_id: system.to_string(),
+            });
+            model.components.push(Component {
+                base: base("handler"),
+                element_type: ElementType::Component,
+                technology: None,
+                system_id: system.to_string(),
+                container_id: container.to_string(),
+            });
+        }
+        model.build_indexes().unwrap();
+
+        let keys: Vec<String> = build_views(&model)
+            .into_iter()
+            .map(|v| v.key)
+            .filter(|k| k.starts_with("components-a"))
+            .collect();
+        assert_eq!(keys, vec!["components-a-b-c", "components-a-b-c-2"]);
+    }
+}
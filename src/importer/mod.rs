@@ -0,0 +1,428 @@
+pub mod structurizr;
+
+use crate::model::{
+    BaseElement, ContainerInstance, Deployment, DeploymentNode, Flow, Relationship, Technology,
+};
+use crate::parser::DataFile;
+use serde_yaml::{Mapping, Value};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ImporterError {
+    #[error("Failed to read {0}: {1}")]
+    Read(String, std::io::Error),
+
+    #[error("Structurizr import error: {0}")]
+    StructurizrImport(String),
+}
+
+pub type Result<T> = std::result::Result<T, ImporterError>;
+
+/// A model imported from another tool, with notes on everything that could
+/// not be carried over
+#[derive(Debug, Default)]
+pub struct ImportedWorkspace {
+    pub name: String,
+    pub data: DataFile,
+    /// System each flow was scoped to, keyed by flow ID
+    pub flow_systems: HashMap<String, String>,
+    pub warnings: Vec<String>,
+}
+
+impl ImportedWorkspace {
+    /// Returns the files of the workspace in the layout `c4 init` creates,
+    /// as paths relative to the workspace root and their YAML content. The
+    /// mod file is not included.
+    pub fn files(&self) -> Vec<(String, String)> {
+        let data = &self.data;
+        let mut files = Vec::new();
+
+        // Systems get a directory when they are internal or have containers
+        let has_dir = |id: &str| {
+            data.systems.iter().any(|s| {
+                s.base.id == id
+                    && (!s.external.unwrap_or(false)
+                        || data.containers.iter().any(|c| c.system_id == id))
+            })
+        };
+
+        if !data.persons.is_empty() {
+            let persons = data.persons.iter().map(|p| element_value(&p.base, None));
+            files.push(data_file("shared/personas.yaml", "persons", persons));
+        }
+
+        let external: Vec<Value> = data
+            .systems
+            .iter()
+            .filter(|s| !has_dir(&s.base.id))
+            .map(|s| system_value(&s.base, s.external))
+            .collect();
+        if !external.is_empty() {
+            files.push(data_file(
+                "shared/external-systems.yaml",
+                "systems",
+                external,
+            ));
+        }
+
+        let mut relationships: BTreeMap<String, Vec<&Relationship>> = BTreeMap::new();
+        for rel in &data.relationships {
+            let system = [&rel.from, &rel.to]
+                .into_iter()
+                .map(|path| path.split('.').next().unwrap_or_default())
+                .find(|id| has_dir(id));
+            let file = match system {
+                Some(id) => format!("systems/{}/relationships.yaml", id),
+                None => "shared/relationships.yaml".to_string(),
+            };
+            relationships.entry(file).or_default().push(rel);
+        }
+        if let Some(shared) = relationships.remove("shared/relationships.yaml") {
+            files.push(data_file(
+                "shared/relationships.yaml",
+                "relationships",
+                shared.into_iter().map(relationship_value),
+            ));
+        }
+
+        let flow_system = |flow: &Flow| {
+            self.flow_systems
+                .get(&flow.id)
+                .filter(|id| has_dir(id))
+                .cloned()
+        };
+        let shared_flows: Vec<Value> = data
+            .flows
+            .iter()
+            .filter(|f| flow_system(f).is_none())
+            .map(flow_value)
+            .collect();
+        if !shared_flows.is_empty() {
+            files.push(data_file("shared/flows.yaml", "flows", shared_flows));
+        }
+
+        for system in data.systems.iter().filter(|s| has_dir(&s.base.id)) {
+            let id = &system.base.id;
+            let dir = format!("systems/{}", id);
+            files.push(data_file(
+                &format!("{}/system.yaml", dir),
+                "systems",
+                [system_value(&system.base, system.external)],
+            ));
+
+            let containers: Vec<_> = data
+                .containers
+                .iter()
+                .filter(|c| &c.system_id == id)
+                .collect();
+            if !containers.is_empty() {
+                files.push(data_file(
+                    &format!("{}/containers.yaml", dir),
+                    "containers",
+                    containers
+                        .iter()
+                        .map(|c| element_value(&c.base, c.technology.as_ref())),
+                ));
+            }
+
+            for container in containers {
+                let components: Vec<Value> = data
+                    .components
+                    .iter()
+                    .filter(|c| &c.system_id == id && c.container_id == container.base.id)
+                    .map(|c| element_value(&c.base, c.technology.as_ref()))
+                    .collect();
+                if !components.is_empty() {
+                    files.push(data_file(
+                        &format!("{}/containers/{}/components.yaml", dir, container.base.id),
+                        "components",
+                        components,
+                    ));
+                }
+            }
+
+            let file = format!("{}/relationships.yaml", dir);
+            if let Some(rels) = relationships.remove(&file) {
+                files.push(data_file(
+                    &file,
+                    "relationships",
+                    rels.into_iter().map(relationship_value),
+                ));
+            }
+
+            for flow in data
+                .flows
+                .iter()
+                .filter(|f| flow_system(f).as_ref() == Some(id))
+            {
+                files.push(data_file(
+                    &format!("{}/flows/{}.yaml", dir, flow.id),
+                    "flows",
+                    [flow_value(flow)],
+                ));
+            }
+        }
+
+        for deployment in &data.deployments {
+            files.push(data_file(
+                &format!("deployments/{}.yaml", deployment.id),
+                "deployments",
+                [deployment_value(deployment)],
+            ));
+        }
+
+        files
+    }
+}
+
+/// Builds a data file holding `values` under `key`
+fn data_file(path: &str, key: &str, values: impl IntoIterator<Item = Value>) -> (String, String) {
+    let mut root = Mapping::new();
+    root.insert(key.into(), Value::Sequence(values.into_iter().collect()));
+    let content = serde_yaml::to_string(&root).unwrap_or_default();
+    (path.to_string(), content)
+}
+
+/// Element fields in the order the example files use. System and container
+/// IDs are left out since the layout derives them from the path.
+fn element_value(base: &BaseElement, technology: Option<&Technology>) -> Value {
+    let mut map = Mapping::new();
+    map.insert("id".into(), base.id.clone().into());
+    map.insert("name".into(), base.name.clone().into());
+    insert_text(&mut map, "description", base.description.as_deref());
+    insert_technology(&mut map, technology);
+    insert_tags(&mut map, base.tags.as_deref());
+    insert_properties(&mut map, base.properties.as_ref());
+    Value::Mapping(map)
+}
+
+fn system_value(base: &BaseElement, external: Option<bool>) -> Value {
+    let mut value = element_value(base, None);
+    if let (Value::Mapping(map), Some(true)) = (&mut value, external) {
+        map.insert("external".into(), true.into());
+    }
+    value
+}
+
+fn relationship_value(rel: &Relationship) -> Value {
+    let mut map = Mapping::new();
+    map.insert("from".into(), rel.from.clone().into());
+    map.insert("to".into(), rel.to.clone().into());
+    insert_text(&mut map, "description", rel.description.as_deref());
+    insert_technology(&mut map, rel.technology.as_ref());
+    insert_tags(&mut map, rel.tags.as_deref());
+    insert_properties(&mut map, rel.properties.as_ref());
+    Value::Mapping(map)
+}
+
+fn flow_value(flow: &Flow) -> Value {
+    let mut map = Mapping::new();
+    map.insert("id".into(), flow.id.clone().into());
+    map.insert("name".into(), flow.name.clone().into());
+    insert_text(&mut map, "description", flow.description.as_deref());
+    insert_tags(&mut map, flow.tags.as_deref());
+    let steps = flow
+        .steps
+        .iter()
+        .map(|step| {
+            let mut map = Mapping::new();
+            map.insert("seq".into(), step.seq.into());
+            map.insert("from".into(), step.from.clone().into());
+            map.insert("to".into(), step.to.clone().into());
+            insert_text(&mut map, "description", step.description.as_deref());
+            insert_technology(&mut map, step.technology.as_ref());
+            Value::Mapping(map)
+        })
+        .collect();
+    map.insert("steps".into(), Value::Sequence(steps));
+    Value::Mapping(map)
+}
+
+fn deployment_value(deployment: &Deployment) -> Value {
+    let mut map = Mapping::new();
+    map.insert("id".into(), deployment.id.clone().into());
+    map.insert("name".into(), deployment.name.clone().into());
+    insert_text(&mut map, "description", deployment.description.as_deref());
+    if let Some(nodes) = &deployment.nodes {
+        map.insert(
+            "nodes".into(),
+            Value::Sequence(nodes.iter().map(node_value).collect()),
+        );
+    }
+    Value::Mapping(map)
+}
+
+fn node_value(node: &DeploymentNode) -> Value {
+    let mut map = Mapping::new();
+    map.insert("id".into(), node.id.clone().into());
+    map.insert("name".into(), node.name.clone().into());
+    insert_technology(&mut map, node.technology.as_ref());
+    insert_properties(&mut map, node.properties.as_ref());
+    if let Some(instances) = node.instances.as_ref().filter(|i| !i.is_empty()) {
+        map.insert(
+            "instances".into(),
+            Value::Sequence(instances.iter().map(instance_value).collect()),
+        );
+    }
+    if let Some(children) = node.children.as_ref().filter(|c| !c.is_empty()) {
+        map.insert(
+            "children".into(),
+            Value::Sequence(children.iter().map(node_value).collect()),
+        );
+    }
+    Value::Mapping(map)
+}
+
+fn instance_value(instance: &ContainerInstance) -> Value {
+    let mut map = Mapping::new();
+    map.insert("container".into(), instance.container.clone().into());
+    if let Some(replicas) = instance.replicas {
+        map.insert("replicas".into(), replicas.into());
+    }
+    insert_properties(&mut map, instance.properties.as_ref());
+    Value::Mapping(map)
+}
+
+fn insert_text(map: &mut Mapping, key: &str, text: Option<&str>) {
+    if let Some(text) = text.filter(|t| !t.is_empty()) {
+        map.insert(key.into(), text.into());
+    }
+}
+
+fn insert_technology(map: &mut Mapping, technology: Option<&Technology>) {
+    if let Some(technology) = technology.filter(|t| !t.is_empty()) {
+        map.insert("technology".into(), technology.as_slice().join(", ").into());
+    }
+}
+
+fn insert_tags(map: &mut Mapping, tags: Option<&[String]>) {
+    if let Some(tags) = tags.filter(|t| !t.is_empty()) {
+        let tags = tags.iter().map(|t| Value::from(t.as_str())).collect();
+        map.insert("tags".into(), Value::Sequence(tags));
+    }
+}
+
+fn insert_properties(map: &mut Mapping, properties: Option<&HashMap<String, serde_json::Value>>) {
+    let Some(properties) = properties.filter(|p| !p.is_empty()) else {
+        return;
+    };
+    let sorted: BTreeMap<_, _> = properties.iter().collect();
+    let mut values = Mapping::new();
+    for (key, value) in sorted {
+        if let Ok(value) = serde_yaml::to_value(value) {
+            values.insert(key.as_str().into(), value);
+        }
+    }
+    map.insert("properties".into(), Value::Mapping(values));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_workspace() -> ImportedWorkspace {
+        let data: DataFile = serde_yaml::from_str(
+            r#"
+persons:
+  - id: customer
+    name: Customer
+systems:
+  - id: shop
+    name: Shop
+    tags: [core]
+    properties:
+      team: shop
+  - id: stripe
+    name: Stripe
+    external: true
+containers:
+  - id: api
+    name: API
+    systemId: shop
+    technology: Go, Chi
+components:
+  - id: orders
+    name: Orders
+    systemId: shop
+    containerId: api
+relationships:
+  - from: customer
+    to: shop.api
+    description: Orders
+  - from: customer
+    to: stripe
+flows:
+  - id: checkout
+    name: Checkout
+    steps:
+      - seq: 1
+        from: customer
+        to: shop.api
+deployments:
+  - id: production
+    name: Production
+    nodes:
+      - id: cloud
+        name: Cloud
+        instances:
+          - container: shop.api
+            replicas: 2
+"#,
+        )
+        .unwrap();
+
+        ImportedWorkspace {
+            name: "shop".to_string(),
+            data,
+            flow_systems: HashMap::from([("checkout".to_string(), "shop".to_string())]),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn file<'a>(files: &'a [(String, String)], path: &str) -> &'a str {
+        files
+            .iter()
+            .find(|(p, _)| p == path)
+            .map(|(_, content)| content.as_str())
+            .unwrap_or_else(|| panic!("missing {}", path))
+    }
+
+    #[test]
+    fn test_files_follow_init_layout() {
+        let files = create_test_workspace().files();
+        let paths: Vec<&str> = files.iter().map(|(p, _)| p.as_str()).collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                "shared/personas.yaml",
+                "shared/external-systems.yaml",
+                "shared/relationships.yaml",
+                "systems/shop/system.yaml",
+                "systems/shop/containers.yaml",
+                "systems/shop/containers/api/components.yaml",
+                "systems/shop/relationships.yaml",
+                "systems/shop/flows/checkout.yaml",
+                "deployments/production.yaml",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_files_content() {
+        let files = create_test_workspace().files();
+
+        assert_eq!(
+            file(&files, "systems/shop/system.yaml"),
+            "systems:\n- id: shop\n  name: Shop\n  tags:\n  - core\n  properties:\n    team: shop\n"
+        );
+        assert_eq!(
+            file(&files, "systems/shop/containers.yaml"),
+            "containers:\n- id: api\n  name: API\n  technology: Go, Chi\n"
+        );
+        assert!(file(&files, "shared/external-systems.yaml").contains("  external: true\n"));
+        assert!(file(&files, "systems/shop/relationships.yaml").contains("to: shop.api"));
+        assert!(file(&files, "deployments/production.yaml").contains("replicas: 2"));
+    }
+}
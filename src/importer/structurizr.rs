@@ -0,0 +1,2018 @@
+use crate::importer::{ImportedWorkspace, ImporterError, Result};
+use crate::model::{
+    BaseElement, Component, Container, ContainerInstance, Deployment, DeploymentNode, ElementType,
+    Flow, FlowStep, Person, Relationship, SoftwareSystem, Technology,
+};
+use serde_json::Value as Json;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Tag marking external software systems, as written by the Structurizr
+/// exporter
+const EXTERNAL_TAG: &str = "External";
+
+/// Tags Structurizr adds to every element and relationship
+const DEFAULT_TAGS: &[&str] = &[
+    "Element",
+    "Person",
+    "Software System",
+    "Container",
+    "Component",
+    "Relationship",
+    "Synchronous",
+    "Deployment Node",
+    "Container Instance",
+    "Software System Instance",
+    "Infrastructure Node",
+];
+
+/// View kinds c4 derives from the model instead of importing
+const STATIC_VIEWS: &[&str] = &[
+    "systemlandscape",
+    "systemcontext",
+    "container",
+    "component",
+    "deployment",
+    "filtered",
+    "image",
+    "custom",
+];
+
+/// Reads a Structurizr `workspace.dsl` or workspace JSON file
+pub fn import_structurizr(path: &Path) -> Result<ImportedWorkspace> {
+    let source =
+        fs::read_to_string(path).map_err(|e| ImporterError::Read(path.display().to_string(), e))?;
+    let fallback = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    if source.trim_start().starts_with('{') {
+        parse_json(&source, &fallback)
+    } else {
+        parse_dsl(&source, &fallback)
+    }
+}
+
+/// An element before it is given a c4 ID
+#[derive(Debug, Default)]
+struct Draft {
+    /// Identifier or name the ID is derived from
+    key: String,
+    name: String,
+    description: String,
+    technology: String,
+    tags: Vec<String>,
+    properties: HashMap<String, Json>,
+}
+
+/// Collects imported elements, giving each a valid ID that is unique among
+/// its siblings
+#[derive(Default)]
+struct Builder {
+    workspace: ImportedWorkspace,
+    paths: HashSet<String>,
+}
+
+impl Builder {
+    fn warn(&mut self, message: String) {
+        self.workspace.warnings.push(message);
+    }
+
+    /// Adds an element under `parent` (empty for the top level) and returns
+    /// its path
+    fn add_element(&mut self, kind: ElementType, parent: &str, mut draft: Draft) -> String {
+        let id = unique_id(&draft.key, kind_name(kind), |id| {
+            self.paths.contains(&join(parent, id))
+        });
+        let path = join(parent, &id);
+        self.paths.insert(path.clone());
+
+        let external = kind == ElementType::System && draft.tags.iter().any(|t| t == EXTERNAL_TAG);
+        if external {
+            draft.tags.retain(|t| t != EXTERNAL_TAG);
+        }
+        let technology = technology(&draft.technology);
+        let base = BaseElement {
+            id,
+            name: draft.name,
+            description: non_empty(draft.description),
+            tags: (!draft.tags.is_empty()).then_some(draft.tags),
+            properties: (!draft.properties.is_empty()).then_some(draft.properties),
+        };
+
+        let data = &mut self.workspace.data;
+        match kind {
+            ElementType::Person => data.persons.push(Person {
+                base,
+                element_type: kind,
+            }),
+            ElementType::System => data.systems.push(SoftwareSystem {
+                base,
+                element_type: kind,
+                external: external.then_some(true),
+            }),
+            ElementType::Container => data.containers.push(Container {
+                base,
+                element_type: kind,
+                technology,
+                system_id: parent.to_string(),
+            }),
+            ElementType::Component => {
+                let (system_id, container_id) = parent.split_once('.').unwrap_or((parent, ""));
+                data.components.push(Component {
+                    base,
+                    element_type: kind,
+                    technology,
+                    system_id: system_id.to_string(),
+                    container_id: container_id.to_string(),
+                });
+            }
+        }
+        path
+    }
+
+    fn add_relationship(
+        &mut self,
+        from: String,
+        to: String,
+        description: String,
+        tech: String,
+        tags: Vec<String>,
+        properties: HashMap<String, Json>,
+    ) {
+        self.workspace.data.relationships.push(Relationship {
+            from,
+            to,
+            description: non_empty(description),
+            technology: technology(&tech),
+            tags: (!tags.is_empty()).then_some(tags),
+            properties: (!properties.is_empty()).then_some(properties),
+            implied: false,
+        });
+    }
+
+    fn is_container(&self, path: &str) -> bool {
+        self.workspace
+            .data
+            .containers
+            .iter()
+            .any(|c| format!("{}.{}", c.system_id, c.base.id) == path)
+    }
+
+    fn add_flow(
+        &mut self,
+        key: &str,
+        name: String,
+        description: String,
+        steps: Vec<FlowStep>,
+    ) -> String {
+        let key = key.strip_prefix("flow-").unwrap_or(key);
+        let key = if key.is_empty() { name.as_str() } else { key };
+        let flows = &self.workspace.data.flows;
+        let id = unique_id(key, "flow", |id| flows.iter().any(|f| f.id == id));
+        self.workspace.data.flows.push(Flow {
+            id: id.clone(),
+            name,
+            description: non_empty(description),
+            steps,
+            tags: None,
+        });
+        id
+    }
+
+    fn add_deployment(&mut self, key: &str, name: String, nodes: Vec<DeploymentNode>) {
+        let deployments = &self.workspace.data.deployments;
+        let id = unique_id(key, "deployment", |id| {
+            deployments.iter().any(|d| d.id == id)
+        });
+        self.workspace.data.deployments.push(Deployment {
+            id,
+            name,
+            description: None,
+            nodes: Some(nodes),
+        });
+    }
+
+    fn finish(mut self, name: &str, fallback: &str) -> ImportedWorkspace {
+        self.workspace.name = match slug(name) {
+            s if s.is_empty() => slug(fallback),
+            s => s,
+        };
+        self.workspace
+    }
+}
+
+/// Replicas of a container instance from its `replicas` property, or else
+/// from the instances of its deployment nodes
+fn take_replicas(properties: &mut HashMap<String, Json>, multiplier: i32) -> Option<i32> {
+    let from_property = properties.get("replicas").and_then(|v| match v {
+        Json::String(s) => s.trim().parse().ok(),
+        Json::Number(n) => n.as_i64().and_then(|n| i32::try_from(n).ok()),
+        _ => None,
+    });
+    if from_property.is_some() {
+        properties.remove("replicas");
+        return from_property;
+    }
+    (multiplier > 1).then_some(multiplier)
+}
+
+fn kind_name(kind: ElementType) -> &'static str {
+    match kind {
+        ElementType::Person => "person",
+        ElementType::System => "system",
+        ElementType::Container => "container",
+        ElementType::Component => "component",
+    }
+}
+
+fn join(parent: &str, id: &str) -> String {
+    if parent.is_empty() {
+        id.to_string()
+    } else {
+        format!("{}.{}", parent, id)
+    }
+}
+
+/// Derives an ID matching `^[a-z][a-z0-9-]*$` from `key`, adding a number
+/// when `taken`
+fn unique_id(key: &str, kind: &str, taken: impl Fn(&str) -> bool) -> String {
+    let base = match slug(key) {
+        s if s.is_empty() => kind.to_string(),
+        s if !s.starts_with(|c: char| c.is_ascii_lowercase()) => format!("{}-{}", kind, s),
+        s => s,
+    };
+    let mut id = base.clone();
+    let mut n = 2;
+    while taken(&id) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
+
+/// Converts an identifier or name to kebab case, splitting camelCase words
+fn slug(text: &str) -> String {
+    let mut out = String::new();
+    let mut prev_lower = false;
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase() && prev_lower && !out.ends_with('-') {
+                out.push('-');
+            }
+            prev_lower = c.is_ascii_lowercase();
+            out.push(c.to_ascii_lowercase());
+        } else {
+            prev_lower = false;
+            if !out.is_empty() && !out.ends_with('-') {
+                out.push('-');
+            }
+        }
+    }
+    out.trim_end_matches('-').to_string()
+}
+
+fn split_tags(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty() && !DEFAULT_TAGS.contains(t))
+        .map(str::to_string)
+        .collect()
+}
+
+fn add_tags(tags: &mut Vec<String>, text: &str) {
+    for tag in split_tags(text) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+}
+
+fn technology(text: &str) -> Option<Technology> {
+    let parts: Vec<String> = text
+        .split(',')
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    (!parts.is_empty()).then(|| Technology::new(parts))
+}
+
+fn non_empty(text: String) -> Option<String> {
+    (!text.trim().is_empty()).then_some(text)
+}
+
+// DSL
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    text: String,
+    quoted: bool,
+}
+
+enum Lexeme {
+    Token(Token),
+    Open,
+    Close,
+    End,
+}
+
+/// A DSL line with its tokens and the statements of its block
+#[derive(Debug, Default)]
+struct Statement {
+    line: usize,
+    tokens: Vec<Token>,
+    block: Option<Vec<Statement>>,
+}
+
+impl Statement {
+    /// The identifier in `identifier = keyword ...`
+    fn identifier(&self) -> Option<&str> {
+        match self.tokens.get(1) {
+            Some(t) if !t.quoted && t.text == "=" => Some(&self.tokens[0].text),
+            _ => None,
+        }
+    }
+
+    fn keyword_index(&self) -> usize {
+        if self.identifier().is_some() {
+            2
+        } else {
+            0
+        }
+    }
+
+    fn keyword(&self) -> String {
+        self.tokens
+            .get(self.keyword_index())
+            .map(|t| t.text.to_lowercase())
+            .unwrap_or_default()
+    }
+
+    /// The argument at `i` after the keyword, or an empty string
+    fn arg(&self, i: usize) -> &str {
+        self.tokens
+            .get(self.keyword_index() + 1 + i)
+            .map(|t| t.text.as_str())
+            .unwrap_or_default()
+    }
+
+    fn children(&self) -> &[Statement] {
+        self.block.as_deref().unwrap_or_default()
+    }
+
+    fn arrow(&self) -> Option<usize> {
+        self.tokens.iter().position(|t| !t.quoted && t.text == "->")
+    }
+}
+
+fn lex(source: &str) -> Result<Vec<(usize, Lexeme)>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = Vec::new();
+    let mut line = 1;
+    let mut line_start = true;
+    let mut i = 0;
+
+    let rest_is_blank = |from: usize| {
+        chars[from..]
+            .iter()
+            .take_while(|c| **c != '\n')
+            .all(|c| c.is_whitespace())
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            '\n' => {
+                out.push((line, Lexeme::End));
+                line += 1;
+                line_start = true;
+                i += 1;
+            }
+            c if c.is_whitespace() => i += 1,
+            // A backslash at the end of a line continues the statement
+            '\\' if rest_is_blank(i + 1) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                line += 1;
+                i += 1;
+            }
+            '/' if next == Some('/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '#' if line_start => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+                i += 2;
+            }
+            '{' | '}' => {
+                let lexeme = if c == '{' {
+                    Lexeme::Open
+                } else {
+                    Lexeme::Close
+                };
+                out.push((line, lexeme));
+                line_start = false;
+                i += 1;
+            }
+            '"' if chars[i..].starts_with(&['"', '"', '"']) => {
+                let start_line = line;
+                i += 3;
+                let mut text = String::new();
+                while !chars[i..].starts_with(&['"', '"', '"']) {
+                    let Some(&c) = chars.get(i) else {
+                        return Err(ImporterError::StructurizrImport(format!(
+                            "line {}: unterminated text block",
+                            start_line
+                        )));
+                    };
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    text.push(c);
+                    i += 1;
+                }
+                i += 3;
+                out.push((
+                    start_line,
+                    Lexeme::Token(Token {
+                        text: text.trim().to_string(),
+                        quoted: true,
+                    }),
+                ));
+                line_start = false;
+            }
+            '"' => {
+                i += 1;
+                let mut text = String::new();
+                loop {
+                    match chars.get(i) {
+                        None | Some('\n') => {
+                            return Err(ImporterError::StructurizrImport(format!(
+                                "line {}: unterminated string",
+                                line
+                            )))
+                        }
+                        Some('"') => break,
+                        Some('\\') if matches!(chars.get(i + 1), Some('"') | Some('\\')) => {
+                            text.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some(&c) => {
+                            text.push(c);
+                            i += 1;
+                        }
+                    }
+                }
+                i += 1;
+                out.push((line, Lexeme::Token(Token { text, quoted: true })));
+                line_start = false;
+            }
+            _ => {
+                let mut text = String::new();
+                while let Some(&c) = chars.get(i) {
+                    if c.is_whitespace() || c == '{' || c == '}' || c == '"' {
+                        break;
+                    }
+                    text.push(c);
+                    i += 1;
+                }
+                out.push((
+                    line,
+                    Lexeme::Token(Token {
+                        text,
+                        quoted: false,
+                    }),
+                ));
+                line_start = false;
+            }
+        }
+    }
+    out.push((line, Lexeme::End));
+    Ok(out)
+}
+
+/// Groups lexemes into statements, nesting the statements of each block
+fn parse_block(
+    lexemes: &[(usize, Lexeme)],
+    pos: &mut usize,
+    depth: usize,
+) -> Result<Vec<Statement>> {
+    let mut statements = Vec::new();
+    let mut current = Statement::default();
+
+    while let Some((line, lexeme)) = lexemes.get(*pos) {
+        *pos += 1;
+        match lexeme {
+            Lexeme::Token(token) => {
+                if current.tokens.is_empty() {
+                    current.line = *line;
+                }
+                current.tokens.push(token.clone());
+            }
+            Lexeme::End => {
+                if !current.tokens.is_empty() {
+                    statements.push(std::mem::take(&mut current));
+                }
+            }
+            Lexeme::Open => {
+                let children = parse_block(lexemes, pos, depth + 1)?;
+                if !current.tokens.is_empty() {
+                    current.block = Some(children);
+                    statements.push(std::mem::take(&mut current));
+                } else {
+                    // A brace on its own line opens the previous statement's block
+                    match statements.last_mut() {
+                        Some(previous) if previous.block.is_none() => {
+                            previous.block = Some(children)
+                        }
+                        _ => statements.push(Statement {
+                            line: *line,
+                            tokens: Vec::new(),
+                            block: Some(children),
+                        }),
+                    }
+                }
+            }
+            Lexeme::Close => {
+                if depth == 0 {
+                    return Err(ImporterError::StructurizrImport(format!(
+                        "line {}: unexpected '}}'",
+                        line
+                    )));
+                }
+                if !current.tokens.is_empty() {
+                    statements.push(current);
+                }
+                return Ok(statements);
+            }
+        }
+    }
+
+    if depth > 0 {
+        return Err(ImporterError::StructurizrImport(
+            "unexpected end of file: missing '}'".to_string(),
+        ));
+    }
+    Ok(statements)
+}
+
+/// Replaces `${NAME}` with the values of `!const` and `!var` statements
+/// defined before it
+fn expand_constants(statements: &mut [Statement], constants: &mut HashMap<String, String>) {
+    for statement in statements {
+        for token in &mut statement.tokens {
+            for (name, value) in constants.iter() {
+                token.text = token.text.replace(&format!("${{{}}}", name), value);
+            }
+        }
+        if matches!(
+            statement.keyword().as_str(),
+            "!const" | "!constant" | "!var"
+        ) {
+            constants.insert(statement.arg(0).to_string(), statement.arg(1).to_string());
+        }
+        if let Some(block) = &mut statement.block {
+            expand_constants(block, constants);
+        }
+    }
+}
+
+fn parse_statements(source: &str) -> Result<Vec<Statement>> {
+    let lexemes = lex(source)?;
+    let mut statements = parse_block(&lexemes, &mut 0, 0)?;
+    expand_constants(&mut statements, &mut HashMap::new());
+    Ok(statements)
+}
+
+/// The element and DSL identifier that statements are nested in
+#[derive(Clone, Default)]
+struct Scope {
+    path: String,
+    identifier: Option<String>,
+    kind: Option<ElementType>,
+}
+
+enum Endpoint {
+    Path(String),
+    Reference(String),
+}
+
+struct PendingRelationship {
+    line: usize,
+    identifier: Option<String>,
+    scope: Option<String>,
+    from: Endpoint,
+    to: Endpoint,
+    description: String,
+    technology: String,
+    tags: Vec<String>,
+    properties: HashMap<String, Json>,
+}
+
+#[derive(Default)]
+struct DslImporter {
+    builder: Builder,
+    /// Element paths by lowercase identifier, both flat and hierarchical
+    elements: HashMap<String, String>,
+    /// Endpoints, description and technology of relationships by identifier
+    relationships: HashMap<String, (String, String, String, String)>,
+    pending: Vec<PendingRelationship>,
+}
+
+/// Parses a Structurizr DSL workspace
+pub fn parse_dsl(source: &str, fallback_name: &str) -> Result<ImportedWorkspace> {
+    let statements = parse_statements(source)?;
+    let mut importer = DslImporter::default();
+
+    let mut workspace = None;
+    for statement in &statements {
+        match statement.keyword().as_str() {
+            "workspace" if workspace.is_none() => workspace = Some(statement),
+            "!const" | "!constant" | "!var" | "!identifiers" => {}
+            _ => importer.unsupported(statement),
+        }
+    }
+    let Some(workspace) = workspace else {
+        return Err(ImporterError::StructurizrImport(
+            "no workspace block found".to_string(),
+        ));
+    };
+
+    let mut name = workspace.arg(0).to_string();
+    if name == "extends" {
+        importer.builder.warn(format!(
+            "line {}: workspace extends {} was not followed; only this file was imported",
+            workspace.line,
+            workspace.arg(1)
+        ));
+        name = String::new();
+    }
+
+    for statement in workspace.children() {
+        match statement.keyword().as_str() {
+            "name" => name = statement.arg(0).to_string(),
+            "model" => {
+                importer.model(statement.children(), &Scope::default());
+                importer.resolve_relationships();
+            }
+            "views" => importer.views(statement.children()),
+            "description" | "!identifiers" | "!const" | "!constant" | "!var" => {}
+            _ => importer.unsupported(statement),
+        }
+    }
+
+    Ok(importer.builder.finish(&name, fallback_name))
+}
+
+impl DslImporter {
+    fn unsupported(&mut self, statement: &Statement) {
+        let keyword = statement
+            .tokens
+            .get(statement.keyword_index())
+            .map(|t| t.text.as_str())
+            .unwrap_or("{");
+        self.builder.warn(format!(
+            "line {}: '{}' is not supported and was skipped",
+            statement.line, keyword
+        ));
+    }
+
+    fn model(&mut self, statements: &[Statement], scope: &Scope) {
+        for statement in statements {
+            if statement.arrow().is_some() {
+                self.relationship(statement, scope);
+                continue;
+            }
+            let top = scope.kind.is_none();
+            match statement.keyword().as_str() {
+                "person" if top => self.element(statement, scope, ElementType::Person),
+                "softwaresystem" if top => self.element(statement, scope, ElementType::System),
+                "container" if scope.kind == Some(ElementType::System) => {
+                    self.element(statement, scope, ElementType::Container)
+                }
+                "component" if scope.kind == Some(ElementType::Container) => {
+                    self.element(statement, scope, ElementType::Component)
+                }
+                keyword @ ("group" | "enterprise") => {
+                    self.builder.warn(format!(
+                        "line {}: {} \"{}\" was flattened; c4 has no {}s",
+                        statement.line,
+                        keyword,
+                        statement.arg(0),
+                        keyword
+                    ));
+                    self.model(statement.children(), scope);
+                }
+                "deploymentenvironment" if top => self.deployment_environment(statement),
+                // Element attributes, read by `element`
+                "description" | "technology" | "tags" | "url" | "properties" if !top => {}
+                "!identifiers" | "!const" | "!constant" | "!var" => {}
+                _ => self.unsupported(statement),
+            }
+        }
+    }
+
+    fn element(&mut self, statement: &Statement, scope: &Scope, kind: ElementType) {
+        let name = statement.arg(0).to_string();
+        let has_technology = matches!(kind, ElementType::Container | ElementType::Component);
+        let mut draft = Draft {
+            key: statement.identifier().unwrap_or(&name).to_string(),
+            description: statement.arg(1).to_string(),
+            technology: if has_technology {
+                statement.arg(2).to_string()
+            } else {
+                String::new()
+            },
+            name,
+            ..Default::default()
+        };
+        add_tags(
+            &mut draft.tags,
+            statement.arg(if has_technology { 3 } else { 2 }),
+        );
+        for child in statement.children() {
+            match child.keyword().as_str() {
+                "description" => draft.description = child.arg(0).to_string(),
+                "technology" if has_technology => draft.technology = child.arg(0).to_string(),
+                "tags" => {
+                    for token in &child.tokens[1..] {
+                        add_tags(&mut draft.tags, &token.text);
+                    }
+                }
+                "url" => {
+                    draft
+                        .properties
+                        .insert("url".to_string(), child.arg(0).into());
+                }
+                "properties" => draft.properties.extend(properties(child)),
+                _ => {}
+            }
+        }
+
+        let path = self.builder.add_element(kind, &scope.path, draft);
+
+        let mut identifier = scope.identifier.clone();
+        if let Some(id) = statement.identifier() {
+            let id = id.to_lowercase();
+            let hierarchical = match &scope.identifier {
+                Some(parent) => format!("{}.{}", parent, id),
+                None => id.clone(),
+            };
+            // Nested elements only take a flat identifier when it is free, so
+            // top level elements win
+            if scope.kind.is_none() {
+                self.elements.insert(id, path.clone());
+            } else {
+                self.elements.entry(id).or_insert_with(|| path.clone());
+            }
+            self.elements.insert(hierarchical.clone(), path.clone());
+            identifier = Some(hierarchical);
+        }
+
+        let scope = Scope {
+            path,
+            identifier,
+            kind: Some(kind),
+        };
+        self.model(statement.children(), &scope);
+    }
+
+    fn relationship(&mut self, statement: &Statement, scope: &Scope) {
+        let Some(arrow) = statement.arrow() else {
+            return;
+        };
+        let identifier = statement.identifier().map(str::to_lowercase);
+        let start = if identifier.is_some() { 2 } else { 0 };
+        let endpoint = |token: &Token| {
+            if token.text == "this" {
+                Endpoint::Path(scope.path.clone())
+            } else {
+                Endpoint::Reference(token.text.clone())
+            }
+        };
+
+        let from = match arrow.checked_sub(start) {
+            Some(0) if !scope.path.is_empty() => Endpoint::Path(scope.path.clone()),
+            Some(1) => endpoint(&statement.tokens[start]),
+            _ => return self.unsupported(statement),
+        };
+        let Some(to) = statement.tokens.get(arrow + 1).map(endpoint) else {
+            return self.unsupported(statement);
+        };
+        let arg = |i: usize| {
+            statement
+                .tokens
+                .get(arrow + 2 + i)
+                .map(|t| t.text.clone())
+                .unwrap_or_default()
+        };
+
+        let mut pending = PendingRelationship {
+            line: statement.line,
+            identifier,
+            scope: scope.identifier.clone(),
+            from,
+            to,
+            description: arg(0),
+            technology: arg(1),
+            tags: split_tags(&arg(2)),
+            properties: HashMap::new(),
+        };
+        for child in statement.children() {
+            match child.keyword().as_str() {
+                "description" => pending.description = child.arg(0).to_string(),
+                "technology" => pending.technology = child.arg(0).to_string(),
+                "tags" => {
+                    for token in &child.tokens[1..] {
+                        add_tags(&mut pending.tags, &token.text);
+                    }
+                }
+                "url" => {
+                    pending
+                        .properties
+                        .insert("url".to_string(), child.arg(0).into());
+                }
+                "properties" => pending.properties.extend(properties(child)),
+                _ => self.unsupported(child),
+            }
+        }
+        self.pending.push(pending);
+    }
+
+    /// Looks up an identifier, also relative to the identifier of the scope
+    /// it was used in
+    fn resolve(&self, reference: &str, scope: Option<&str>) -> Option<String> {
+        let reference = reference.to_lowercase();
+        self.elements
+            .get(&reference)
+            .or_else(|| scope.and_then(|s| self.elements.get(&format!("{}.{}", s, reference))))
+            .cloned()
+    }
+
+    fn resolve_relationships(&mut self) {
+        for rel in std::mem::take(&mut self.pending) {
+            let mut endpoints = Vec::new();
+            for endpoint in [&rel.from, &rel.to] {
+                match endpoint {
+                    Endpoint::Path(path) => endpoints.push(path.clone()),
+                    Endpoint::Reference(reference) => {
+                        match self.resolve(reference, rel.scope.as_deref()) {
+                            Some(path) => endpoints.push(path),
+                            None => self.builder.warn(format!(
+                                "line {}: unknown element '{}'; relationship skipped",
+                                rel.line, reference
+                            )),
+                        }
+                    }
+                }
+            }
+            let [from, to] = <[String; 2]>::try_from(endpoints).ok().unwrap_or_default();
+            if from.is_empty() || to.is_empty() {
+                continue;
+            }
+
+            if let Some(identifier) = rel.identifier {
+                self.relationships.insert(
+                    identifier,
+                    (
+                        from.clone(),
+                        to.clone(),
+                        rel.description.clone(),
+                        rel.technology.clone(),
+                    ),
+                );
+            }
+            self.builder.add_relationship(
+                from,
+                to,
+                rel.description,
+                rel.technology,
+                rel.tags,
+                rel.properties,
+            );
+        }
+    }
+
+    fn deployment_environment(&mut self, statement: &Statement) {
+        let name = statement.arg(0).to_string();
+        let key = statement.identifier().unwrap_or(&name).to_string();
+        let (nodes, instances) = self.deployment_children(statement.children(), 1);
+        if !instances.is_empty() {
+            self.builder.warn(format!(
+                "line {}: container instances outside a deployment node were skipped",
+                statement.line
+            ));
+        }
+        self.builder.add_deployment(&key, name, nodes);
+    }
+
+    /// Reads the nodes and container instances of a deployment environment or
+    /// node. `multiplier` is the number of instances of the enclosing nodes.
+    fn deployment_children(
+        &mut self,
+        statements: &[Statement],
+        multiplier: i32,
+    ) -> (Vec<DeploymentNode>, Vec<ContainerInstance>) {
+        let mut nodes: Vec<DeploymentNode> = Vec::new();
+        let mut instances = Vec::new();
+
+        for statement in statements {
+            if statement.arrow().is_some() {
+                self.builder.warn(format!(
+                    "line {}: relationships between deployment elements are not supported and were skipped",
+                    statement.line
+                ));
+                continue;
+            }
+            match statement.keyword().as_str() {
+                "deploymentnode" => {
+                    let name = statement.arg(0);
+                    let key = statement.identifier().unwrap_or(name);
+                    let id = unique_id(key, "node", |id| nodes.iter().any(|n| n.id == id));
+                    nodes.push(self.deployment_node(statement, id, multiplier));
+                }
+                "containerinstance" => {
+                    if let Some(instance) = self.container_instance(statement, multiplier) {
+                        instances.push(instance);
+                    }
+                }
+                "description" | "technology" | "tags" | "instances" | "properties" | "url" => {}
+                _ => self.unsupported(statement),
+            }
+        }
+        (nodes, instances)
+    }
+
+    fn deployment_node(
+        &mut self,
+        statement: &Statement,
+        id: String,
+        multiplier: i32,
+    ) -> DeploymentNode {
+        let mut technology = statement.arg(2).to_string();
+        let mut count = statement.arg(4).to_string();
+        let mut props = HashMap::new();
+        for child in statement.children() {
+            match child.keyword().as_str() {
+                "technology" => technology = child.arg(0).to_string(),
+                "instances" => count = child.arg(0).to_string(),
+                "properties" => props.extend(properties(child)),
+                _ => {}
+            }
+        }
+
+        let count = match count.trim() {
+            "" => 1,
+            text => text.parse::<i32>().unwrap_or_else(|_| {
+                self.builder.warn(format!(
+                    "line {}: instances '{}' of deployment node \"{}\" is not a number and was ignored",
+                    statement.line,
+                    text,
+                    statement.arg(0)
+                ));
+                1
+            }),
+        };
+        let (children, instances) =
+            self.deployment_children(statement.children(), multiplier * count);
+
+        DeploymentNode {
+            id,
+            name: statement.arg(0).to_string(),
+            technology: self::technology(&technology),
+            children: (!children.is_empty()).then_some(children),
+            instances: (!instances.is_empty()).then_some(instances),
+            properties: (!props.is_empty()).then_some(props),
+        }
+    }
+
+    fn container_instance(
+        &mut self,
+        statement: &Statement,
+        multiplier: i32,
+    ) -> Option<ContainerInstance> {
+        let reference = statement.arg(0);
+        let path = self
+            .resolve(reference, None)
+            .filter(|path| self.builder.is_container(path));
+        let Some(container) = path else {
+            self.builder.warn(format!(
+                "line {}: unknown container '{}'; container instance skipped",
+                statement.line, reference
+            ));
+            return None;
+        };
+
+        let mut props = HashMap::new();
+        for child in statement.children() {
+            match child.keyword().as_str() {
+                "properties" => props.extend(properties(child)),
+                "description" | "tags" | "url" => {}
+                _ => self.unsupported(child),
+            }
+        }
+
+        Some(ContainerInstance {
+            container,
+            replicas: take_replicas(&mut props, multiplier),
+            properties: (!props.is_empty()).then_some(props),
+        })
+    }
+
+    fn views(&mut self, statements: &[Statement]) {
+        let mut skipped = 0;
+        for statement in statements {
+            let keyword = statement.keyword();
+            match keyword.as_str() {
+                "dynamic" => self.dynamic_view(statement),
+                k if STATIC_VIEWS.contains(&k) => skipped += 1,
+                _ => self.unsupported(statement),
+            }
+        }
+        if skipped > 0 {
+            self.builder.warn(format!(
+                "{} static view(s) were skipped; c4 derives its views from the model",
+                skipped
+            ));
+        }
+    }
+
+    fn dynamic_view(&mut self, statement: &Statement) {
+        let scope = statement.arg(0);
+        let system = match scope {
+            "*" => None,
+            reference => self
+                .resolve(reference, None)
+                .map(|path| path.split('.').next().unwrap_or_default().to_string()),
+        };
+
+        let mut title = String::new();
+        let mut description = String::new();
+        let mut steps = Vec::new();
+        self.dynamic_steps(
+            statement.children(),
+            &mut steps,
+            &mut title,
+            &mut description,
+        );
+
+        let key = statement.arg(1);
+        let name = [title.as_str(), statement.arg(2), key]
+            .into_iter()
+            .find(|s| !s.is_empty())
+            .unwrap_or("Flow")
+            .to_string();
+        if steps.is_empty() {
+            self.builder.warn(format!(
+                "line {}: dynamic view \"{}\" has no steps and was skipped",
+                statement.line, name
+            ));
+            return;
+        }
+
+        let id = self.builder.add_flow(key, name, description, steps);
+        if let Some(system) = system {
+            self.builder.workspace.flow_systems.insert(id, system);
+        }
+    }
+
+    fn dynamic_steps(
+        &mut self,
+        statements: &[Statement],
+        steps: &mut Vec<FlowStep>,
+        title: &mut String,
+        description: &mut String,
+    ) {
+        for statement in statements {
+            // Parallel sequences are blocks without a keyword
+            if statement.tokens.is_empty() {
+                self.builder.warn(format!(
+                    "line {}: parallel sequence was flattened into the flow",
+                    statement.line
+                ));
+                self.dynamic_steps(statement.children(), steps, title, description);
+                continue;
+            }
+
+            // Steps may start with an explicit order such as `1:`
+            let tokens = match statement.tokens.first() {
+                Some(t) if !t.quoted && t.text.ends_with(':') => &statement.tokens[1..],
+                _ => &statement.tokens[..],
+            };
+            let text = |i: usize| tokens.get(i).map(|t| t.text.clone()).unwrap_or_default();
+
+            let step = if tokens.len() > 2 && !tokens[1].quoted && tokens[1].text == "->" {
+                let from = self.resolve(&tokens[0].text, None);
+                let to = self.resolve(&tokens[2].text, None);
+                match (from, to) {
+                    (Some(from), Some(to)) => Some((from, to, text(3), text(4))),
+                    _ => {
+                        self.builder.warn(format!(
+                            "line {}: unknown element in dynamic view step; step skipped",
+                            statement.line
+                        ));
+                        None
+                    }
+                }
+            } else if let Some((from, to, desc, tech)) =
+                self.relationships.get(&text(0).to_lowercase()).cloned()
+            {
+                let desc = match text(1) {
+                    d if d.is_empty() => desc,
+                    d => d,
+                };
+                Some((from, to, desc, tech))
+            } else {
+                match statement.keyword().as_str() {
+                    "title" => *title = statement.arg(0).to_string(),
+                    "description" => *description = statement.arg(0).to_string(),
+                    "autolayout" | "include" | "exclude" | "animation" | "properties"
+                    | "default" => {}
+                    _ => self.unsupported(statement),
+                }
+                None
+            };
+
+            if let Some((from, to, desc, tech)) = step {
+                steps.push(FlowStep {
+                    seq: steps.len() as i32 + 1,
+                    from,
+                    to,
+                    description: non_empty(desc),
+                    technology: technology(&tech),
+                });
+            }
+        }
+    }
+}
+
+/// Reads the `"name" "value"` lines of a properties block
+fn properties(statement: &Statement) -> HashMap<String, Json> {
+    statement
+        .children()
+        .iter()
+        .filter_map(|p| {
+            let key = p.tokens.first()?.text.clone();
+            let value = p.tokens.get(1).map(|t| t.text.clone()).unwrap_or_default();
+            Some((key, Json::String(value)))
+        })
+        .collect()
+}
+
+// JSON
+
+#[derive(Default)]
+struct JsonImporter {
+    builder: Builder,
+    /// Element paths by Structurizr ID
+    elements: HashMap<String, String>,
+    /// Endpoints, description and technology of relationships by Structurizr ID
+    relationships: HashMap<String, (String, String, String, String)>,
+    pending: Vec<Json>,
+}
+
+/// Parses a Structurizr workspace JSON document
+pub fn parse_json(source: &str, fallback_name: &str) -> Result<ImportedWorkspace> {
+    let root: Json = serde_json::from_str(source)
+        .map_err(|e| ImporterError::StructurizrImport(format!("invalid workspace JSON: {}", e)))?;
+    let mut importer = JsonImporter::default();
+    let model = &root["model"];
+
+    for person in array(model, "people") {
+        importer.element(person, ElementType::Person, "");
+    }
+    for system in array(model, "softwareSystems") {
+        let system_path = importer.element(system, ElementType::System, "");
+        for container in array(system, "containers") {
+            let container_path = importer.element(container, ElementType::Container, &system_path);
+            for component in array(container, "components") {
+                importer.element(component, ElementType::Component, &container_path);
+            }
+        }
+    }
+    if !array(model, "customElements").is_empty() {
+        importer.builder.warn(format!(
+            "{} custom element(s) were skipped",
+            array(model, "customElements").len()
+        ));
+    }
+    importer.resolve_relationships();
+
+    let mut environments: Vec<(String, Vec<DeploymentNode>)> = Vec::new();
+    for node in array(model, "deploymentNodes") {
+        let environment = match string(node, "environment") {
+            e if e.is_empty() => "Default".to_string(),
+            e => e,
+        };
+        let index = match environments
+            .iter()
+            .position(|(name, _)| *name == environment)
+        {
+            Some(index) => index,
+            None => {
+                environments.push((environment, Vec::new()));
+                environments.len() - 1
+            }
+        };
+        let siblings = &environments[index].1;
+        let id = unique_id(&node_key(node), "node", |id| {
+            siblings.iter().any(|n| n.id == id)
+        });
+        let node = importer.deployment_node(node, id, 1);
+        environments[index].1.push(node);
+    }
+    for (name, nodes) in environments {
+        importer.builder.add_deployment(&name, name.clone(), nodes);
+    }
+
+    importer.views(&root["views"]);
+
+    let documentation = &root["documentation"];
+    if !array(documentation, "sections").is_empty() || !array(documentation, "decisions").is_empty()
+    {
+        importer
+            .builder
+            .warn("documentation and decisions were skipped".to_string());
+    }
+
+    Ok(importer
+        .builder
+        .finish(&string(&root, "name"), fallback_name))
+}
+
+fn array<'a>(value: &'a Json, key: &str) -> &'a [Json] {
+    value[key].as_array().map(Vec::as_slice).unwrap_or_default()
+}
+
+fn string(value: &Json, key: &str) -> String {
+    match &value[key] {
+        Json::String(s) => s.clone(),
+        Json::Number(n) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Properties of an element, without the ones Structurizr adds itself
+fn json_properties(value: &Json) -> HashMap<String, Json> {
+    let mut props: HashMap<String, Json> = value["properties"]
+        .as_object()
+        .map(|o| {
+            o.iter()
+                .filter(|(k, _)| !k.starts_with("structurizr."))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let url = string(value, "url");
+    if !url.is_empty() {
+        props.insert("url".to_string(), url.into());
+    }
+    props
+}
+
+/// The DSL identifier an element was defined with, or else its name
+fn node_key(value: &Json) -> String {
+    let identifier = value["properties"]["structurizr.dsl.identifier"]
+        .as_str()
+        .and_then(|i| i.rsplit('.').next())
+        .unwrap_or_default();
+    match identifier {
+        "" => string(value, "name"),
+        i => i.to_string(),
+    }
+}
+
+impl JsonImporter {
+    fn element(&mut self, value: &Json, kind: ElementType, parent: &str) -> String {
+        let mut tags = split_tags(&string(value, "tags"));
+        if string(value, "location") == "External" && !tags.iter().any(|t| t == EXTERNAL_TAG) {
+            tags.push(EXTERNAL_TAG.to_string());
+        }
+        let draft = Draft {
+            key: node_key(value),
+            name: string(value, "name"),
+            description: string(value, "description"),
+            technology: string(value, "technology"),
+            tags,
+            properties: json_properties(value),
+        };
+
+        let path = self.builder.add_element(kind, parent, draft);
+        self.elements.insert(string(value, "id"), path.clone());
+        self.pending
+            .extend(array(value, "relationships").iter().cloned());
+        path
+    }
+
+    fn resolve_relationships(&mut self) {
+        for rel in std::mem::take(&mut self.pending) {
+            let from = self.elements.get(&string(&rel, "sourceId")).cloned();
+            let to = self.elements.get(&string(&rel, "destinationId")).cloned();
+            let (Some(from), Some(to)) = (from, to) else {
+                self.builder.warn(format!(
+                    "relationship {} \"{}\" has an unknown end and was skipped",
+                    string(&rel, "id"),
+                    string(&rel, "description")
+                ));
+                continue;
+            };
+
+            let description = string(&rel, "description");
+            let technology = string(&rel, "technology");
+            self.relationships.insert(
+                string(&rel, "id"),
+                (
+                    from.clone(),
+                    to.clone(),
+                    description.clone(),
+                    technology.clone(),
+                ),
+            );
+            // Relationships implied from nested elements are derived by c4
+            if rel.get("linkedRelationshipId").is_some() {
+                continue;
+            }
+            self.builder.add_relationship(
+                from,
+                to,
+                description,
+                technology,
+                split_tags(&string(&rel, "tags")),
+                json_properties(&rel),
+            );
+        }
+    }
+
+    fn deployment_node(&mut self, value: &Json, id: String, multiplier: i32) -> DeploymentNode {
+        let name = string(value, "name");
+        let count = match string(value, "instances").trim() {
+            "" => 1,
+            text => text.parse::<i32>().unwrap_or_else(|_| {
+                self.builder.warn(format!(
+                    "instances '{}' of deployment node \"{}\" is not a number and was ignored",
+                    text, name
+                ));
+                1
+            }),
+        };
+
+        for (key, label) in [
+            ("softwareSystemInstances", "software system instance"),
+            ("infrastructureNodes", "infrastructure node"),
+        ] {
+            let skipped = array(value, key).len();
+            if skipped > 0 {
+                self.builder.warn(format!(
+                    "{} {}(s) in deployment node \"{}\" were skipped",
+                    skipped, label, name
+                ));
+            }
+        }
+
+        let mut children: Vec<DeploymentNode> = Vec::new();
+        for child in array(value, "children") {
+            let id = unique_id(&node_key(child), "node", |id| {
+                children.iter().any(|n| n.id == id)
+            });
+            let node = self.deployment_node(child, id, multiplier * count);
+            children.push(node);
+        }
+
+        let mut instances = Vec::new();
+        for instance in array(value, "containerInstances") {
+            let container = self
+                .elements
+                .get(&string(instance, "containerId"))
+                .filter(|path| self.builder.is_container(path))
+                .cloned();
+            let Some(container) = container else {
+                self.builder.warn(format!(
+                    "container instance {} refers to an unknown container and was skipped",
+                    string(instance, "id")
+                ));
+                continue;
+            };
+            let mut props = json_properties(instance);
+            instances.push(ContainerInstance {
+                container,
+                replicas: take_replicas(&mut props, multiplier * count),
+                properties: (!props.is_empty()).then_some(props),
+            });
+        }
+
+        let props = json_properties(value);
+        DeploymentNode {
+            id,
+            name,
+            technology: technology(&string(value, "technology")),
+            children: (!children.is_empty()).then_some(children),
+            instances: (!instances.is_empty()).then_some(instances),
+            properties: (!props.is_empty()).then_some(props),
+        }
+    }
+
+    fn views(&mut self, views: &Json) {
+        for view in array(views, "dynamicViews") {
+            let mut steps = Vec::new();
+            for step in array(view, "relationships") {
+                let Some((from, to, desc, tech)) =
+                    self.relationships.get(&string(step, "id")).cloned()
+                else {
+                    self.builder.warn(format!(
+                        "dynamic view {} refers to unknown relationship {}; step skipped",
+                        string(view, "key"),
+                        string(step, "id")
+                    ));
+                    continue;
+                };
+                let desc = match string(step, "description") {
+                    d if d.is_empty() => desc,
+                    d => d,
+                };
+                steps.push(FlowStep {
+                    seq: steps.len() as i32 + 1,
+                    from,
+                    to,
+                    description: non_empty(desc),
+                    technology: technology(&tech),
+                });
+            }
+
+            let key = string(view, "key");
+            let title = string(view, "title");
+            let description = string(view, "description");
+            let (name, description) = match (title.is_empty(), description.is_empty()) {
+                (false, _) => (title, description),
+                (true, false) => (description, String::new()),
+                (true, true) => (key.clone(), String::new()),
+            };
+            if steps.is_empty() {
+                self.builder.warn(format!(
+                    "dynamic view \"{}\" has no steps and was skipped",
+                    name
+                ));
+                continue;
+            }
+
+            let system = self
+                .elements
+                .get(&string(view, "elementId"))
+                .map(|path| path.split('.').next().unwrap_or_default().to_string());
+            let id = self.builder.add_flow(&key, name, description, steps);
+            if let Some(system) = system {
+                self.builder.workspace.flow_systems.insert(id, system);
+            }
+        }
+
+        let skipped: usize = [
+            "systemLandscapeViews",
+            "systemContextViews",
+            "containerViews",
+            "componentViews",
+            "deploymentViews",
+            "filteredViews",
+            "imageViews",
+            "customViews",
+        ]
+        .iter()
+        .map(|key| array(views, key).len())
+        .sum();
+        if skipped > 0 {
+            self.builder.warn(format!(
+                "{} static view(s) were skipped; c4 derives its views from the model",
+                skipped
+            ));
+        }
+
+        let styles = &views["configuration"]["styles"];
+        if !array(styles, "elements").is_empty() || !array(styles, "relationships").is_empty() {
+            self.builder.warn("styles were skipped".to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exporter::structurizr::render_workspace;
+    use crate::model::{self, Element};
+    use crate::parser::DataFile;
+
+    fn paths(workspace: &ImportedWorkspace) -> Vec<String> {
+        let data = &workspace.data;
+        data.persons
+            .iter()
+            .map(|p| p.get_full_path())
+            .chain(data.systems.iter().map(|s| s.get_full_path()))
+            .chain(data.containers.iter().map(|c| c.get_full_path()))
+            .chain(data.components.iter().map(|c| c.get_full_path()))
+            .collect()
+    }
+
+    fn rels(workspace: &ImportedWorkspace) -> Vec<(&str, &str)> {
+        workspace
+            .data
+            .relationships
+            .iter()
+            .map(|r| (r.from.as_str(), r.to.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_slug_and_unique_id() {
+        assert_eq!(slug("webApp"), "web-app");
+        assert_eq!(slug("Internet Banking System"), "internet-banking-system");
+        assert_eq!(slug("API (v2)"), "api-v2");
+        assert_eq!(
+            unique_id("3D Viewer", "system", |_| false),
+            "system-3d-viewer"
+        );
+        assert_eq!(unique_id("", "person", |_| false), "person");
+        assert_eq!(unique_id("api", "container", |id| id == "api"), "api-2");
+    }
+
+    #[test]
+    fn test_parse_statements() {
+        let statements = parse_statements(
+            r#"
+# comment
+workspace "Name" "Has \"quotes\"" {
+    /* block
+       comment */
+    model { // trailing
+        a = person "A" \
+            "Description"
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(statements.len(), 1);
+        let workspace = &statements[0];
+        assert_eq!(workspace.line, 3);
+        assert_eq!(workspace.arg(1), "Has \"quotes\"");
+        let person = &workspace.children()[0].children()[0];
+        assert_eq!(person.identifier(), Some("a"));
+        assert_eq!(person.keyword(), "person");
+        assert_eq!(person.arg(1), "Description");
+    }
+
+    #[test]
+    fn test_parse_statements_unbalanced() {
+        let err = parse_statements("workspace {\n  model {\n}\n")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("missing '}'"));
+
+        let err = parse_statements("workspace {\n}\n}\n").err().unwrap();
+        assert!(err.to_string().contains("line 3: unexpected '}'"));
+    }
+
+    #[test]
+    fn test_parse_dsl_elements() {
+        let workspace = parse_dsl(
+            r#"
+workspace "Big Bank" {
+    !const TEAM "banking"
+
+    model {
+        customer = person "Personal Banking Customer" "A customer" "Customer"
+        bankingSystem = softwareSystem "Internet Banking System" {
+            description "Allows customers to bank online"
+            tags "Core"
+            properties {
+                "team" "${TEAM}"
+            }
+            webApp = container "Web Application" "Delivers content" "Java, Spring MVC" {
+                signIn = component "Sign In Controller" "" "Spring MVC Controller"
+                -> mainframe "Uses" "XML/HTTPS"
+            }
+            database = container "Database" "" "Oracle" "Database"
+        }
+        mainframe = softwareSystem "Mainframe" "" "External"
+
+        customer -> webApp "Visits" "HTTPS"
+        webApp -> this.database "Reads from" {
+            tags "sql"
+        }
+    }
+}
+"#,
+            "fallback",
+        )
+        .unwrap();
+
+        assert_eq!(workspace.name, "big-bank");
+        assert_eq!(
+            paths(&workspace),
+            vec![
+                "customer",
+                "banking-system",
+                "mainframe",
+                "banking-system.web-app",
+                "banking-system.database",
+                "banking-system.web-app.sign-in",
+            ]
+        );
+
+        let system = &workspace.data.systems[0];
+        assert_eq!(
+            system.base.description.as_deref(),
+            Some("Allows customers to bank online")
+        );
+        assert_eq!(system.base.tags, Some(vec!["Core".to_string()]));
+        assert_eq!(
+            system.base.get_properties()["team"],
+            Json::String("banking".to_string())
+        );
+        assert_eq!(workspace.data.systems[1].external, Some(true));
+        assert_eq!(workspace.data.systems[1].base.tags, None);
+        assert_eq!(
+            workspace.data.containers[0]
+                .technology
+                .as_ref()
+                .unwrap()
+                .as_slice(),
+            &["Java".to_string(), "Spring MVC".to_string()]
+        );
+
+        assert_eq!(
+            rels(&workspace),
+            vec![
+                ("banking-system.web-app", "mainframe"),
+                ("customer", "banking-system.web-app"),
+            ]
+        );
+        // `this.database` does not resolve and is reported
+        assert_eq!(workspace.warnings.len(), 1);
+        assert!(workspace.warnings[0].contains("unknown element 'this.database'"));
+    }
+
+    #[test]
+    fn test_parse_dsl_hierarchical_identifiers() {
+        let workspace = parse_dsl(
+            r#"
+workspace {
+    !identifiers hierarchical
+    model {
+        a = softwareSystem "A" {
+            api = container "API"
+            db = container "DB"
+            api -> db "Reads"
+        }
+        b = softwareSystem "B" {
+            api = container "API"
+        }
+        a.api -> b.api "Calls"
+    }
+}
+"#,
+            "legacy",
+        )
+        .unwrap();
+
+        assert_eq!(workspace.name, "legacy");
+        assert_eq!(
+            rels(&workspace),
+            vec![("a.api", "a.db"), ("a.api", "b.api")]
+        );
+        assert!(workspace.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_parse_dsl_deployment() {
+        let workspace = parse_dsl(
+            r#"
+workspace {
+    model {
+        shop = softwareSystem "Shop" {
+            api = container "API"
+        }
+        live = deploymentEnvironment "Live" {
+            deploymentNode "AWS" "" "Amazon Web Services" {
+                deploymentNode "EC2" "" "Ubuntu" "" 4 {
+                    containerInstance api
+                }
+                deploymentNode "EC2" {
+                    containerInstance api {
+                        properties {
+                            "replicas" "2"
+                        }
+                    }
+                    infrastructureNode "Load Balancer"
+                }
+            }
+        }
+    }
+}
+"#,
+            "shop",
+        )
+        .unwrap();
+
+        let deployment = &workspace.data.deployments[0];
+        assert_eq!(deployment.id, "live");
+        let aws = &deployment.nodes.as_ref().unwrap()[0];
+        assert_eq!(aws.id, "aws");
+        assert_eq!(
+            aws.technology.as_ref().unwrap().as_slice(),
+            &["Amazon Web Services".to_string()]
+        );
+        let children = aws.children.as_ref().unwrap();
+        assert_eq!(children[0].id, "ec2");
+        assert_eq!(children[1].id, "ec2-2");
+        let first = &children[0].instances.as_ref().unwrap()[0];
+        assert_eq!(first.container, "shop.api");
+        assert_eq!(first.replicas, Some(4));
+        let second = &children[1].instances.as_ref().unwrap()[0];
+        assert_eq!(second.replicas, Some(2));
+        assert_eq!(second.properties, None);
+
+        assert_eq!(workspace.warnings.len(), 1);
+        assert!(workspace.warnings[0].contains("'infrastructureNode' is not supported"));
+    }
+
+    #[test]
+    fn test_parse_dsl_views() {
+        let workspace = parse_dsl(
+            r#"
+workspace {
+    model {
+        user = person "User"
+        group "Internal" {
+            shop = softwareSystem "Shop" {
+                web = container "Web"
+            }
+        }
+        login = user -> shop.web "Logs in"
+    }
+    views {
+        systemContext shop {
+            include *
+        }
+        dynamic shop "signIn" "Sign in" {
+            login "Submits credentials"
+            2: shop.web -> user "Shows dashboard" "HTML"
+            autoLayout
+        }
+        styles {
+            element "Person" {
+                shape Person
+            }
+        }
+    }
+}
+"#,
+            "shop",
+        )
+        .unwrap();
+
+        let flow = &workspace.data.flows[0];
+        assert_eq!(flow.id, "sign-in");
+        assert_eq!(flow.name, "Sign in");
+        assert_eq!(flow.steps.len(), 2);
+        assert_eq!(flow.steps[0].from, "user");
+        assert_eq!(
+            flow.steps[0].description.as_deref(),
+            Some("Submits credentials")
+        );
+        assert_eq!(flow.steps[1].seq, 2);
+        assert_eq!(flow.steps[1].to, "user");
+        assert_eq!(workspace.flow_systems["sign-in"], "shop");
+
+        assert_eq!(workspace.warnings.len(), 3, "{:?}", workspace.warnings);
+        assert!(workspace.warnings[0].contains("group \"Internal\" was flattened"));
+        assert!(workspace.warnings[1].contains("'styles' is not supported"));
+        assert!(workspace.warnings[2].contains("1 static view(s) were skipped"));
+    }
+
+    #[test]
+    fn test_parse_dsl_without_workspace() {
+        let err = parse_dsl("model {\n}\n", "x").err().unwrap();
+        assert!(err.to_string().contains("no workspace block found"));
+    }
+
+    #[test]
+    fn test_parse_dsl_round_trips_exported_workspace() {
+        let data: DataFile = serde_yaml::from_str(
+            r#"
+persons:
+  - id: customer
+    name: Customer
+    tags: [end-user]
+systems:
+  - id: shop
+    name: Shop
+    properties:
+      team: shop-team
+  - id: stripe
+    name: Stripe
+    external: true
+containers:
+  - id: api
+    name: API
+    systemId: shop
+    technology: Go, Chi
+  - id: db
+    name: Database
+    systemId: shop
+components:
+  - id: orders
+    name: Orders
+    systemId: shop
+    containerId: api
+relationships:
+  - from: customer
+    to: shop.api
+    description: Places orders
+    technology: HTTPS
+  - from: shop.api.orders
+    to: shop.db
+  - from: shop.api
+    to: stripe
+    tags: [external]
+flows:
+  - id: checkout
+    name: Checkout
+    description: Buying things
+    steps:
+      - seq: 1
+        from: customer
+        to: shop.api
+        description: Checks out
+deployments:
+  - id: production
+    name: Production
+    nodes:
+      - id: cloud
+        name: Cloud
+        technology: AWS
+        instances:
+          - container: shop.api
+            replicas: 3
+"#,
+        )
+        .unwrap();
+        let mut m = model::Model::new();
+        m.persons = data.persons.clone();
+        m.systems = data.systems.clone();
+        m.containers = data.containers.clone();
+        m.components = data.components.clone();
+        m.relationships = data.relationships.clone();
+        m.flows = data.flows.clone();
+        m.deployments = data.deployments.clone();
+        m.build_indexes().unwrap();
+
+        let workspace = parse_dsl(&render_workspace(&m, "shop"), "x").unwrap();
+
+        assert_eq!(workspace.name, "shop");
+        assert_eq!(
+            paths(&workspace),
+            vec![
+                "customer",
+                "shop",
+                "stripe",
+                "shop.api",
+                "shop.db",
+                "shop.api.orders"
+            ]
+        );
+        assert_eq!(workspace.data.systems[1].external, Some(true));
+        assert_eq!(workspace.data.systems[1].base.tags, None);
+        assert_eq!(
+            rels(&workspace),
+            vec![
+                ("customer", "shop.api"),
+                ("shop.api.orders", "shop.db"),
+                ("shop.api", "stripe"),
+            ]
+        );
+        let flow = &workspace.data.flows[0];
+        assert_eq!(
+            (flow.id.as_str(), flow.name.as_str()),
+            ("checkout", "Checkout")
+        );
+        assert_eq!(flow.description.as_deref(), Some("Buying things"));
+        let node = &workspace.data.deployments[0].nodes.as_ref().unwrap()[0];
+        let instance = &node.instances.as_ref().unwrap()[0];
+        assert_eq!(
+            (instance.container.as_str(), instance.replicas),
+            ("shop.api", Some(3))
+        );
+        // Only the derived views and their styles are reported
+        assert_eq!(workspace.warnings.len(), 2, "{:?}", workspace.warnings);
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let workspace = parse_json(
+            r#"{
+  "name": "Big Bank",
+  "model": {
+    "people": [
+      {"id": "1", "name": "Customer", "tags": "Element,Person", "location": "External",
+       "relationships": [{"id": "10", "sourceId": "1", "destinationId": "3", "description": "Uses", "technology": "HTTPS", "tags": "Relationship"},
+                         {"id": "11", "sourceId": "1", "destinationId": "2", "description": "Uses", "linkedRelationshipId": "10"}]}
+    ],
+    "softwareSystems": [
+      {"id": "2", "name": "Internet Banking", "tags": "Element,Software System,Core",
+       "properties": {"structurizr.dsl.identifier": "bank", "team": "web"},
+       "containers": [
+         {"id": "3", "name": "Web App", "technology": "Java", "tags": "Element,Container",
+          "components": [{"id": "4", "name": "Sign In", "properties": {"structurizr.dsl.identifier": "bank.web.signIn"}}]}
+       ]},
+      {"id": "5", "name": "Mainframe", "location": "External"}
+    ],
+    "deploymentNodes": [
+      {"id": "20", "name": "AWS", "environment": "Live", "instances": "2",
+       "containerInstances": [{"id": "21", "containerId": "3"}],
+       "infrastructureNodes": [{"id": "22", "name": "ELB"}]}
+    ]
+  },
+  "views": {
+    "systemContextViews": [{"key": "context"}],
+    "dynamicViews": [{"key": "signin", "description": "Sign in", "elementId": "2",
+                      "relationships": [{"id": "10", "description": "Opens", "order": "1"}]}]
+  }
+}"#,
+            "fallback",
+        )
+        .unwrap();
+
+        assert_eq!(workspace.name, "big-bank");
+        assert_eq!(
+            paths(&workspace),
+            vec![
+                "customer",
+                "bank",
+                "mainframe",
+                "bank.web-app",
+                "bank.web-app.sign-in"
+            ]
+        );
+        assert_eq!(
+            workspace.data.persons[0].base.tags,
+            Some(vec!["External".to_string()])
+        );
+        assert_eq!(
+            workspace.data.systems[0].base.tags,
+            Some(vec!["Core".to_string()])
+        );
+        assert_eq!(
+            workspace.data.systems[0]
+                .base
+                .get_properties()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["team"]
+        );
+        assert_eq!(workspace.data.systems[1].external, Some(true));
+
+        // The linked relationship is implied and left to c4
+        assert_eq!(rels(&workspace), vec![("customer", "bank.web-app")]);
+        assert_eq!(workspace.data.relationships[0].tags, None);
+
+        let deployment = &workspace.data.deployments[0];
+        assert_eq!(
+            (deployment.id.as_str(), deployment.name.as_str()),
+            ("live", "Live")
+        );
+        let node = &deployment.nodes.as_ref().unwrap()[0];
+        assert_eq!(node.instances.as_ref().unwrap()[0].replicas, Some(2));
+
+        let flow = &workspace.data.flows[0];
+        assert_eq!(
+            (flow.id.as_str(), flow.name.as_str()),
+            ("signin", "Sign in")
+        );
+        assert_eq!(flow.steps[0].description.as_deref(), Some("Opens"));
+        assert_eq!(workspace.flow_systems["signin"], "bank");
+
+        assert_eq!(workspace.warnings.len(), 2, "{:?}", workspace.warnings);
+        assert!(workspace.warnings[0].contains("1 infrastructure node(s)"));
+        assert!(workspace.warnings[1].contains("1 static view(s) were skipped"));
+    }
+
+    #[test]
+    fn test_parse_json_invalid() {
+        let err = parse_json("{ nope", "x").err().unwrap();
+        assert!(err.to_string().contains("invalid workspace JSON"));
+    }
+}
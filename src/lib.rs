@@ -1,5 +1,6 @@
 pub mod cli;
 pub mod exporter;
+pub mod importer;
 pub mod model;
 pub mod parser;
 pub mod server;
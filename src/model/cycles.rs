@@ -0,0 +1,392 @@
+use super::{ElementType, Model};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// Most cycles reported per level; densely connected models can contain
+/// exponentially many
+pub const MAX_CYCLES: usize = 100;
+
+/// Most dependencies followed per level while searching for cycles, which
+/// bounds the search inside densely connected groups of elements
+const MAX_STEPS: usize = 100_000;
+
+/// A dependency cycle between elements at one C4 level
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cycle {
+    pub level: ElementType,
+    /// Elements in dependency order; the last one depends on the first
+    pub elements: Vec<String>,
+    /// Index of a relationship behind each step of the cycle
+    #[serde(skip)]
+    pub relationships: Vec<usize>,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for element in &self.elements {
+            write!(f, "{} -> ", element)?;
+        }
+        write!(f, "{}", self.elements.first().map_or("", String::as_str))
+    }
+}
+
+/// Rolled-up dependencies, each with the first relationship behind it
+type Graph = BTreeMap<String, BTreeMap<String, usize>>;
+
+impl Model {
+    /// Finds dependency cycles between systems, containers or components.
+    /// Relationships of nested elements count as relationships of their
+    /// ancestor at `level`, and persons are left out. Each cycle is
+    /// reported once, starting from its alphabetically first element.
+    pub fn find_cycles(&self, level: ElementType) -> Vec<Cycle> {
+        let mut graph = Graph::new();
+        for indices in self.outgoing_rels.values() {
+            for &idx in indices {
+                let rel = &self.relationships[idx];
+                let (Some(from), Some(to)) =
+                    (self.roll_up(&rel.from, level), self.roll_up(&rel.to, level))
+                else {
+                    continue;
+                };
+                if from != to {
+                    let first = graph.entry(from).or_default().entry(to).or_insert(idx);
+                    *first = (*first).min(idx);
+                }
+            }
+        }
+
+        // Cycles only run within a strongly connected component, so the
+        // search skips elements that are not part of one
+        let components = strongly_connected(&graph);
+        let mut sizes: HashMap<usize, usize> = HashMap::new();
+        for component in components.values() {
+            *sizes.entry(*component).or_default() += 1;
+        }
+
+        let mut search = Search {
+            graph: &graph,
+            components: &components,
+            level,
+            budget: MAX_STEPS,
+            cycles: Vec::new(),
+        };
+        for start in graph.keys() {
+            if sizes[&components[start.as_str()]] > 1 {
+                search.from(start);
+            }
+        }
+        search.cycles
+    }
+
+    /// The element at `level` that `path` is or belongs to
+    fn roll_up(&self, path: &str, level: ElementType) -> Option<String> {
+        let element = self.get_element(path)?;
+        let depth = match level {
+            ElementType::Person => return None,
+            ElementType::System => 1,
+            ElementType::Container => 2,
+            ElementType::Component => 3,
+        };
+        if element.get_type() == ElementType::Person {
+            return None;
+        }
+
+        let segments: Vec<&str> = path.split('.').collect();
+        if segments.len() < depth {
+            return None;
+        }
+        Some(segments[..depth].join("."))
+    }
+}
+
+/// Tarjan's algorithm: the index of the strongly connected component of
+/// every element in `graph`
+fn strongly_connected(graph: &Graph) -> HashMap<&str, usize> {
+    #[derive(Default)]
+    struct Tarjan<'a> {
+        next_index: usize,
+        index: HashMap<&'a str, usize>,
+        low: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: HashSet<&'a str>,
+        components: HashMap<&'a str, usize>,
+        count: usize,
+    }
+
+    impl<'a> Tarjan<'a> {
+        fn visit(&mut self, graph: &'a Graph, node: &'a str) {
+            self.index.insert(node, self.next_index);
+            self.low.insert(node, self.next_index);
+            self.next_index += 1;
+            self.stack.push(node);
+            self.on_stack.insert(node);
+
+            for next in graph.get(node).into_iter().flat_map(|edges| edges.keys()) {
+                let next = next.as_str();
+                if !self.index.contains_key(next) {
+                    self.visit(graph, next);
+                    let low = self.low[node].min(self.low[next]);
+                    self.low.insert(node, low);
+                } else if self.on_stack.contains(next) {
+                    let low = self.low[node].min(self.index[next]);
+                    self.low.insert(node, low);
+                }
+            }
+
+            if self.low[node] == self.index[node] {
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(member);
+                    self.components.insert(member, self.count);
+                    if member == node {
+                        break;
+                    }
+                }
+                self.count += 1;
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan::default();
+    for node in graph.keys() {
+        if !tarjan.index.contains_key(node.as_str()) {
+            tarjan.visit(graph, node);
+        }
+    }
+    tarjan.components
+}
+
+/// Depth-first search for elementary cycles within strongly connected
+/// components, stopping after `MAX_CYCLES` cycles or `MAX_STEPS` steps
+struct Search<'a> {
+    graph: &'a Graph,
+    components: &'a HashMap<&'a str, usize>,
+    level: ElementType,
+    budget: usize,
+    cycles: Vec<Cycle>,
+}
+
+impl<'a> Search<'a> {
+    fn from(&mut self, start: &'a str) {
+        self.extend(&mut vec![start], &mut Vec::new());
+    }
+
+    /// Extends `path` with elements after its start in the same component,
+    /// recording every way back to the start as a cycle
+    fn extend(&mut self, path: &mut Vec<&'a str>, relationships: &mut Vec<usize>) {
+        let start = path[0];
+        let node = path[path.len() - 1];
+        for (next, &idx) in self.graph.get(node).into_iter().flatten() {
+            if self.cycles.len() >= MAX_CYCLES || self.budget == 0 {
+                return;
+            }
+            self.budget -= 1;
+            if self.components.get(next.as_str()) != self.components.get(start) {
+                continue;
+            }
+
+            relationships.push(idx);
+            if next == start {
+                self.cycles.push(Cycle {
+                    level: self.level,
+                    elements: path.iter().map(|e| e.to_string()).collect(),
+                    relationships: relationships.clone(),
+                });
+            } else if next.as_str() > start && !path.contains(&next.as_str()) {
+                path.push(next);
+                self.extend(path, relationships);
+                path.pop();
+            }
+            relationships.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DataFile;
+
+    fn create_model(relationships: &str) -> Model {
+        let yaml = format!(
+            r#"
+persons:
+  - id: customer
+    name: Customer
+systems:
+  - id: shop
+    name: Shop
+  - id: billing
+    name: Billing
+  - id: crm
+    name: CRM
+containers:
+  - id: web
+    name: Web
+    systemId: shop
+  - id: api
+    name: API
+    systemId: shop
+  - id: api
+    name: Billing API
+    systemId: billing
+components:
+  - id: orders
+    name: Orders
+    systemId: shop
+    containerId: api
+  - id: payments
+    name: Payments
+    systemId: shop
+    containerId: api
+relationships:
+{}"#,
+            relationships
+        );
+        let data: DataFile = serde_yaml::from_str(&yaml).unwrap();
+        let mut m = Model::new();
+        m.persons = data.persons;
+        m.systems = data.systems;
+        m.containers = data.containers;
+        m.components = data.components;
+        m.relationships = data.relationships;
+        m.build_indexes().unwrap();
+        m
+    }
+
+    fn paths(cycles: &[Cycle]) -> Vec<String> {
+        cycles.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_cycles_rolled_up_to_each_level() {
+        let model = create_model(
+            r#"
+  - from: shop.api.orders
+    to: billing.api
+  - from: billing.api
+    to: shop.api.payments
+  - from: shop.web
+    to: shop.api
+"#,
+        );
+
+        assert_eq!(
+            paths(&model.find_cycles(ElementType::System)),
+            vec!["billing -> shop -> billing"]
+        );
+        assert_eq!(
+            paths(&model.find_cycles(ElementType::Container)),
+            vec!["billing.api -> shop.api -> billing.api"]
+        );
+        // Components are only linked through another container
+        assert!(model.find_cycles(ElementType::Component).is_empty());
+    }
+
+    #[test]
+    fn test_cycle_relationships() {
+        let model = create_model(
+            r#"
+  - from: shop
+    to: billing
+  - from: billing
+    to: crm
+  - from: crm
+    to: shop
+  - from: shop.api.orders
+    to: shop.api.payments
+"#,
+        );
+
+        let cycles = model.find_cycles(ElementType::System);
+        assert_eq!(paths(&cycles), vec!["billing -> crm -> shop -> billing"]);
+        assert_eq!(cycles[0].relationships, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn test_every_elementary_cycle_reported_once() {
+        let model = create_model(
+            r#"
+  - from: shop
+    to: billing
+  - from: billing
+    to: shop
+  - from: billing
+    to: crm
+  - from: crm
+    to: shop
+  - from: customer
+    to: shop
+  - from: shop
+    to: customer
+"#,
+        );
+
+        assert_eq!(
+            paths(&model.find_cycles(ElementType::System)),
+            vec![
+                "billing -> crm -> shop -> billing",
+                "billing -> shop -> billing"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layered_dag_is_searched_quickly() {
+        // Every container depends on every container in the next layer, so
+        // there are 3^21 paths through the layers but no cycles
+        let mut yaml = String::from("systems:\n  - id: app\n    name: App\ncontainers:\n");
+        for layer in 0..22 {
+            for i in 0..3 {
+                yaml.push_str(&format!(
+                    "  - id: l{}-c{}\n    name: L{} C{}\n    systemId: app\n",
+                    layer, i, layer, i
+                ));
+            }
+        }
+        yaml.push_str("relationships:\n");
+        for layer in 0..21 {
+            for i in 0..3 {
+                for j in 0..3 {
+                    yaml.push_str(&format!(
+                        "  - from: app.l{}-c{}\n    to: app.l{}-c{}\n",
+                        layer,
+                        i,
+                        layer + 1,
+                        j
+                    ));
+                }
+            }
+        }
+        // One cycle at the end of the layers is still found
+        yaml.push_str("  - from: app.l21-c0\n    to: app.l20-c0\n");
+
+        let data: DataFile = serde_yaml::from_str(&yaml).unwrap();
+        let mut model = Model::new();
+        model.systems = data.systems;
+        model.containers = data.containers;
+        model.relationships = data.relationships;
+        model.build_indexes().unwrap();
+
+        assert_eq!(
+            paths(&model.find_cycles(ElementType::Container)),
+            vec!["app.l20-c0 -> app.l21-c0 -> app.l20-c0"]
+        );
+    }
+
+    #[test]
+    fn test_no_cycles() {
+        let model = create_model(
+            r#"
+  - from: shop.web
+    to: shop.api
+  - from: shop.api
+    to: billing.api
+"#,
+        );
+
+        assert!(model.find_cycles(ElementType::System).is_empty());
+        assert!(model.find_cycles(ElementType::Container).is_empty());
+        assert!(model.find_cycles(ElementType::Person).is_empty());
+    }
+}
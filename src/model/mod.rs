@@ -1,9 +1,13 @@
+pub mod cycles;
+pub mod source;
 pub mod types;
 
+pub use cycles::Cycle;
+pub use source::*;
 pub use types::*;
 
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error as StdError;
 use std::fmt;
 
@@ -31,6 +35,10 @@ pub struct Model {
     pub containers: Vec<Container>,
     pub components: Vec<Component>,
     pub relationships: Vec<Relationship>,
+    /// Relationships between ancestors of related elements, derived by
+    /// `build_indexes`
+    #[serde(rename = "impliedRelationships", skip_serializing_if = "Vec::is_empty")]
+    pub implied_relationships: Vec<Relationship>,
     pub flows: Vec<Flow>,
     pub deployments: Vec<Deployment>,
     pub options: Options,
@@ -49,6 +57,14 @@ pub struct Model {
     outgoing_rels: HashMap<String, Vec<usize>>,
     #[serde(skip)]
     incoming_rels: HashMap<String, Vec<usize>>,
+    #[serde(skip)]
+    implied_outgoing: HashMap<String, Vec<usize>>,
+    #[serde(skip)]
+    implied_incoming: HashMap<String, Vec<usize>>,
+
+    // Where each item was defined, recorded by the parser
+    #[serde(skip)]
+    sources: HashMap<SourceKey, SourceLocation>,
 }
 
 impl Model {
@@ -60,6 +76,7 @@ impl Model {
             containers: Vec::new(),
             components: Vec::new(),
             relationships: Vec::new(),
+            implied_relationships: Vec::new(),
             flows: Vec::new(),
             deployments: Vec::new(),
             options: Options {
@@ -71,6 +88,9 @@ impl Model {
             children_by_id: HashMap::new(),
             outgoing_rels: HashMap::new(),
             incoming_rels: HashMap::new(),
+            implied_outgoing: HashMap::new(),
+            implied_incoming: HashMap::new(),
+            sources: HashMap::new(),
         }
     }
 
@@ -83,6 +103,8 @@ impl Model {
         self.children_by_id.clear();
         self.outgoing_rels.clear();
         self.incoming_rels.clear();
+        self.implied_outgoing.clear();
+        self.implied_incoming.clear();
 
         // Index persons
         for (idx, p) in self.persons.iter().enumerate() {
@@ -191,9 +213,77 @@ impl Model {
                 .push(idx);
         }
 
+        // Derive and index implied relationships
+        self.implied_relationships = self.derive_implied_relationships();
+        for (idx, r) in self.implied_relationships.iter().enumerate() {
+            self.implied_outgoing
+                .entry(r.from.clone())
+                .or_default()
+                .push(idx);
+            self.implied_incoming
+                .entry(r.to.clone())
+                .or_default()
+                .push(idx);
+        }
+
         Ok(())
     }
 
+    /// Rolls every relationship up to the ancestors of both ends, so that a
+    /// component calling into another system also relates its container and
+    /// system to that system. Pairs that are already related explicitly,
+    /// and those between an element and its own ancestor, are skipped; the
+    /// first relationship behind a pair provides its description and
+    /// technology.
+    fn derive_implied_relationships(&self) -> Vec<Relationship> {
+        let explicit: HashSet<(&str, &str)> = self
+            .relationships
+            .iter()
+            .map(|r| (r.from.as_str(), r.to.as_str()))
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut implied = Vec::new();
+        for rel in &self.relationships {
+            for from in self.ancestors_or_self(&rel.from) {
+                for to in self.ancestors_or_self(&rel.to) {
+                    if from == to || is_ancestor(from, to) || is_ancestor(to, from) {
+                        continue;
+                    }
+                    if explicit.contains(&(from, to)) || !seen.insert((from, to)) {
+                        continue;
+                    }
+                    implied.push(Relationship {
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        description: rel.description.clone(),
+                        technology: rel.technology.clone(),
+                        tags: None,
+                        properties: None,
+                        implied: true,
+                    });
+                }
+            }
+        }
+        implied
+    }
+
+    /// `path` followed by the paths of its parent elements, if it exists
+    fn ancestors_or_self<'a>(&self, path: &'a str) -> Vec<&'a str> {
+        if !self.elements_by_id.contains_key(path) {
+            return Vec::new();
+        }
+        let mut paths = vec![path];
+        let mut rest = path;
+        while let Some(idx) = rest.rfind('.') {
+            rest = &rest[..idx];
+            if self.elements_by_id.contains_key(rest) {
+                paths.push(rest);
+            }
+        }
+        paths
+    }
+
     /// Returns an element by its full path using O(1) indexed lookup
     pub fn get_element(&self, path: &str) -> Option<&dyn Element> {
         let (element_type, idx) = self.elements_by_id.get(path)?;
@@ -266,6 +356,32 @@ impl Model {
             .unwrap_or_default()
     }
 
+    /// Returns implied relationships from an element
+    pub fn get_implied_outgoing_relationships(&self, path: &str) -> Vec<&Relationship> {
+        self.implied_outgoing
+            .get(path)
+            .map(|indices| {
+                indices
+                    .iter()
+                    .filter_map(|&idx| self.implied_relationships.get(idx))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns implied relationships to an element
+    pub fn get_implied_incoming_relationships(&self, path: &str) -> Vec<&Relationship> {
+        self.implied_incoming
+            .get(path)
+            .map(|indices| {
+                indices
+                    .iter()
+                    .filter_map(|&idx| self.implied_relationships.get(idx))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Ensures all slices are non-nil for proper JSON encoding
     pub fn ensure_non_nil_slices(&mut self) {
         // Vecs in Rust are never nil, this is a no-op for compatibility
@@ -282,6 +398,66 @@ impl Model {
             .chain(self.components.iter().map(|c| c as &dyn Element))
             .collect()
     }
+
+    /// Records where an item was defined
+    pub fn set_source(&mut self, key: SourceKey, location: SourceLocation) {
+        self.sources.insert(key, location);
+    }
+
+    /// Returns where an item was defined, if known
+    pub fn source(&self, key: &SourceKey) -> Option<&SourceLocation> {
+        self.sources.get(key)
+    }
+
+    /// Returns where the element with the given path was defined
+    pub fn element_source(&self, path: &str) -> Option<&SourceLocation> {
+        let (t, idx) = self.elements_by_id.get(path)?;
+        self.sources.get(&SourceKey::Element(*t, *idx))
+    }
+
+    /// Current vector lengths, used to re-key sources when appending
+    pub fn offsets(&self) -> SourceOffsets {
+        SourceOffsets {
+            persons: self.persons.len(),
+            systems: self.systems.len(),
+            containers: self.containers.len(),
+            components: self.components.len(),
+            relationships: self.relationships.len(),
+            flows: self.flows.len(),
+            deployments: self.deployments.len(),
+        }
+    }
+
+    /// Moves all items and their sources from `other` into this model.
+    /// Indexes must be rebuilt afterwards.
+    pub fn append(&mut self, mut other: Model) {
+        let offsets = self.offsets();
+        for (key, location) in other.sources.drain() {
+            self.sources.insert(key.offset(&offsets), location);
+        }
+
+        self.persons.append(&mut other.persons);
+        self.systems.append(&mut other.systems);
+        self.containers.append(&mut other.containers);
+        self.components.append(&mut other.components);
+        self.relationships.append(&mut other.relationships);
+        self.flows.append(&mut other.flows);
+        self.deployments.append(&mut other.deployments);
+    }
+
+    /// Rewrites the file of every recorded source
+    pub fn map_source_files<F: Fn(&str) -> String>(&mut self, f: F) {
+        for location in self.sources.values_mut() {
+            location.file = f(&location.file);
+        }
+    }
+}
+
+/// Whether `path` is nested inside `ancestor`
+fn is_ancestor(ancestor: &str, path: &str) -> bool {
+    path.len() > ancestor.len()
+        && path.starts_with(ancestor)
+        && path.as_bytes()[ancestor.len()] == b'.'
 }
 
 #[cfg(test)]
@@ -476,6 +652,7 @@ mod tests {
             technology: None,
             tags: None,
             properties: None,
+            implied: false,
         };
         model.relationships.push(rel);
 
@@ -675,6 +852,7 @@ mod tests {
             technology: None,
             tags: None,
             properties: None,
+            implied: false,
         };
         let rel2 = Relationship {
             from: "user1".to_string(),
@@ -683,6 +861,7 @@ mod tests {
             technology: None,
             tags: None,
             properties: None,
+            implied: false,
         };
         model.relationships.push(rel1);
         model.relationships.push(rel2);
@@ -707,6 +886,7 @@ mod tests {
             technology: None,
             tags: None,
             properties: None,
+            implied: false,
         };
         let rel2 = Relationship {
             from: "user2".to_string(),
@@ -715,6 +895,7 @@ mod tests {
             technology: None,
             tags: None,
             properties: None,
+            implied: false,
         };
         model.relationships.push(rel1);
         model.relationships.push(rel2);
@@ -725,6 +906,122 @@ mod tests {
         assert_eq!(rels.len(), 2);
     }
 
+    fn create_test_relationship(from: &str, to: &str, description: &str) -> Relationship {
+        Relationship {
+            from: from.to_string(),
+            to: to.to_string(),
+            description: Some(description.to_string()),
+            technology: None,
+            tags: None,
+            properties: None,
+            implied: false,
+        }
+    }
+
+    fn implied_pairs(model: &Model) -> Vec<(&str, &str)> {
+        model
+            .implied_relationships
+            .iter()
+            .map(|r| (r.from.as_str(), r.to.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_implied_relationships() {
+        let mut model = Model::new();
+        model.persons.push(create_test_person("user"));
+        model.systems.push(create_test_system("order"));
+        model.systems.push(create_test_system("payment"));
+        model.containers.push(create_test_container("order", "api"));
+        model
+            .containers
+            .push(create_test_container("payment", "api"));
+        model
+            .components
+            .push(create_test_component("order", "api", "handler"));
+        model.relationships.push(create_test_relationship(
+            "order.api.handler",
+            "payment.api",
+            "Charges cards",
+        ));
+        model.relationships.push(create_test_relationship(
+            "user",
+            "order.api",
+            "Places orders",
+        ));
+        model.build_indexes().unwrap();
+
+        assert_eq!(
+            implied_pairs(&model),
+            vec![
+                ("order.api.handler", "payment"),
+                ("order.api", "payment.api"),
+                ("order.api", "payment"),
+                ("order", "payment.api"),
+                ("order", "payment"),
+                ("user", "order"),
+            ]
+        );
+        assert!(model.implied_relationships.iter().all(|r| r.implied));
+
+        let rels = model.get_implied_outgoing_relationships("order");
+        assert_eq!(rels.len(), 2);
+        assert_eq!(rels[1].to, "payment");
+        assert_eq!(rels[1].description.as_deref(), Some("Charges cards"));
+        assert_eq!(model.get_implied_incoming_relationships("payment").len(), 3);
+        // Explicit relationships are not implied
+        assert_eq!(model.get_outgoing_relationships("order").len(), 0);
+    }
+
+    #[test]
+    fn test_implied_relationships_skip_explicit_and_internal() {
+        let mut model = Model::new();
+        model.systems.push(create_test_system("order"));
+        model.systems.push(create_test_system("payment"));
+        model.containers.push(create_test_container("order", "api"));
+        model.containers.push(create_test_container("order", "db"));
+        model.relationships.push(create_test_relationship(
+            "order.api",
+            "order.db",
+            "Reads from",
+        ));
+        model
+            .relationships
+            .push(create_test_relationship("order.api", "payment", "Charges"));
+        model
+            .relationships
+            .push(create_test_relationship("order", "payment", "Pays through"));
+        model.relationships.push(create_test_relationship(
+            "order.db",
+            "payment",
+            "Replicates to",
+        ));
+        model.build_indexes().unwrap();
+
+        // order.api -> order.db stays inside order; order -> payment is declared
+        assert!(implied_pairs(&model).is_empty());
+
+        let json = serde_json::to_value(&model).unwrap();
+        assert!(json.get("impliedRelationships").is_none());
+    }
+
+    #[test]
+    fn test_implied_relationships_serialized() {
+        let mut model = Model::new();
+        model.systems.push(create_test_system("order"));
+        model.systems.push(create_test_system("payment"));
+        model.containers.push(create_test_container("order", "api"));
+        model
+            .relationships
+            .push(create_test_relationship("order.api", "payment", "Charges"));
+        model.build_indexes().unwrap();
+
+        let json = serde_json::to_value(&model).unwrap();
+        assert_eq!(json["impliedRelationships"][0]["from"], "order");
+        assert_eq!(json["impliedRelationships"][0]["implied"], true);
+        assert!(json["relationships"][0].get("implied").is_none());
+    }
+
     #[test]
     fn test_get_relationships_empty() {
         let mut model = Model::new();
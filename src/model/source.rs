@@ -0,0 +1,78 @@
+use super::ElementType;
+use std::fmt;
+
+/// Where a model item was defined: a file relative to the workspace root and
+/// a 1-based line and column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceLocation {
+    pub fn new(file: impl Into<String>, line: usize, column: usize) -> Self {
+        Self {
+            file: file.into(),
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Identifies a model item by its position in the model's vectors
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SourceKey {
+    /// Index into the persons, systems, containers or components vector
+    Element(ElementType, usize),
+    Relationship(usize),
+    Flow(usize),
+    /// Flow index and step index
+    FlowStep(usize, usize),
+    Deployment(usize),
+    /// Deployment index and the child indexes leading to the node
+    DeploymentNode(usize, Vec<usize>),
+}
+
+impl SourceKey {
+    /// Shifts the key's top-level index by the given vector offsets, used when
+    /// one model's items are appended to another
+    pub fn offset(self, offsets: &SourceOffsets) -> Self {
+        match self {
+            SourceKey::Element(t, i) => {
+                let base = match t {
+                    ElementType::Person => offsets.persons,
+                    ElementType::System => offsets.systems,
+                    ElementType::Container => offsets.containers,
+                    ElementType::Component => offsets.components,
+                };
+                SourceKey::Element(t, base + i)
+            }
+            SourceKey::Relationship(i) => SourceKey::Relationship(offsets.relationships + i),
+            SourceKey::Flow(i) => SourceKey::Flow(offsets.flows + i),
+            SourceKey::FlowStep(i, s) => SourceKey::FlowStep(offsets.flows + i, s),
+            SourceKey::Deployment(i) => SourceKey::Deployment(offsets.deployments + i),
+            SourceKey::DeploymentNode(i, path) => {
+                SourceKey::DeploymentNode(offsets.deployments + i, path)
+            }
+        }
+    }
+}
+
+/// Vector lengths of a model before another model is appended to it
+#[derive(Debug, Clone, Default)]
+pub struct SourceOffsets {
+    pub persons: usize,
+    pub systems: usize,
+    pub containers: usize,
+    pub components: usize,
+    pub relationships: usize,
+    pub flows: usize,
+    pub deployments: usize,
+}
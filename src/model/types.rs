@@ -295,6 +295,13 @@ pub struct Relationship {
     pub tags: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub properties: Option<HashMap<String, serde_json::Value>>,
+    /// Derived from a relationship between descendants rather than declared
+    #[serde(
+        default,
+        skip_deserializing,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub implied: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -699,9 +706,11 @@ mod tests {
             technology: Some(Technology::new(vec!["HTTPS".to_string()])),
             tags: Some(vec!["external".to_string()]),
             properties: Some(HashMap::new()),
+            implied: false,
         };
 
         let json = serde_json::to_value(&rel).unwrap();
+        assert!(json.get("implied").is_none());
         assert_eq!(json["from"], "user1");
         assert_eq!(json["to"], "sys1");
         assert_eq!(json["description"], "Uses");
@@ -1,4 +1,7 @@
 use crate::model::*;
+use crate::parser::layout::default_layout;
+use crate::parser::lint::Level;
+use crate::parser::rules::ArchitectureRule;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -14,6 +17,15 @@ pub struct ModFile {
     pub imports: HashMap<String, Import>,
     #[serde(default)]
     pub options: Options,
+    /// Level of each lint rule, overriding the default of `warn`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub lint: HashMap<String, Level>,
+    /// Architecture rules checked by `c4 validate`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<ArchitectureRule>,
+    /// Patterns that give files a system and container context
+    #[serde(default = "default_layout")]
+    pub layout: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
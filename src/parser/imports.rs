@@ -0,0 +1,499 @@
+use crate::model::{self, DeploymentNode};
+use crate::parser::file::Import;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+/// Directory, relative to the workspace root, where git imports are checked out
+pub const CACHE_DIR: &str = ".c4/cache";
+
+/// Separator between an import alias and the IDs of its elements
+pub const NAMESPACE_SEPARATOR: char = '/';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportSource {
+    Local(PathBuf),
+    Git(String),
+}
+
+impl ImportSource {
+    /// Classifies an import source as a git remote or a local directory.
+    /// Local paths are resolved against the workspace root.
+    pub fn parse(source: &str, root_dir: &Path) -> Self {
+        if let Some(url) = source.strip_prefix("git+") {
+            return ImportSource::Git(url.to_string());
+        }
+        if source.starts_with("https://")
+            || source.starts_with("http://")
+            || source.starts_with("ssh://")
+            || source.starts_with("git://")
+            || source.starts_with("git@")
+            || source.ends_with(".git")
+        {
+            return ImportSource::Git(source.to_string());
+        }
+        ImportSource::Local(root_dir.join(source))
+    }
+}
+
+pub struct Importer {
+    root_dir: PathBuf,
+    cache_dir: PathBuf,
+}
+
+impl Importer {
+    pub fn new<P: AsRef<Path>>(root_dir: P) -> Self {
+        let root_dir = root_dir.as_ref().to_path_buf();
+        let cache_dir = root_dir.join(CACHE_DIR);
+        Self {
+            root_dir,
+            cache_dir,
+        }
+    }
+
+    pub fn with_cache_dir<P: AsRef<Path>>(mut self, cache_dir: P) -> Self {
+        self.cache_dir = cache_dir.as_ref().to_path_buf();
+        self
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Returns the directory holding the imported workspace's c4.mod.yaml,
+    /// cloning git sources into the cache on first use
+    pub fn resolve(&self, alias: &str, import: &Import) -> Result<PathBuf> {
+        self.resolve_with(alias, import, None)
+    }
+
+    /// Like `resolve`, but checks git sources out at `commit` instead of
+    /// the declared ref. Local sources ignore the commit.
+    pub fn resolve_at(&self, alias: &str, import: &Import, commit: &str) -> Result<PathBuf> {
+        self.resolve_with(alias, import, Some(commit))
+    }
+
+    /// Discards any cached checkout of a git import and resolves it again,
+    /// picking up the latest commit of its ref
+    pub fn refresh(&self, alias: &str, import: &Import) -> Result<PathBuf> {
+        if let ImportSource::Git(_) = ImportSource::parse(&import.source, &self.root_dir) {
+            let dir = self.checkout_dir(alias, import.ref_.as_deref());
+            if dir.exists() {
+                fs::remove_dir_all(&dir)
+                    .with_context(|| format!("Failed to clear import cache {:?}", dir))?;
+            }
+        }
+        self.resolve(alias, import)
+    }
+
+    fn resolve_with(&self, alias: &str, import: &Import, commit: Option<&str>) -> Result<PathBuf> {
+        if import.source.is_empty() {
+            return Err(anyhow::anyhow!("import {:?} has no source", alias));
+        }
+
+        let base = match ImportSource::parse(&import.source, &self.root_dir) {
+            ImportSource::Local(dir) => {
+                if import.ref_.is_some() {
+                    return Err(anyhow::anyhow!(
+                        "import {:?}: ref is only supported for git sources",
+                        alias
+                    ));
+                }
+                dir
+            }
+            ImportSource::Git(url) => {
+                // Sources come from imported modules too, so nothing may reach
+                // git as an option
+                for (what, value) in [
+                    ("source", Some(url.as_str())),
+                    ("ref", import.ref_.as_deref()),
+                    ("commit", commit),
+                ] {
+                    if value.is_some_and(|v| v.starts_with('-')) {
+                        return Err(anyhow::anyhow!(
+                            "import {:?}: {} must not start with '-'",
+                            alias,
+                            what
+                        ));
+                    }
+                }
+                let dir = self.checkout(alias, &url, import.ref_.as_deref())?;
+                if let Some(commit) = commit {
+                    pin(&dir, alias, commit)?;
+                }
+                dir
+            }
+        };
+
+        let dir = match &import.path {
+            Some(sub) => base.join(relative_subpath(sub)?),
+            None => base,
+        };
+
+        if !dir.join("c4.mod.yaml").exists() {
+            return Err(anyhow::anyhow!(
+                "import {:?}: no c4.mod.yaml found in {:?}",
+                alias,
+                dir
+            ));
+        }
+
+        Ok(dir)
+    }
+
+    /// Returns the cache directory used for a git import
+    pub fn checkout_dir(&self, alias: &str, ref_: Option<&str>) -> PathBuf {
+        let ref_dir = ref_.map(sanitize).unwrap_or_else(|| "default".to_string());
+        self.cache_dir.join(sanitize(alias)).join(ref_dir)
+    }
+
+    fn checkout(&self, alias: &str, url: &str, ref_: Option<&str>) -> Result<PathBuf> {
+        let dir = self.checkout_dir(alias, ref_);
+
+        if dir.join(".git").exists() {
+            let origin = git(&dir, &["remote", "get-url", "origin"]).unwrap_or_default();
+            if origin == url {
+                return Ok(dir);
+            }
+            fs::remove_dir_all(&dir)
+                .with_context(|| format!("Failed to clear stale import cache {:?}", dir))?;
+        }
+
+        if let Some(parent) = dir.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create import cache {:?}", parent))?;
+        }
+
+        let dir_str = dir.to_string_lossy().to_string();
+        git(&self.root_dir, &["clone", "--quiet", "--", url, &dir_str])
+            .with_context(|| format!("import {:?}: failed to clone {}", alias, url))?;
+
+        if let Some(r) = ref_ {
+            git(&dir, &["checkout", "--quiet", r, "--"])
+                .with_context(|| format!("import {:?}: failed to check out {:?}", alias, r))?;
+        }
+
+        Ok(dir)
+    }
+}
+
+/// Checks out `commit` in an existing clone, fetching if it is not yet known
+fn pin(dir: &Path, alias: &str, commit: &str) -> Result<()> {
+    if git(dir, &["rev-parse", "HEAD"]).unwrap_or_default() == commit {
+        return Ok(());
+    }
+    if git(dir, &["checkout", "--quiet", commit, "--"]).is_ok() {
+        return Ok(());
+    }
+    git(dir, &["fetch", "--quiet", "origin"])
+        .with_context(|| format!("import {:?}: failed to fetch", alias))?;
+    git(dir, &["checkout", "--quiet", commit, "--"])
+        .with_context(|| format!("import {:?}: locked commit {} not found", alias, commit))?;
+    Ok(())
+}
+
+/// Runs git in `dir` and returns its trimmed stdout
+pub fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Prefixes every element ID of an imported model with `<alias>/` and
+/// rewrites all references to match
+pub fn namespace_model(model: &mut model::Model, alias: &str) {
+    let ns = |id: &str| format!("{}{}{}", alias, NAMESPACE_SEPARATOR, id);
+
+    for p in &mut model.persons {
+        p.base.id = ns(&p.base.id);
+    }
+    for s in &mut model.systems {
+        s.base.id = ns(&s.base.id);
+    }
+    for c in &mut model.containers {
+        c.system_id = ns(&c.system_id);
+    }
+    for c in &mut model.components {
+        c.system_id = ns(&c.system_id);
+    }
+    for r in &mut model.relationships {
+        r.from = ns(&r.from);
+        r.to = ns(&r.to);
+    }
+    for f in &mut model.flows {
+        f.id = ns(&f.id);
+        for step in &mut f.steps {
+            step.from = ns(&step.from);
+            step.to = ns(&step.to);
+        }
+    }
+    for d in &mut model.deployments {
+        d.id = ns(&d.id);
+        if let Some(nodes) = &mut d.nodes {
+            namespace_nodes(nodes, &ns);
+        }
+    }
+}
+
+fn namespace_nodes(nodes: &mut [DeploymentNode], ns: &dyn Fn(&str) -> String) {
+    for node in nodes {
+        if let Some(instances) = &mut node.instances {
+            for inst in instances {
+                inst.container = ns(&inst.container);
+            }
+        }
+        if let Some(children) = &mut node.children {
+            namespace_nodes(children, ns);
+        }
+    }
+}
+
+/// Interprets an import `path` relative to the import source, rejecting
+/// paths that escape it
+fn relative_subpath(path: &str) -> Result<PathBuf> {
+    let mut result = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => result.push(part),
+            Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+            Component::ParentDir => {
+                return Err(anyhow::anyhow!(
+                    "import path {:?} must not contain '..'",
+                    path
+                ))
+            }
+        }
+    }
+    Ok(result)
+}
+
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{BaseElement, Container, ElementType, Relationship, SoftwareSystem};
+    use tempfile::TempDir;
+
+    fn write_workspace(dir: &Path) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: shared\ninclude:\n  - \"model.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("model.yaml"),
+            "systems:\n  - id: auth\n    name: Auth\n",
+        )
+        .unwrap();
+    }
+
+    fn import(source: &str) -> Import {
+        Import {
+            source: source.to_string(),
+            ref_: None,
+            path: None,
+        }
+    }
+
+    #[test]
+    fn test_import_source_parse() {
+        let root = Path::new("/ws");
+        assert_eq!(
+            ImportSource::parse("https://github.com/acme/model.git", root),
+            ImportSource::Git("https://github.com/acme/model.git".to_string())
+        );
+        assert_eq!(
+            ImportSource::parse("git@github.com:acme/model", root),
+            ImportSource::Git("git@github.com:acme/model".to_string())
+        );
+        assert_eq!(
+            ImportSource::parse("git+file:///srv/model", root),
+            ImportSource::Git("file:///srv/model".to_string())
+        );
+        assert_eq!(
+            ImportSource::parse("../shared", root),
+            ImportSource::Local(PathBuf::from("/ws/../shared"))
+        );
+    }
+
+    #[test]
+    fn test_resolve_local_import() {
+        let temp = TempDir::new().unwrap();
+        write_workspace(&temp.path().join("shared"));
+        fs::create_dir_all(temp.path().join("ws")).unwrap();
+
+        let importer = Importer::new(temp.path().join("ws"));
+        let dir = importer.resolve("platform", &import("../shared")).unwrap();
+        assert!(dir.join("c4.mod.yaml").exists());
+    }
+
+    #[test]
+    fn test_resolve_local_import_with_path() {
+        let temp = TempDir::new().unwrap();
+        write_workspace(&temp.path().join("shared/models/core"));
+
+        let importer = Importer::new(temp.path());
+        let mut imp = import("shared");
+        imp.path = Some("/models/core".to_string());
+        let dir = importer.resolve("platform", &imp).unwrap();
+        assert!(dir.ends_with("models/core"));
+    }
+
+    #[test]
+    fn test_resolve_missing_mod_file() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("empty")).unwrap();
+
+        let importer = Importer::new(temp.path());
+        let result = importer.resolve("platform", &import("empty"));
+        assert!(result.unwrap_err().to_string().contains("no c4.mod.yaml"));
+    }
+
+    #[test]
+    fn test_resolve_rejects_ref_for_local_source() {
+        let temp = TempDir::new().unwrap();
+        write_workspace(&temp.path().join("shared"));
+
+        let importer = Importer::new(temp.path());
+        let mut imp = import("shared");
+        imp.ref_ = Some("main".to_string());
+        assert!(importer.resolve("platform", &imp).is_err());
+    }
+
+    #[test]
+    fn test_resolve_git_import() {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        write_workspace(&repo);
+        git(&repo, &["init", "--quiet"]).unwrap();
+        git(&repo, &["add", "."]).unwrap();
+        git(
+            &repo,
+            &[
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "init",
+            ],
+        )
+        .unwrap();
+        git(&repo, &["tag", "v1"]).unwrap();
+
+        let ws = temp.path().join("ws");
+        fs::create_dir_all(&ws).unwrap();
+        let importer = Importer::new(&ws);
+        let mut imp = import(&format!("git+file://{}", repo.display()));
+        imp.ref_ = Some("v1".to_string());
+
+        let dir = importer.resolve("platform", &imp).unwrap();
+        assert_eq!(dir, ws.join(".c4/cache/platform/v1"));
+        assert!(dir.join("model.yaml").exists());
+
+        // Second resolution reuses the checkout
+        assert_eq!(importer.resolve("platform", &imp).unwrap(), dir);
+    }
+
+    #[test]
+    fn test_resolve_rejects_git_options() {
+        let temp = TempDir::new().unwrap();
+        let importer = Importer::new(temp.path());
+
+        let imp = import("git+--upload-pack=touch pwned");
+        let err = importer.resolve("platform", &imp).unwrap_err();
+        assert!(err.to_string().contains("source must not start with '-'"));
+
+        let mut imp = import("https://example.com/repo.git");
+        imp.ref_ = Some("--output=/tmp/x".to_string());
+        let err = importer.resolve("platform", &imp).unwrap_err();
+        assert!(err.to_string().contains("ref must not start with '-'"));
+
+        let err = importer
+            .resolve_at("platform", &import("https://example.com/repo.git"), "-x")
+            .unwrap_err();
+        assert!(err.to_string().contains("commit must not start with '-'"));
+        assert!(!temp.path().join("pwned").exists());
+    }
+
+    #[test]
+    fn test_relative_subpath_rejects_parent() {
+        assert!(relative_subpath("../outside").is_err());
+        assert_eq!(
+            relative_subpath("/models/shared").unwrap(),
+            PathBuf::from("models/shared")
+        );
+    }
+
+    #[test]
+    fn test_namespace_model() {
+        let mut model = model::Model::new();
+        model.systems.push(SoftwareSystem {
+            base: BaseElement {
+                id: "auth".to_string(),
+                name: "Auth".to_string(),
+                description: None,
+                tags: None,
+                properties: None,
+            },
+            element_type: ElementType::System,
+            external: None,
+        });
+        model.containers.push(Container {
+            base: BaseElement {
+                id: "api".to_string(),
+                name: "API".to_string(),
+                description: None,
+                tags: None,
+                properties: None,
+            },
+            element_type: ElementType::Container,
+            technology: None,
+            system_id: "auth".to_string(),
+        });
+        model.relationships.push(Relationship {
+            from: "auth.api".to_string(),
+            to: "auth".to_string(),
+            description: None,
+            technology: None,
+            tags: None,
+            properties: None,
+            implied: false,
+        });
+
+        namespace_model(&mut model, "platform");
+
+        assert_eq!(model.systems[0].base.id, "platform/auth");
+        assert_eq!(model.containers[0].system_id, "platform/auth");
+        assert_eq!(model.relationships[0].from, "platform/auth.api");
+        model.build_indexes().unwrap();
+        assert!(model.get_element("platform/auth.api").is_some());
+    }
+}
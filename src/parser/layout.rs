@@ -0,0 +1,190 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+
+/// Layout used when the mod file does not declare one. A `systems`
+/// directory at any depth counts, the first one when they nest.
+pub const DEFAULT_LAYOUT: &[&str] = &[
+    "**/systems/{system}/containers/{container}/**/*",
+    "**/systems/{system}/**/*",
+];
+
+const CAPTURES: &[&str] = &["system", "container"];
+
+pub fn default_layout() -> Vec<String> {
+    DEFAULT_LAYOUT.iter().map(|p| p.to_string()).collect()
+}
+
+/// Glob patterns that give files a system and container context. `{system}`
+/// and `{container}` capture one path segment each; `*`, `?` and `**` match
+/// like in `include`. The first matching pattern wins.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    patterns: Vec<Regex>,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::new(&default_layout()).expect("default layout is valid")
+    }
+}
+
+impl Layout {
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let patterns = patterns.iter().map(|p| compile(p)).collect::<Result<_>>()?;
+        Ok(Self { patterns })
+    }
+
+    /// System and container captured from `path`, relative to the workspace
+    /// root with `/` separators. Either is empty when not captured.
+    pub fn context(&self, path: &str) -> (String, String) {
+        let Some(caps) = self.patterns.iter().find_map(|p| p.captures(path)) else {
+            return (String::new(), String::new());
+        };
+        let capture = |name| caps.name(name).map_or("", |m| m.as_str()).to_string();
+        (capture("system"), capture("container"))
+    }
+}
+
+fn compile(pattern: &str) -> Result<Regex> {
+    let mut regex = String::from("^");
+    let mut captures: Vec<&str> = Vec::new();
+    let mut rest = pattern;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            regex.push_str("(?:[^/]+/)*?");
+            rest = after;
+        } else if rest == "**" {
+            regex.push_str(".*");
+            rest = "";
+        } else if c == '*' {
+            regex.push_str("[^/]*");
+            rest = &rest[1..];
+        } else if c == '?' {
+            regex.push_str("[^/]");
+            rest = &rest[1..];
+        } else if c == '{' {
+            let end = rest
+                .find('}')
+                .ok_or_else(|| anyhow!("layout pattern {:?} has an unclosed '{{'", pattern))?;
+            let name = &rest[1..end];
+            if !CAPTURES.contains(&name) {
+                return Err(anyhow!(
+                    "layout pattern {:?} has unknown capture {{{}}}; use {{system}} or {{container}}",
+                    pattern,
+                    name
+                ));
+            }
+            if captures.contains(&name) {
+                return Err(anyhow!(
+                    "layout pattern {:?} captures {{{}}} more than once",
+                    pattern,
+                    name
+                ));
+            }
+            captures.push(name);
+            regex.push_str(&format!("(?P<{}>[^/]+)", name));
+            rest = &rest[end + 1..];
+        } else {
+            regex.push_str(&regex::escape(&c.to_string()));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    regex.push('$');
+    Regex::new(&regex).map_err(|e| anyhow!("invalid layout pattern {:?}: {}", pattern, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(patterns: &[&str]) -> Layout {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        Layout::new(&patterns).unwrap()
+    }
+
+    #[test]
+    fn test_default_layout() {
+        let layout = Layout::default();
+        assert_eq!(
+            layout.context("systems/shop/containers.yaml"),
+            ("shop".to_string(), String::new())
+        );
+        assert_eq!(
+            layout.context("systems/shop/containers/api/components.yaml"),
+            ("shop".to_string(), "api".to_string())
+        );
+        assert_eq!(
+            layout.context("systems/shop/containers/api/more/components.yaml"),
+            ("shop".to_string(), "api".to_string())
+        );
+        // Files directly in a directory named by a capture have no context
+        assert_eq!(
+            layout.context("systems/shop/containers/api.yaml"),
+            ("shop".to_string(), String::new())
+        );
+        assert_eq!(
+            layout.context("systems/model.yaml"),
+            (String::new(), String::new())
+        );
+        assert_eq!(
+            layout.context("shared/model.yaml"),
+            (String::new(), String::new())
+        );
+    }
+
+    #[test]
+    fn test_default_layout_nested_systems_directory() {
+        let layout = Layout::default();
+        assert_eq!(
+            layout.context("arch/systems/shop/containers.yaml"),
+            ("shop".to_string(), String::new())
+        );
+        assert_eq!(
+            layout.context("arch/systems/shop/containers/api/components.yaml"),
+            ("shop".to_string(), "api".to_string())
+        );
+        // The outermost systems directory wins
+        assert_eq!(
+            layout.context("systems/shop/docs/systems/other/model.yaml"),
+            ("shop".to_string(), String::new())
+        );
+    }
+
+    #[test]
+    fn test_custom_layout() {
+        let layout = layout(&[
+            "services/{system}/architecture/*.yaml",
+            "**/svc-{container}/c4.yaml",
+        ]);
+        assert_eq!(
+            layout.context("services/orders/architecture/model.yaml"),
+            ("orders".to_string(), String::new())
+        );
+        assert_eq!(
+            layout.context("services/orders/docs/model.yaml"),
+            (String::new(), String::new())
+        );
+        assert_eq!(
+            layout.context("deploy/svc-api/c4.yaml"),
+            (String::new(), "api".to_string())
+        );
+        assert_eq!(
+            layout.context("svc-api/c4.yaml"),
+            (String::new(), "api".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_layout_patterns() {
+        let err = Layout::new(&["services/{service}/*.yaml".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("unknown capture {service}"));
+
+        let err = Layout::new(&["{system}/{system}/*.yaml".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("more than once"));
+
+        let err = Layout::new(&["services/{system/*.yaml".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("unclosed"));
+    }
+}
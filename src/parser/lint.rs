@@ -0,0 +1,393 @@
+use crate::model::{self, DeploymentNode, Element, ElementType, SourceKey};
+use crate::parser::resolver::ValidationError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How findings of a lint rule are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Off,
+    Warn,
+    Error,
+}
+
+pub struct Rule {
+    pub id: &'static str,
+    pub description: &'static str,
+}
+
+/// Built-in rules, all reported as warnings unless configured otherwise
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "orphan-element",
+        description: "element has no relationships",
+    },
+    Rule {
+        id: "missing-description",
+        description: "software system has no description",
+    },
+    Rule {
+        id: "missing-technology",
+        description: "relationship has no technology",
+    },
+    Rule {
+        id: "flow-sequence",
+        description: "flow steps are not numbered 1 to N",
+    },
+    Rule {
+        id: "empty-deployment",
+        description: "deployment has no container instances",
+    },
+    Rule {
+        id: "dependency-cycle",
+        description: "elements depend on each other in a cycle",
+    },
+];
+
+/// A lint finding together with the rule that produced it
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub rule: String,
+    pub level: Level,
+    pub error: ValidationError,
+}
+
+pub struct Linter<'a> {
+    model: &'a model::Model,
+    levels: &'a HashMap<String, Level>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Linter<'a> {
+    /// Creates a linter using the per-rule levels from the mod file
+    pub fn new(model: &'a model::Model, levels: &'a HashMap<String, Level>) -> Self {
+        Self {
+            model,
+            levels,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn lint(&mut self) -> Vec<Diagnostic> {
+        self.diagnostics.clear();
+
+        self.check_orphans();
+
+        for (i, system) in self.model.systems.iter().enumerate() {
+            if system.get_description().trim().is_empty() {
+                let key = SourceKey::Element(ElementType::System, i);
+                self.report(
+                    "missing-description",
+                    system.get_full_path(),
+                    "software system has no description",
+                    &key,
+                );
+            }
+        }
+
+        for (i, rel) in self.model.relationships.iter().enumerate() {
+            if rel.technology.as_ref().is_none_or(|t| t.is_empty()) {
+                self.report(
+                    "missing-technology",
+                    format!("relationship({} -> {})", rel.from, rel.to),
+                    "relationship has no technology",
+                    &SourceKey::Relationship(i),
+                );
+            }
+        }
+
+        for (i, flow) in self.model.flows.iter().enumerate() {
+            let mut seqs: Vec<i32> = flow.steps.iter().map(|s| s.seq).collect();
+            seqs.sort();
+            if seqs.iter().zip(1..).any(|(seq, expected)| *seq != expected) {
+                let numbers: Vec<String> = seqs.iter().map(|s| s.to_string()).collect();
+                self.report(
+                    "flow-sequence",
+                    format!("flow.{}", flow.id),
+                    format!(
+                        "steps are numbered {}; expected 1 to {}",
+                        numbers.join(", "),
+                        seqs.len()
+                    ),
+                    &SourceKey::Flow(i),
+                );
+            }
+        }
+
+        for (i, dep) in self.model.deployments.iter().enumerate() {
+            if !has_instances(dep.nodes.as_deref().unwrap_or_default()) {
+                self.report(
+                    "empty-deployment",
+                    format!("deployment.{}", dep.id),
+                    "deployment has no container instances",
+                    &SourceKey::Deployment(i),
+                );
+            }
+        }
+
+        for level in [
+            ElementType::System,
+            ElementType::Container,
+            ElementType::Component,
+        ] {
+            for cycle in self.model.find_cycles(level) {
+                let kind = match level {
+                    ElementType::System => "software systems",
+                    ElementType::Container => "containers",
+                    _ => "components",
+                };
+                self.report(
+                    "dependency-cycle",
+                    format!("cycle({})", cycle),
+                    format!("{} depend on each other in a cycle", kind),
+                    &SourceKey::Relationship(cycle.relationships[0]),
+                );
+            }
+        }
+
+        self.diagnostics.clone()
+    }
+
+    /// Flags elements that neither they nor their children take part in any
+    /// relationship
+    fn check_orphans(&mut self) {
+        let ends: Vec<&str> = self
+            .model
+            .relationships
+            .iter()
+            .flat_map(|r| [r.from.as_str(), r.to.as_str()])
+            .collect();
+        let connected = |path: &str| {
+            let prefix = format!("{}.", path);
+            ends.iter().any(|e| *e == path || e.starts_with(&prefix))
+        };
+
+        let mut orphans = Vec::new();
+        let groups: [(ElementType, Vec<String>); 4] = [
+            (ElementType::Person, paths(&self.model.persons)),
+            (ElementType::System, paths(&self.model.systems)),
+            (ElementType::Container, paths(&self.model.containers)),
+            (ElementType::Component, paths(&self.model.components)),
+        ];
+        for (element_type, paths) in groups {
+            for (i, path) in paths.into_iter().enumerate() {
+                if !connected(&path) {
+                    orphans.push((SourceKey::Element(element_type, i), path));
+                }
+            }
+        }
+
+        for (key, path) in orphans {
+            self.report("orphan-element", path, "element has no relationships", &key);
+        }
+    }
+
+    fn report(
+        &mut self,
+        rule: &str,
+        path: impl Into<String>,
+        message: impl Into<String>,
+        key: &SourceKey,
+    ) {
+        let level = self.levels.get(rule).copied().unwrap_or(Level::Warn);
+        if level == Level::Off {
+            return;
+        }
+        self.diagnostics.push(Diagnostic {
+            rule: rule.to_string(),
+            level,
+            error: ValidationError::new(path, message, self.model.source(key)),
+        });
+    }
+}
+
+fn paths<E: Element>(elements: &[E]) -> Vec<String> {
+    elements.iter().map(|e| e.get_full_path()).collect()
+}
+
+fn has_instances(nodes: &[DeploymentNode]) -> bool {
+    nodes.iter().any(|n| {
+        n.instances.as_ref().is_some_and(|i| !i.is_empty())
+            || has_instances(n.children.as_deref().unwrap_or_default())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DataFile;
+
+    fn create_model(yaml: &str) -> model::Model {
+        let data: DataFile = serde_yaml::from_str(yaml).unwrap();
+        let mut m = model::Model::new();
+        m.persons = data.persons;
+        m.systems = data.systems;
+        m.containers = data.containers;
+        m.components = data.components;
+        m.relationships = data.relationships;
+        m.flows = data.flows;
+        m.deployments = data.deployments;
+        m.build_indexes().unwrap();
+        m
+    }
+
+    fn rules(diagnostics: &[Diagnostic]) -> Vec<(&str, &str)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.rule.as_str(), d.error.path.as_str()))
+            .collect()
+    }
+
+    const MODEL: &str = r#"
+persons:
+  - id: customer
+    name: Customer
+  - id: auditor
+    name: Auditor
+systems:
+  - id: shop
+    name: Shop
+    description: Sells things
+  - id: crm
+    name: CRM
+containers:
+  - id: api
+    name: API
+    systemId: shop
+relationships:
+  - from: customer
+    to: shop.api
+    technology: HTTPS
+  - from: shop
+    to: crm
+flows:
+  - id: checkout
+    name: Checkout
+    steps:
+      - seq: 1
+        from: customer
+        to: shop.api
+      - seq: 3
+        from: shop
+        to: crm
+deployments:
+  - id: prod
+    name: Production
+    nodes:
+      - id: aws
+        name: AWS
+"#;
+
+    #[test]
+    fn test_lint_default_warnings() {
+        let model = create_model(MODEL);
+        let levels = HashMap::new();
+        let diagnostics = Linter::new(&model, &levels).lint();
+
+        assert_eq!(
+            rules(&diagnostics),
+            vec![
+                ("orphan-element", "auditor"),
+                ("missing-description", "crm"),
+                ("missing-technology", "relationship(shop -> crm)"),
+                ("flow-sequence", "flow.checkout"),
+                ("empty-deployment", "deployment.prod"),
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| d.level == Level::Warn));
+        assert_eq!(
+            diagnostics[3].error.message,
+            "steps are numbered 1, 3; expected 1 to 2"
+        );
+    }
+
+    #[test]
+    fn test_lint_levels_from_config() {
+        let model = create_model(MODEL);
+        let levels: HashMap<String, Level> =
+            serde_yaml::from_str("orphan-element: off\nmissing-technology: error\n").unwrap();
+        let diagnostics = Linter::new(&model, &levels).lint();
+
+        assert!(!diagnostics.iter().any(|d| d.rule == "orphan-element"));
+        let tech = diagnostics
+            .iter()
+            .find(|d| d.rule == "missing-technology")
+            .unwrap();
+        assert_eq!(tech.level, Level::Error);
+    }
+
+    #[test]
+    fn test_lint_deployment_with_nested_instances() {
+        let model = create_model(
+            r#"
+deployments:
+  - id: prod
+    name: Production
+    nodes:
+      - id: aws
+        name: AWS
+        children:
+          - id: eks
+            name: EKS
+            instances:
+              - container: shop.api
+"#,
+        );
+        let levels = HashMap::new();
+        assert!(Linter::new(&model, &levels).lint().is_empty());
+    }
+
+    #[test]
+    fn test_lint_dependency_cycles() {
+        let model = create_model(
+            r#"
+systems:
+  - id: shop
+    name: Shop
+    description: Sells things
+  - id: billing
+    name: Billing
+    description: Bills
+containers:
+  - id: api
+    name: API
+    systemId: shop
+  - id: api
+    name: API
+    systemId: billing
+relationships:
+  - from: shop.api
+    to: billing.api
+    technology: HTTPS
+  - from: billing.api
+    to: shop.api
+    technology: HTTPS
+"#,
+        );
+        let levels = HashMap::new();
+        let diagnostics = Linter::new(&model, &levels).lint();
+
+        assert_eq!(
+            rules(&diagnostics),
+            vec![
+                ("dependency-cycle", "cycle(billing -> shop -> billing)"),
+                (
+                    "dependency-cycle",
+                    "cycle(billing.api -> shop.api -> billing.api)"
+                ),
+            ]
+        );
+        assert_eq!(
+            diagnostics[1].error.message,
+            "containers depend on each other in a cycle"
+        );
+    }
+
+    #[test]
+    fn test_rule_ids_are_unique() {
+        for (i, rule) in RULES.iter().enumerate() {
+            assert!(RULES[i + 1..].iter().all(|r| r.id != rule.id));
+        }
+    }
+}
@@ -0,0 +1,475 @@
+use crate::model::SourceLocation;
+use std::fmt;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser as YamlParser};
+use yaml_rust2::scanner::Marker;
+
+/// A 1-based line and column in a YAML document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    fn from_marker(mark: Marker) -> Self {
+        Self {
+            line: mark.line(),
+            column: mark.col() + 1,
+        }
+    }
+}
+
+/// Positions of the items in a data file, in the same order as the
+/// corresponding `DataFile` vectors
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Locations {
+    pub persons: Vec<Position>,
+    pub systems: Vec<Position>,
+    pub containers: Vec<Position>,
+    pub components: Vec<Position>,
+    pub relationships: Vec<Position>,
+    pub flows: Vec<NestedPosition>,
+    pub deployments: Vec<NestedPosition>,
+}
+
+/// Position of an item together with those of its nested items: the steps of
+/// a flow, the nodes of a deployment or the children of a deployment node
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NestedPosition {
+    pub position: Position,
+    pub children: Vec<NestedPosition>,
+}
+
+impl Locations {
+    /// Scans a data file for item positions. Content that fails to scan
+    /// yields no positions; the YAML error is reported by deserialization.
+    pub fn scan(content: &str) -> Self {
+        let root = match Document::parse(content).root {
+            Some(root) => root,
+            None => return Self::default(),
+        };
+
+        let positions = |key: &str| -> Vec<Position> {
+            items(root.get(key)).iter().map(|i| i.position).collect()
+        };
+
+        Self {
+            persons: positions("persons"),
+            systems: positions("systems"),
+            containers: positions("containers"),
+            components: positions("components"),
+            relationships: positions("relationships"),
+            flows: items(root.get("flows"))
+                .iter()
+                .map(|flow| NestedPosition {
+                    position: flow.position,
+                    children: items(flow.get("steps"))
+                        .iter()
+                        .map(|step| NestedPosition {
+                            position: step.position,
+                            children: Vec::new(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            deployments: items(root.get("deployments"))
+                .iter()
+                .map(|deployment| NestedPosition {
+                    position: deployment.position,
+                    children: nodes(deployment.get("nodes")),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn items(node: Option<&Node>) -> &[Node] {
+    node.map(|n| n.items()).unwrap_or_default()
+}
+
+/// Positions of deployment nodes and, recursively, their children
+fn nodes(node: Option<&Node>) -> Vec<NestedPosition> {
+    items(node)
+        .iter()
+        .map(|item| NestedPosition {
+            position: item.position,
+            children: nodes(item.get("children")),
+        })
+        .collect()
+}
+
+/// A YAML document annotated with the position of every node
+#[derive(Debug, Default)]
+pub struct Document {
+    root: Option<Node>,
+}
+
+impl Document {
+    /// Parses `content`, yielding an empty document if it is not valid YAML
+    pub fn parse(content: &str) -> Self {
+        let mut builder = TreeBuilder::default();
+        let mut parser = YamlParser::new(content.chars());
+        if parser.load(&mut builder, false).is_err() {
+            return Self::default();
+        }
+        Self { root: builder.root }
+    }
+
+    /// Position of the value at `path`, given as mapping keys and sequence
+    /// indexes. Falls back to the closest ancestor that exists.
+    pub fn position<S: AsRef<str>>(&self, path: &[S]) -> Option<Position> {
+        let mut node = self.root.as_ref()?;
+        for segment in path {
+            let segment = segment.as_ref();
+            let child = match &node.kind {
+                NodeKind::Mapping(entries) => {
+                    entries.iter().find(|e| e.key == segment).map(|e| &e.value)
+                }
+                NodeKind::Sequence(items) => {
+                    segment.parse::<usize>().ok().and_then(|i| items.get(i))
+                }
+                NodeKind::Scalar(_) => None,
+            };
+            match child {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+        Some(node.position)
+    }
+
+    /// Position of `key` in the mapping at `path`
+    pub fn key_position<S: AsRef<str>>(&self, path: &[S], key: &str) -> Option<Position> {
+        let mut node = self.root.as_ref()?;
+        for segment in path {
+            let segment = segment.as_ref();
+            node = match &node.kind {
+                NodeKind::Mapping(entries) => &entries.iter().find(|e| e.key == segment)?.value,
+                NodeKind::Sequence(items) => items.get(segment.parse::<usize>().ok()?)?,
+                NodeKind::Scalar(_) => return None,
+            };
+        }
+        match &node.kind {
+            NodeKind::Mapping(entries) => entries.iter().find(|e| e.key == key).map(|e| e.position),
+            _ => None,
+        }
+    }
+
+    /// Positions of the scalars equal to `value` under `key` in the mapping
+    /// at `at` or any mapping nested in it
+    pub fn value_positions(&self, at: Position, key: &str, value: &str) -> Vec<Position> {
+        let mut positions = Vec::new();
+        if let Some(node) = self.root.as_ref().and_then(|root| root.find(at)) {
+            node.collect_values(key, value, &mut positions);
+        }
+        positions
+    }
+}
+
+/// A parse error tied to a location in a workspace file
+#[derive(Debug, Clone)]
+pub struct SourceError {
+    pub location: SourceLocation,
+    pub message: String,
+}
+
+impl SourceError {
+    pub fn new(location: SourceLocation, message: impl Into<String>) -> Self {
+        Self {
+            location,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+impl std::error::Error for SourceError {}
+
+#[derive(Debug)]
+struct Node {
+    position: Position,
+    kind: NodeKind,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Scalar(String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<Entry>),
+}
+
+#[derive(Debug)]
+struct Entry {
+    key: String,
+    // Position of the key itself
+    position: Position,
+    value: Node,
+}
+
+impl Node {
+    fn get(&self, key: &str) -> Option<&Node> {
+        match &self.kind {
+            NodeKind::Mapping(entries) => entries.iter().find(|e| e.key == key).map(|e| &e.value),
+            _ => None,
+        }
+    }
+
+    fn items(&self) -> &[Node] {
+        match &self.kind {
+            NodeKind::Sequence(items) => items,
+            _ => &[],
+        }
+    }
+
+    /// The outermost mapping starting at `position`
+    fn find(&self, position: Position) -> Option<&Node> {
+        match &self.kind {
+            NodeKind::Mapping(_) if self.position == position => Some(self),
+            NodeKind::Mapping(entries) => entries.iter().find_map(|e| e.value.find(position)),
+            NodeKind::Sequence(items) => items.iter().find_map(|i| i.find(position)),
+            NodeKind::Scalar(_) => None,
+        }
+    }
+
+    fn collect_values(&self, key: &str, value: &str, positions: &mut Vec<Position>) {
+        match &self.kind {
+            NodeKind::Mapping(entries) => {
+                for entry in entries {
+                    match &entry.value.kind {
+                        NodeKind::Scalar(s) if entry.key == key && s == value => {
+                            positions.push(entry.value.position)
+                        }
+                        _ => entry.value.collect_values(key, value, positions),
+                    }
+                }
+            }
+            NodeKind::Sequence(items) => {
+                for item in items {
+                    item.collect_values(key, value, positions);
+                }
+            }
+            NodeKind::Scalar(_) => {}
+        }
+    }
+}
+
+/// Builds a position-annotated tree from parser events
+#[derive(Default)]
+struct TreeBuilder {
+    stack: Vec<Node>,
+    // Pending mapping keys and their positions, one per open mapping
+    keys: Vec<Option<(String, Position)>>,
+    root: Option<Node>,
+}
+
+impl TreeBuilder {
+    fn push_value(&mut self, node: Node) {
+        let parent = match self.stack.last_mut() {
+            Some(parent) => parent,
+            None => {
+                if self.root.is_none() {
+                    self.root = Some(node);
+                }
+                return;
+            }
+        };
+
+        match &mut parent.kind {
+            NodeKind::Sequence(items) => items.push(node),
+            NodeKind::Mapping(entries) => {
+                let pending = self.keys.last_mut().expect("mapping has a key slot");
+                match pending.take() {
+                    Some((key, position)) => entries.push(Entry {
+                        key,
+                        position,
+                        value: node,
+                    }),
+                    None => {
+                        // Block mapping events are marked at the first ':',
+                        // so anchor the mapping at its first key instead
+                        if entries.is_empty() {
+                            parent.position = node.position;
+                        }
+                        let key = match node.kind {
+                            NodeKind::Scalar(s) => s,
+                            // Complex keys are not used by data files
+                            _ => String::new(),
+                        };
+                        *pending = Some((key, node.position));
+                    }
+                }
+            }
+            NodeKind::Scalar(_) => {}
+        }
+    }
+}
+
+impl MarkedEventReceiver for TreeBuilder {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        let position = Position::from_marker(mark);
+        match ev {
+            Event::Scalar(value, ..) => self.push_value(Node {
+                position,
+                kind: NodeKind::Scalar(value),
+            }),
+            Event::Alias(_) => self.push_value(Node {
+                position,
+                kind: NodeKind::Scalar(String::new()),
+            }),
+            Event::SequenceStart(..) => self.stack.push(Node {
+                position,
+                kind: NodeKind::Sequence(Vec::new()),
+            }),
+            Event::MappingStart(..) => {
+                self.keys.push(None);
+                self.stack.push(Node {
+                    position,
+                    kind: NodeKind::Mapping(Vec::new()),
+                });
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                if let Some(node) = self.stack.pop() {
+                    if let NodeKind::Mapping(_) = node.kind {
+                        self.keys.pop();
+                    }
+                    self.push_value(node);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+
+    #[test]
+    fn test_scan_element_positions() {
+        let content = r#"# Systems
+systems:
+  - id: shop
+    name: Shop
+
+  - id: billing
+    name: Billing
+containers:
+- id: api
+  name: API
+relationships: [{from: a, to: b}]
+"#;
+        let locations = Locations::scan(content);
+
+        assert_eq!(locations.systems, vec![pos(3, 5), pos(6, 5)]);
+        assert_eq!(locations.containers, vec![pos(9, 3)]);
+        assert_eq!(locations.relationships, vec![pos(11, 18)]);
+        assert!(locations.persons.is_empty());
+    }
+
+    #[test]
+    fn test_scan_flow_steps() {
+        let content = r#"
+flows:
+  - id: checkout
+    name: Checkout
+    steps:
+      - seq: 1
+        from: a
+        to: b
+      - seq: 2
+        from: b
+        to: c
+"#;
+        let locations = Locations::scan(content);
+
+        assert_eq!(locations.flows.len(), 1);
+        assert_eq!(locations.flows[0].position, pos(3, 5));
+        let steps: Vec<Position> = locations.flows[0]
+            .children
+            .iter()
+            .map(|s| s.position)
+            .collect();
+        assert_eq!(steps, vec![pos(6, 9), pos(9, 9)]);
+    }
+
+    #[test]
+    fn test_scan_nested_deployment_nodes() {
+        let content = r#"
+deployments:
+  - id: prod
+    name: Production
+    nodes:
+      - id: aws
+        name: AWS
+        children:
+          - id: eks
+            name: EKS
+            children:
+              - id: pod
+                name: Pod
+"#;
+        let locations = Locations::scan(content);
+
+        let aws = &locations.deployments[0].children[0];
+        assert_eq!(aws.position, pos(6, 9));
+        let eks = &aws.children[0];
+        assert_eq!(eks.position, pos(9, 13));
+        assert_eq!(eks.children[0].position, pos(12, 17));
+    }
+
+    #[test]
+    fn test_document_positions() {
+        let doc = Document::parse("containers:\n  - id: api\n    name: API\n    technology: 5\n");
+
+        assert_eq!(doc.position(&["containers", "0"]), Some(pos(2, 5)));
+        assert_eq!(
+            doc.position(&["containers", "0", "technology"]),
+            Some(pos(4, 17))
+        );
+        // Missing segments resolve to the closest ancestor
+        assert_eq!(doc.position(&["containers", "0", "nope"]), Some(pos(2, 5)));
+        assert_eq!(
+            doc.key_position(&["containers", "0"], "technology"),
+            Some(pos(4, 5))
+        );
+        assert_eq!(doc.key_position(&["containers", "0"], "nope"), None);
+    }
+
+    #[test]
+    fn test_document_value_positions() {
+        let content = r#"relationships:
+  - from: shop.api
+    to: "shop.db"
+nodes:
+  - id: k8s
+    instances:
+      - container: shop.db
+"#;
+        let doc = Document::parse(content);
+
+        assert_eq!(
+            doc.value_positions(pos(2, 5), "to", "shop.db"),
+            vec![pos(3, 9)]
+        );
+        assert_eq!(
+            doc.value_positions(pos(5, 5), "container", "shop.db"),
+            vec![pos(7, 20)]
+        );
+        assert!(doc.value_positions(pos(2, 5), "from", "shop").is_empty());
+        assert!(doc.value_positions(pos(9, 1), "to", "shop.db").is_empty());
+    }
+
+    #[test]
+    fn test_scan_invalid_yaml() {
+        assert_eq!(Locations::scan("systems: [\n"), Locations::default());
+    }
+}
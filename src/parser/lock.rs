@@ -0,0 +1,384 @@
+use crate::parser::file::{Import, ModFile};
+use crate::parser::imports::{git, ImportSource, Importer};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the lockfile, stored next to c4.mod.yaml
+pub const LOCK_FILE: &str = "c4.lock.yaml";
+
+const MOD_FILE: &str = "c4.mod.yaml";
+
+const LOCK_VERSION: &str = "1";
+
+const HEADER: &str = "# Generated by `c4 mod update`. Do not edit by hand.\n";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockFile {
+    pub version: String,
+    #[serde(default)]
+    pub imports: BTreeMap<String, LockEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockEntry {
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "ref")]
+    pub ref_: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Commit the git checkout resolved to; absent for local imports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Content hash of the imported workspace
+    pub hash: String,
+}
+
+impl Default for LockFile {
+    fn default() -> Self {
+        Self {
+            version: LOCK_VERSION.to_string(),
+            imports: BTreeMap::new(),
+        }
+    }
+}
+
+impl LockFile {
+    pub fn path(root_dir: &Path) -> PathBuf {
+        root_dir.join(LOCK_FILE)
+    }
+
+    /// Reads the workspace lockfile, returning `None` when there is none
+    pub fn load(root_dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(root_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let data = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read lock file: {:?}", path))?;
+        let lock: LockFile = serde_yaml::from_str(&data)
+            .with_context(|| format!("Invalid YAML in lock file: {:?}", path))?;
+
+        if lock.version != LOCK_VERSION {
+            return Err(anyhow::anyhow!(
+                "Unsupported lock file version {:?} in {:?}",
+                lock.version,
+                path
+            ));
+        }
+
+        Ok(Some(lock))
+    }
+
+    pub fn save(&self, root_dir: &Path) -> Result<()> {
+        let path = Self::path(root_dir);
+        let yaml = serde_yaml::to_string(self).context("Failed to serialize lock file")?;
+        fs::write(&path, format!("{}{}", HEADER, yaml))
+            .with_context(|| format!("Failed to write lock file: {:?}", path))
+    }
+}
+
+impl LockEntry {
+    /// Whether the entry was recorded for the import as currently declared
+    pub fn matches(&self, import: &Import) -> bool {
+        self.source == import.source && self.ref_ == import.ref_ && self.path == import.path
+    }
+}
+
+/// Resolves an import and records what it resolved to. With `refresh`, git
+/// imports are fetched again so floating refs move to their latest commit.
+pub fn lock_import(
+    importer: &Importer,
+    alias: &str,
+    import: &Import,
+    refresh: bool,
+) -> Result<LockEntry> {
+    let dir = if refresh {
+        importer.refresh(alias, import)?
+    } else {
+        importer.resolve(alias, import)?
+    };
+
+    let commit = match ImportSource::parse(&import.source, Path::new("")) {
+        ImportSource::Git(_) => Some(git(&dir, &["rev-parse", "HEAD"])?),
+        ImportSource::Local(_) => None,
+    };
+
+    Ok(LockEntry {
+        source: import.source.clone(),
+        ref_: import.ref_.clone(),
+        path: import.path.clone(),
+        commit,
+        hash: hash_dir(&dir)?,
+    })
+}
+
+/// Resolves an import at its locked commit and checks that its content still
+/// matches the lock entry
+pub fn verify_import(
+    importer: &Importer,
+    alias: &str,
+    import: &Import,
+    entry: Option<&LockEntry>,
+) -> Result<PathBuf> {
+    let entry = entry.ok_or_else(|| {
+        anyhow::anyhow!(
+            "import {:?} is not in {}; run `c4 mod update`",
+            alias,
+            LOCK_FILE
+        )
+    })?;
+
+    if !entry.matches(import) {
+        return Err(anyhow::anyhow!(
+            "import {:?} has changed since it was locked; run `c4 mod update`",
+            alias
+        ));
+    }
+
+    let dir = match &entry.commit {
+        Some(commit) => importer.resolve_at(alias, import, commit)?,
+        None => importer.resolve(alias, import)?,
+    };
+
+    let hash = hash_dir(&dir)?;
+    if hash != entry.hash {
+        return Err(anyhow::anyhow!(
+            "import {:?} no longer matches {} (expected {}, found {}); run `c4 mod update`",
+            alias,
+            LOCK_FILE,
+            entry.hash,
+            hash
+        ));
+    }
+
+    Ok(dir)
+}
+
+/// Hashes the files an import is made of, its c4.mod.yaml and the files
+/// its `include` patterns match (outside .git and the .c4 cache), along with
+/// their relative paths
+pub fn hash_dir(dir: &Path) -> Result<String> {
+    let files = collect_files(dir)?;
+
+    let mut hasher = Sha256::new();
+    for rel in &files {
+        let content =
+            fs::read(dir.join(rel)).with_context(|| format!("Failed to read {:?}", rel))?;
+        hasher.update(rel.as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+
+    let digest: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Ok(format!("sha256:{}", digest))
+}
+
+/// The sorted relative paths of the mod file and its included files
+fn collect_files(dir: &Path) -> Result<Vec<String>> {
+    let mod_path = dir.join(MOD_FILE);
+    let data =
+        fs::read_to_string(&mod_path).with_context(|| format!("Failed to read {:?}", mod_path))?;
+    let mod_file: ModFile =
+        serde_yaml::from_str(&data).with_context(|| format!("Invalid YAML in {:?}", mod_path))?;
+
+    let mut files = BTreeSet::from([MOD_FILE.to_string()]);
+    for pattern in &mod_file.include {
+        let full_pattern = dir.join(pattern);
+        let matches = glob::glob(&full_pattern.to_string_lossy())
+            .with_context(|| format!("Invalid include pattern {:?}", pattern))?;
+        for path in matches {
+            let path = path?;
+            if !path.is_file() {
+                continue;
+            }
+            let rel: Vec<String> = path
+                .strip_prefix(dir)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            if rel.iter().any(|part| part == ".git" || part == ".c4") {
+                continue;
+            }
+            files.insert(rel.join("/"));
+        }
+    }
+
+    Ok(files.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_workspace(dir: &Path) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: shared\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("persons.yaml"),
+            "persons:\n  - id: customer\n    name: Customer\n",
+        )
+        .unwrap();
+    }
+
+    fn import(source: &str) -> Import {
+        Import {
+            source: source.to_string(),
+            ref_: None,
+            path: None,
+        }
+    }
+
+    #[test]
+    fn test_hash_dir_is_stable_and_ignores_cache() {
+        let dir = TempDir::new().unwrap();
+        write_workspace(dir.path());
+
+        let first = hash_dir(dir.path()).unwrap();
+        fs::create_dir_all(dir.path().join(".c4/cache")).unwrap();
+        fs::write(dir.path().join(".c4/cache/x"), "ignored").unwrap();
+        assert_eq!(hash_dir(dir.path()).unwrap(), first);
+        assert!(first.starts_with("sha256:"));
+
+        fs::write(dir.path().join("persons.yaml"), "persons: []\n").unwrap();
+        assert_ne!(hash_dir(dir.path()).unwrap(), first);
+    }
+
+    #[test]
+    fn test_hash_dir_ignores_files_not_included() {
+        let dir = TempDir::new().unwrap();
+        write_workspace(dir.path());
+
+        let first = hash_dir(dir.path()).unwrap();
+        fs::write(dir.path().join("README.md"), "# Shared").unwrap();
+        fs::create_dir_all(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/notes.yaml"), "notes: []\n").unwrap();
+        assert_eq!(hash_dir(dir.path()).unwrap(), first);
+
+        fs::write(dir.path().join("systems.yaml"), "systems: []\n").unwrap();
+        assert_ne!(hash_dir(dir.path()).unwrap(), first);
+    }
+
+    #[test]
+    fn test_lock_file_round_trip() {
+        let dir = TempDir::new().unwrap();
+        assert!(LockFile::load(dir.path()).unwrap().is_none());
+
+        let mut lock = LockFile::default();
+        lock.imports.insert(
+            "shared".to_string(),
+            LockEntry {
+                source: "../shared".to_string(),
+                ref_: None,
+                path: None,
+                commit: None,
+                hash: "sha256:abc".to_string(),
+            },
+        );
+        lock.save(dir.path()).unwrap();
+
+        let loaded = LockFile::load(dir.path()).unwrap().unwrap();
+        assert_eq!(loaded, lock);
+    }
+
+    #[test]
+    fn test_verify_local_import() {
+        let dir = TempDir::new().unwrap();
+        write_workspace(&dir.path().join("shared"));
+        let importer = Importer::new(dir.path());
+        let imp = import("shared");
+
+        let entry = lock_import(&importer, "shared", &imp, false).unwrap();
+        assert!(entry.commit.is_none());
+        assert!(verify_import(&importer, "shared", &imp, Some(&entry)).is_ok());
+
+        fs::write(dir.path().join("shared/persons.yaml"), "persons: []\n").unwrap();
+        let err = verify_import(&importer, "shared", &imp, Some(&entry)).unwrap_err();
+        assert!(err.to_string().contains("no longer matches"));
+    }
+
+    #[test]
+    fn test_verify_missing_or_changed_entry() {
+        let dir = TempDir::new().unwrap();
+        write_workspace(&dir.path().join("shared"));
+        let importer = Importer::new(dir.path());
+        let imp = import("shared");
+
+        let err = verify_import(&importer, "shared", &imp, None).unwrap_err();
+        assert!(err.to_string().contains("not in c4.lock.yaml"));
+
+        let entry = lock_import(&importer, "shared", &imp, false).unwrap();
+        let moved = import("other");
+        let err = verify_import(&importer, "shared", &moved, Some(&entry)).unwrap_err();
+        assert!(err.to_string().contains("has changed"));
+    }
+
+    #[test]
+    fn test_lock_git_import_pins_commit() {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path().join("repo");
+        write_workspace(&repo);
+        git(&repo, &["init", "--quiet"]).unwrap();
+        git(&repo, &["add", "."]).unwrap();
+        git(
+            &repo,
+            &[
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "initial",
+            ],
+        )
+        .unwrap();
+        let first = git(&repo, &["rev-parse", "HEAD"]).unwrap();
+
+        let ws = dir.path().join("ws");
+        fs::create_dir_all(&ws).unwrap();
+        let importer = Importer::new(&ws);
+        let imp = import(&format!("git+file://{}", repo.display()));
+
+        let entry = lock_import(&importer, "shared", &imp, false).unwrap();
+        assert_eq!(entry.commit.as_deref(), Some(first.as_str()));
+
+        // Upstream moves on; the lock keeps resolving the original commit
+        fs::write(repo.join("persons.yaml"), "persons: []\n").unwrap();
+        git(
+            &repo,
+            &[
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "--quiet",
+                "-am",
+                "update",
+            ],
+        )
+        .unwrap();
+        assert!(verify_import(&importer, "shared", &imp, Some(&entry)).is_ok());
+
+        let updated = lock_import(&importer, "shared", &imp, true).unwrap();
+        assert_ne!(updated.commit, entry.commit);
+        assert_ne!(updated.hash, entry.hash);
+    }
+}
@@ -1,10 +1,21 @@
 pub mod file;
+pub mod imports;
+pub mod layout;
+pub mod lint;
+pub mod locations;
+pub mod lock;
 #[allow(clippy::module_inception)]
 pub mod parser;
 pub mod resolver;
+pub mod rules;
+pub mod schema;
 pub mod writer;
 
 pub use file::{DataFile, FileContext, Import, ModFile};
+pub use imports::Importer;
+pub use lint::Linter;
+pub use lock::LockFile;
 pub use parser::Parser;
 pub use resolver::{Resolver, ValidationError};
+pub use schema::SchemaValidator;
 pub use writer::Writer;
@@ -1,5 +1,10 @@
-use crate::model::{self, ElementType};
-use crate::parser::file::{DataFile, FileContext, ModFile};
+use crate::model::{self, Element, ElementType, SourceKey, SourceLocation};
+use crate::parser::file::{DataFile, FileContext, Import, ModFile};
+use crate::parser::imports::{namespace_model, Importer};
+use crate::parser::layout::Layout;
+use crate::parser::locations::{Locations, NestedPosition, Position, SourceError};
+use crate::parser::lock::{verify_import, LockFile, LOCK_FILE};
+use crate::parser::schema::SchemaValidator;
 use anyhow::{Context, Result};
 use glob::glob;
 use std::fs;
@@ -8,8 +13,14 @@ use std::path::{Path, PathBuf};
 pub struct Parser {
     root_dir: PathBuf,
     mod_file: Option<ModFile>,
+    layout: Layout,
     model: model::Model,
     errors: Vec<anyhow::Error>,
+    // Workspaces currently being parsed, used to detect import cycles
+    import_chain: Vec<PathBuf>,
+    // Directories of the imported workspaces, whose files are not ours to edit
+    import_dirs: Vec<PathBuf>,
+    schema: Option<SchemaValidator>,
 }
 
 impl Parser {
@@ -17,21 +28,38 @@ impl Parser {
         Self {
             root_dir: dir.as_ref().to_path_buf(),
             mod_file: None,
+            layout: Layout::default(),
             model: model::Model::new(),
             errors: Vec::new(),
+            import_chain: Vec::new(),
+            import_dirs: Vec::new(),
+            schema: None,
         }
     }
 
+    /// Checks the mod file and every data file against JSON Schemas before
+    /// loading them. Imported workspaces are not checked.
+    pub fn with_schema(mut self, schema: SchemaValidator) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
     pub fn parse(&mut self) -> Result<model::Model> {
         let mod_path = self.root_dir.join("c4.mod.yaml");
         self.load_mod_file(&mod_path)?;
 
+        self.load_imports();
+
         let patterns: Vec<String> = self.mod_file.as_ref().unwrap().include.clone();
 
         for pattern in &patterns {
             let matches = self.find_files(pattern)?;
 
             for path in matches {
+                // Include globs like "*.yaml" also match the workspace files
+                if is_workspace_file(&path) {
+                    continue;
+                }
                 if let Err(e) = self.load_data_file(&path) {
                     self.errors.push(e);
                 }
@@ -47,9 +75,13 @@ impl Parser {
 
         let options = self.mod_file.as_ref().unwrap().options.clone();
         self.model.options = options;
-        self.model
-            .build_indexes()
-            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        if let Err(e) = self.model.build_indexes() {
+            let model::ModelError::DuplicateElement(path) = &e;
+            return Err(match self.duplicate_source(path) {
+                Some(location) => SourceError::new(location, e.to_string()).into(),
+                None => anyhow::anyhow!("{}", e),
+            });
+        }
 
         let mut parsed_model = model::Model::new();
         std::mem::swap(&mut self.model, &mut parsed_model);
@@ -60,6 +92,20 @@ impl Parser {
         let data = fs::read_to_string(path)
             .with_context(|| format!("Failed to read mod file: {:?}", path))?;
 
+        if let Some(schema) = &self.schema {
+            let file = relative_path(&self.root_dir, path);
+            let errors = schema.validate_mod_file(&file, &data);
+            if !errors.is_empty() {
+                let count = errors.len();
+                self.errors
+                    .extend(errors.into_iter().map(anyhow::Error::from));
+                return Err(anyhow::anyhow!(
+                    "Parse errors: {} errors encountered",
+                    count
+                ));
+            }
+        }
+
         let mod_file: ModFile = serde_yaml::from_str(&data).context("Invalid YAML in mod file")?;
 
         if mod_file.version.is_empty() {
@@ -69,51 +115,188 @@ impl Parser {
             return Err(anyhow::anyhow!("Missing name field in mod file"));
         }
 
+        self.layout = Layout::new(&mod_file.layout)?;
         self.mod_file = Some(mod_file);
         Ok(())
     }
 
+    /// Parses every import declared in the mod file and merges its elements
+    /// into the model under the import's alias. When c4.lock.yaml exists,
+    /// each import must still match its lock entry.
+    pub fn load_imports(&mut self) {
+        let imports = match &self.mod_file {
+            Some(m) => m.imports.clone(),
+            None => return,
+        };
+        if imports.is_empty() {
+            return;
+        }
+
+        let lock = match LockFile::load(&self.root_dir) {
+            Ok(lock) => lock,
+            Err(e) => {
+                self.errors.push(e);
+                return;
+            }
+        };
+
+        let mut aliases: Vec<&String> = imports.keys().collect();
+        aliases.sort();
+
+        let importer = Importer::new(&self.root_dir);
+        for alias in aliases {
+            if let Err(e) = self.load_import(&importer, alias, &imports[alias], lock.as_ref()) {
+                self.errors.push(e);
+            }
+        }
+    }
+
+    fn load_import(
+        &mut self,
+        importer: &Importer,
+        alias: &str,
+        import: &Import,
+        lock: Option<&LockFile>,
+    ) -> Result<()> {
+        let dir = match lock {
+            Some(lock) => verify_import(importer, alias, import, lock.imports.get(alias))?,
+            None => importer.resolve(alias, import)?,
+        };
+
+        let mut chain = self.import_chain.clone();
+        chain.push(canonical(&self.root_dir));
+        if chain.contains(&canonical(&dir)) {
+            return Err(anyhow::anyhow!(
+                "import {:?}: import cycle detected at {:?}",
+                alias,
+                dir
+            ));
+        }
+
+        self.import_dirs.push(canonical(&dir));
+        let mut parser = Parser::new(&dir);
+        parser.import_chain = chain;
+        let mut imported = parser.parse().map_err(|e| {
+            let details: Vec<String> = parser.errors().iter().map(|e| format!("{:#}", e)).collect();
+            if details.is_empty() {
+                anyhow::anyhow!("import {:?}: {}", alias, e)
+            } else {
+                anyhow::anyhow!("import {:?}: {}", alias, details.join("; "))
+            }
+        })?;
+
+        namespace_model(&mut imported, alias);
+        imported.map_source_files(|file| relative_path(&self.root_dir, &dir.join(file)));
+        self.model.append(imported);
+
+        Ok(())
+    }
+
     pub fn load_data_file(&mut self, path: &Path) -> Result<()> {
         let data = fs::read_to_string(path)
             .with_context(|| format!("Failed to read data file: {:?}", path))?;
 
-        let df: DataFile = serde_yaml::from_str(&data)
-            .with_context(|| format!("Invalid YAML in data file: {:?}", path))?;
+        let file = relative_path(&self.root_dir, path);
+
+        if let Some(schema) = &self.schema {
+            let errors = schema.validate_data_file(&file, &data);
+            if !errors.is_empty() {
+                // Report every violation and leave the file out of the model
+                self.errors
+                    .extend(errors.into_iter().map(anyhow::Error::from));
+                return Ok(());
+            }
+        }
+
+        let df: DataFile = serde_yaml::from_str(&data).map_err(|e| match e.location() {
+            Some(loc) => {
+                let message = e.to_string();
+                let message = match message.find(" at line ") {
+                    Some(idx) => message[..idx].to_string(),
+                    None => message,
+                };
+                SourceError::new(
+                    SourceLocation::new(&file, loc.line(), loc.column()),
+                    format!("invalid YAML: {}", message),
+                )
+                .into()
+            }
+            None => anyhow::anyhow!("Invalid YAML in data file: {:?}: {}", path, e),
+        })?;
 
         let ctx = self.context_from_path(path);
+        let locations = Locations::scan(&data);
+        let loc = |pos: Position| SourceLocation::new(&file, pos.line, pos.column);
+        let at = |positions: &[Position], idx: usize| -> SourceLocation {
+            loc(positions.get(idx).copied().unwrap_or_default())
+        };
 
         // Add persons
-        for mut person in df.persons {
+        for (i, mut person) in df.persons.into_iter().enumerate() {
             person.element_type = ElementType::Person;
+            let key = SourceKey::Element(ElementType::Person, self.model.persons.len());
+            self.model.set_source(key, at(&locations.persons, i));
             self.model.persons.push(person);
         }
 
         // Add systems
-        for mut system in df.systems {
+        for (i, mut system) in df.systems.into_iter().enumerate() {
             system.element_type = ElementType::System;
+            let key = SourceKey::Element(ElementType::System, self.model.systems.len());
+            self.model.set_source(key, at(&locations.systems, i));
             self.model.systems.push(system);
         }
 
         // Add containers with system context
-        for mut container in df.containers {
+        for (i, mut container) in df.containers.into_iter().enumerate() {
             container.element_type = ElementType::Container;
+            if let Some(message) = path_conflict(
+                "container",
+                &container.base.id,
+                &[("systemId", &container.system_id, &ctx.system_id, "system")],
+            ) {
+                self.errors
+                    .push(SourceError::new(at(&locations.containers, i), message).into());
+                continue;
+            }
             if container.system_id.is_empty() {
                 container.system_id = ctx.system_id.clone();
             }
             if container.system_id.is_empty() {
-                self.errors.push(anyhow::anyhow!(
-                    "{:?}: container {:?} has no system context",
-                    path,
-                    container.base.id
-                ));
+                self.errors.push(
+                    SourceError::new(
+                        at(&locations.containers, i),
+                        format!("container {:?} has no system context", container.base.id),
+                    )
+                    .into(),
+                );
                 continue;
             }
+            let key = SourceKey::Element(ElementType::Container, self.model.containers.len());
+            self.model.set_source(key, at(&locations.containers, i));
             self.model.containers.push(container);
         }
 
         // Add components with container context
-        for mut component in df.components {
+        for (i, mut component) in df.components.into_iter().enumerate() {
             component.element_type = ElementType::Component;
+            if let Some(message) = path_conflict(
+                "component",
+                &component.base.id,
+                &[
+                    ("systemId", &component.system_id, &ctx.system_id, "system"),
+                    (
+                        "containerId",
+                        &component.container_id,
+                        &ctx.container,
+                        "container",
+                    ),
+                ],
+            ) {
+                self.errors
+                    .push(SourceError::new(at(&locations.components, i), message).into());
+                continue;
+            }
             if component.system_id.is_empty() {
                 component.system_id = ctx.system_id.clone();
             }
@@ -121,28 +304,107 @@ impl Parser {
                 component.container_id = ctx.container.clone();
             }
             if component.system_id.is_empty() || component.container_id.is_empty() {
-                self.errors.push(anyhow::anyhow!(
-                    "{:?}: component {:?} missing system/container context",
-                    path,
-                    component.base.id
-                ));
+                self.errors.push(
+                    SourceError::new(
+                        at(&locations.components, i),
+                        format!(
+                            "component {:?} missing system/container context",
+                            component.base.id
+                        ),
+                    )
+                    .into(),
+                );
                 continue;
             }
+            let key = SourceKey::Element(ElementType::Component, self.model.components.len());
+            self.model.set_source(key, at(&locations.components, i));
             self.model.components.push(component);
         }
 
         // Add relationships
-        self.model.relationships.extend(df.relationships);
+        for (i, rel) in df.relationships.into_iter().enumerate() {
+            let key = SourceKey::Relationship(self.model.relationships.len());
+            self.model.set_source(key, at(&locations.relationships, i));
+            self.model.relationships.push(rel);
+        }
 
         // Add flows
-        self.model.flows.extend(df.flows);
+        for (i, flow) in df.flows.into_iter().enumerate() {
+            let idx = self.model.flows.len();
+            if let Some(flow_pos) = locations.flows.get(i) {
+                self.model
+                    .set_source(SourceKey::Flow(idx), loc(flow_pos.position));
+                for (s, step) in flow_pos.children.iter().enumerate() {
+                    self.model
+                        .set_source(SourceKey::FlowStep(idx, s), loc(step.position));
+                }
+            }
+            self.model.flows.push(flow);
+        }
 
         // Add deployments
-        self.model.deployments.extend(df.deployments);
+        for (i, deployment) in df.deployments.into_iter().enumerate() {
+            let idx = self.model.deployments.len();
+            if let Some(dep_pos) = locations.deployments.get(i) {
+                self.model
+                    .set_source(SourceKey::Deployment(idx), loc(dep_pos.position));
+                self.record_node_sources(idx, &mut Vec::new(), &dep_pos.children, &file);
+            }
+            self.model.deployments.push(deployment);
+        }
 
         Ok(())
     }
 
+    fn record_node_sources(
+        &mut self,
+        deployment: usize,
+        path: &mut Vec<usize>,
+        nodes: &[NestedPosition],
+        file: &str,
+    ) {
+        for (i, node) in nodes.iter().enumerate() {
+            path.push(i);
+            self.model.set_source(
+                SourceKey::DeploymentNode(deployment, path.clone()),
+                SourceLocation::new(file, node.position.line, node.position.column),
+            );
+            self.record_node_sources(deployment, path, &node.children, file);
+            path.pop();
+        }
+    }
+
+    /// Location of the second definition of a duplicated element path
+    fn duplicate_source(&self, path: &str) -> Option<SourceLocation> {
+        let m = &self.model;
+        let paths = m
+            .persons
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (ElementType::Person, i, e.get_full_path()))
+            .chain(
+                m.systems
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| (ElementType::System, i, e.get_full_path())),
+            )
+            .chain(
+                m.containers
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| (ElementType::Container, i, e.get_full_path())),
+            )
+            .chain(
+                m.components
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| (ElementType::Component, i, e.get_full_path())),
+            );
+
+        let (t, idx, _) = paths.filter(|(_, _, p)| p == path).nth(1)?;
+        m.source(&SourceKey::Element(t, idx)).cloned()
+    }
+
     pub fn context_from_path(&self, path: &Path) -> FileContext {
         let mut ctx = FileContext::new(path.display().to_string());
 
@@ -151,22 +413,33 @@ impl Parser {
             Err(_) => return ctx,
         };
 
-        let components: Vec<_> = rel_path.components().collect();
-
-        for (i, component) in components.iter().enumerate() {
-            if let Some(name) = component.as_os_str().to_str() {
-                if name == "systems" && i + 1 < components.len() {
-                    if let Some(system_id) = components[i + 1].as_os_str().to_str() {
-                        ctx.system_id = system_id.to_string();
-                        break;
-                    }
-                }
-            }
-        }
+        let rel_path: Vec<_> = rel_path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect();
+        let (system_id, container) = self.layout.context(&rel_path.join("/"));
+        ctx.system_id = system_id;
+        ctx.container = container;
 
         ctx
     }
 
+    pub fn root_dir(&self) -> &Path {
+        &self.root_dir
+    }
+
+    /// Whether `file`, relative to the workspace, belongs to the workspace
+    /// itself rather than lying outside it or coming from an import
+    pub fn owns_file(&self, file: &str) -> bool {
+        let root = canonical(&self.root_dir);
+        let Ok(path) = fs::canonicalize(self.root_dir.join(file)) else {
+            return false;
+        };
+        path.starts_with(&root)
+            && !path.starts_with(root.join(".c4"))
+            && !self.import_dirs.iter().any(|dir| path.starts_with(dir))
+    }
+
     pub fn get_mod_file(&self) -> Option<&ModFile> {
         self.mod_file.as_ref()
     }
@@ -188,6 +461,54 @@ impl Parser {
     }
 }
 
+/// Path of `path` relative to `base` with `/` separators, walking up with
+/// `..` when `path` lies outside `base`
+fn relative_path(base: &Path, path: &Path) -> String {
+    let base = canonical(base);
+    let path = canonical(path);
+
+    let base_parts: Vec<_> = base.components().collect();
+    let path_parts: Vec<_> = path.components().collect();
+    let common = base_parts
+        .iter()
+        .zip(&path_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); base_parts.len() - common];
+    parts.extend(
+        path_parts[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+fn is_workspace_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == "c4.mod.yaml" || name == LOCK_FILE)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Describes the first explicit ID that disagrees with the one the layout
+/// gives the file's path; each check is `(field, explicit, from path, kind)`
+fn path_conflict(kind: &str, id: &str, checks: &[(&str, &str, &str, &str)]) -> Option<String> {
+    checks
+        .iter()
+        .find(|(_, explicit, implied, _)| {
+            !explicit.is_empty() && !implied.is_empty() && explicit != implied
+        })
+        .map(|(field, explicit, implied, parent)| {
+            format!(
+                "{} {:?} has {} {:?} but its path belongs to {} {:?}",
+                kind, id, field, explicit, parent, implied
+            )
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,6 +631,16 @@ systems:
         assert_eq!(ctx.system_id, "api");
     }
 
+    #[test]
+    fn test_parser_context_from_nested_systems_directory() {
+        let temp = create_test_workspace();
+        let path = temp.path();
+
+        let parser = Parser::new(path);
+        let ctx = parser.context_from_path(&path.join("arch/systems/shop/containers.yaml"));
+        assert_eq!(ctx.system_id, "shop");
+    }
+
     #[test]
     fn test_parser_context_from_path_no_system() {
         let temp = create_test_workspace();
@@ -320,6 +651,115 @@ systems:
         assert_eq!(ctx.system_id, "");
     }
 
+    #[test]
+    fn test_parser_context_from_path_container() {
+        let temp = create_test_workspace();
+        let path = temp.path();
+
+        let parser = Parser::new(path);
+        let ctx =
+            parser.context_from_path(&path.join("systems/api/containers/web/components.yaml"));
+        assert_eq!(ctx.system_id, "api");
+        assert_eq!(ctx.container, "web");
+
+        // A file directly in containers/ names no container
+        let ctx = parser.context_from_path(&path.join("systems/api/containers/web.yaml"));
+        assert_eq!(ctx.system_id, "api");
+        assert_eq!(ctx.container, "");
+    }
+
+    #[test]
+    fn test_parser_components_take_container_from_path() {
+        let temp = create_test_workspace();
+        let root = temp.path();
+        let dir = root.join("systems/api/containers/web");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            root.join("systems/api/containers.yaml"),
+            "containers:\n  - id: web\n    name: Web\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("components.yaml"),
+            "components:\n  - id: auth\n    name: Auth\n",
+        )
+        .unwrap();
+
+        let model = Parser::new(root).parse().unwrap();
+        assert_eq!(model.components[0].get_full_path(), "api.web.auth");
+    }
+
+    #[test]
+    fn test_parser_layout_from_mod_file() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(
+            root.join("c4.mod.yaml"),
+            r#"version: "1.0"
+name: test
+include:
+  - "services/*/architecture/*.yaml"
+layout:
+  - "services/{system}/architecture/*.yaml"
+"#,
+        )
+        .unwrap();
+        let dir = root.join("services/orders/architecture");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("model.yaml"),
+            "systems:\n  - id: orders\n    name: Orders\ncontainers:\n  - id: api\n    name: API\n",
+        )
+        .unwrap();
+
+        let model = Parser::new(root).parse().unwrap();
+        assert_eq!(model.containers[0].get_full_path(), "orders.api");
+    }
+
+    #[test]
+    fn test_parser_invalid_layout() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(
+            root.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\nlayout:\n  - \"services/{service}/*.yaml\"\n",
+        )
+        .unwrap();
+
+        let err = Parser::new(root).parse().unwrap_err();
+        assert!(err.to_string().contains("unknown capture {service}"));
+    }
+
+    #[test]
+    fn test_parser_explicit_ids_conflicting_with_path() {
+        let temp = create_test_workspace();
+        let root = temp.path();
+        let dir = root.join("systems/api/containers/web");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            root.join("systems/api/containers.yaml"),
+            "containers:\n  - id: web\n    name: Web\n  - id: db\n    name: DB\n    systemId: billing\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("components.yaml"),
+            "components:\n  - id: auth\n    name: Auth\n    containerId: db\n  - id: users\n    name: Users\n    containerId: web\n",
+        )
+        .unwrap();
+
+        let mut parser = Parser::new(root);
+        assert!(parser.parse().is_err());
+        let mut errors: Vec<String> = parser.errors().iter().map(|e| e.to_string()).collect();
+        errors.sort();
+        assert_eq!(
+            errors,
+            vec![
+                "systems/api/containers.yaml:4:5: container \"db\" has systemId \"billing\" but its path belongs to system \"api\"",
+                "systems/api/containers/web/components.yaml:2:5: component \"auth\" has containerId \"db\" but its path belongs to container \"web\"",
+            ]
+        );
+    }
+
     #[test]
     fn test_parser_find_files() {
         let temp = create_test_workspace();
@@ -417,4 +857,299 @@ components:
         assert!(result.is_err());
         assert!(!parser.errors().is_empty());
     }
+
+    #[test]
+    fn test_parser_records_source_locations() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(
+            root.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"model.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("model.yaml"),
+            r#"systems:
+  - id: shop
+    name: Shop
+flows:
+  - id: checkout
+    name: Checkout
+    steps:
+      - seq: 1
+        from: shop
+        to: shop
+deployments:
+  - id: prod
+    name: Production
+    nodes:
+      - id: cloud
+        name: Cloud
+        children:
+          - id: cluster
+            name: Cluster
+"#,
+        )
+        .unwrap();
+
+        let mut parser = Parser::new(root);
+        let model = parser.parse().unwrap();
+
+        let shop = model.element_source("shop").unwrap();
+        assert_eq!(shop.to_string(), "model.yaml:2:5");
+        assert_eq!(
+            model
+                .source(&SourceKey::FlowStep(0, 0))
+                .unwrap()
+                .to_string(),
+            "model.yaml:8:9"
+        );
+        assert_eq!(
+            model
+                .source(&SourceKey::DeploymentNode(0, vec![0, 0]))
+                .unwrap()
+                .to_string(),
+            "model.yaml:18:13"
+        );
+    }
+
+    #[test]
+    fn test_parser_duplicate_element_location() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(
+            root.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"model.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("model.yaml"),
+            "systems:\n  - id: shop\n    name: Shop\n  - id: shop\n    name: Shop again\n",
+        )
+        .unwrap();
+
+        let err = Parser::new(root).parse().unwrap_err();
+        let err = err.downcast_ref::<SourceError>().unwrap();
+        assert_eq!(err.location.to_string(), "model.yaml:4:5");
+        assert_eq!(err.message, "duplicate element ID: shop");
+    }
+
+    #[test]
+    fn test_parser_schema_errors() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(
+            root.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("model.yaml"),
+            "systems:\n  - id: shop\n    name: Shop\n    owner: team-a\n",
+        )
+        .unwrap();
+
+        // Without a schema, unknown fields are ignored
+        assert!(Parser::new(root).parse().is_ok());
+
+        let mut parser = Parser::new(root).with_schema(SchemaValidator::bundled().unwrap());
+        assert!(parser.parse().is_err());
+        let err = parser.errors()[0].downcast_ref::<SourceError>().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "model.yaml:4:5: systems[0]: unknown field \"owner\""
+        );
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let temp = TempDir::new().unwrap();
+        let ws = temp.path().join("ws");
+        let shared = temp.path().join("shared");
+        fs::create_dir_all(ws.join("systems")).unwrap();
+        fs::create_dir_all(&shared).unwrap();
+
+        assert_eq!(
+            relative_path(&ws, &ws.join("systems/model.yaml")),
+            "systems/model.yaml"
+        );
+        assert_eq!(
+            relative_path(&ws, &ws.join("../shared/model.yaml")),
+            "../shared/model.yaml"
+        );
+    }
+
+    fn create_shared_workspace(root: &Path) {
+        fs::create_dir_all(root).unwrap();
+        fs::write(
+            root.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: shared\ninclude:\n  - \"model.yaml\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("model.yaml"),
+            r#"
+systems:
+  - id: auth
+    name: Auth
+containers:
+  - id: api
+    name: Auth API
+    systemId: auth
+relationships:
+  - from: auth.api
+    to: auth
+"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_parser_local_import_is_namespaced() {
+        let temp = TempDir::new().unwrap();
+        create_shared_workspace(&temp.path().join("shared"));
+
+        let ws = temp.path().join("ws");
+        fs::create_dir_all(&ws).unwrap();
+        fs::write(
+            ws.join("c4.mod.yaml"),
+            r#"
+version: "1.0"
+name: "product"
+include:
+  - "model.yaml"
+imports:
+  platform:
+    source: "../shared"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            ws.join("model.yaml"),
+            r#"
+persons:
+  - id: user
+    name: User
+relationships:
+  - from: user
+    to: platform/auth.api
+"#,
+        )
+        .unwrap();
+
+        let mut parser = Parser::new(&ws);
+        let model = parser.parse().unwrap();
+
+        assert!(model.get_element("platform/auth").is_some());
+        assert!(model.get_element("platform/auth.api").is_some());
+        assert!(model.get_element("auth").is_none());
+        assert_eq!(
+            model.element_source("platform/auth.api").unwrap().file,
+            "../shared/model.yaml"
+        );
+        assert_eq!(
+            model.get_outgoing_relationships("platform/auth.api").len(),
+            1
+        );
+        assert_eq!(
+            model.get_incoming_relationships("platform/auth.api").len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_parser_owns_only_workspace_files() {
+        let temp = TempDir::new().unwrap();
+        let ws = temp.path().join("ws");
+        create_shared_workspace(&ws.join("vendor"));
+        fs::write(
+            ws.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: product\ninclude:\n  - \"model.yaml\"\nimports:\n  platform:\n    source: ./vendor\n",
+        )
+        .unwrap();
+        fs::write(
+            ws.join("model.yaml"),
+            "persons:\n  - id: user\n    name: User\n",
+        )
+        .unwrap();
+
+        let mut parser = Parser::new(&ws);
+        parser.parse().unwrap();
+
+        assert!(parser.owns_file("model.yaml"));
+        assert!(!parser.owns_file("vendor/model.yaml"));
+        assert!(!parser.owns_file(".c4/cache/platform/v1/model.yaml"));
+        assert!(!parser.owns_file("../other/model.yaml"));
+    }
+
+    #[test]
+    fn test_parser_missing_import_is_error() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\nimports:\n  platform:\n    source: ./missing\n",
+        )
+        .unwrap();
+
+        let mut parser = Parser::new(temp.path());
+        assert!(parser.parse().is_err());
+        assert!(parser.errors()[0].to_string().contains("platform"));
+    }
+
+    #[test]
+    fn test_parser_fails_when_import_does_not_match_lock() {
+        let temp = TempDir::new().unwrap();
+        let shared = temp.path().join("shared");
+        create_shared_workspace(&shared);
+        fs::write(
+            temp.path().join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\nimports:\n  platform:\n    source: ./shared\n",
+        )
+        .unwrap();
+
+        let mod_file: ModFile =
+            serde_yaml::from_str(&fs::read_to_string(temp.path().join("c4.mod.yaml")).unwrap())
+                .unwrap();
+        let importer = Importer::new(temp.path());
+        let entry = crate::parser::lock::lock_import(
+            &importer,
+            "platform",
+            &mod_file.imports["platform"],
+            false,
+        )
+        .unwrap();
+        let mut lock = LockFile::default();
+        lock.imports.insert("platform".to_string(), entry);
+        lock.save(temp.path()).unwrap();
+
+        assert!(Parser::new(temp.path()).parse().is_ok());
+
+        fs::write(shared.join("model.yaml"), "systems: []\n").unwrap();
+        let mut parser = Parser::new(temp.path());
+        assert!(parser.parse().is_err());
+        assert!(parser.errors()[0]
+            .to_string()
+            .contains("no longer matches c4.lock.yaml"));
+    }
+
+    #[test]
+    fn test_parser_import_cycle() {
+        let temp = TempDir::new().unwrap();
+        for (name, other) in [("a", "b"), ("b", "a")] {
+            let dir = temp.path().join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("c4.mod.yaml"),
+                format!(
+                    "version: \"1.0\"\nname: {}\nimports:\n  other:\n    source: ../{}\n",
+                    name, other
+                ),
+            )
+            .unwrap();
+        }
+
+        let mut parser = Parser::new(temp.path().join("a"));
+        assert!(parser.parse().is_err());
+        assert!(parser.errors()[0].to_string().contains("cycle"));
+    }
 }
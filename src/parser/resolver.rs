@@ -1,4 +1,4 @@
-use crate::model::{self, DeploymentNode, Element};
+use crate::model::{self, DeploymentNode, Element, ElementType, SourceKey, SourceLocation};
 use std::fmt;
 
 #[derive(Debug, Clone)]
@@ -6,19 +6,59 @@ pub struct ValidationError {
     pub path: String,
     pub message: String,
     pub line: usize,
+    pub column: usize,
     pub file: String,
+    /// Closest element paths for an unresolved reference, best first
+    pub suggestions: Vec<String>,
+    /// Replacement of an unresolved reference with the best suggestion,
+    /// when it has the expected type and no other suggestion is as close
+    pub fix: Option<Fix>,
+}
+
+/// Replaces an unresolved reference with the closest matching element
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    /// Key holding the reference, such as `from` or `container`
+    pub field: String,
+    pub reference: String,
+    pub replacement: String,
+}
+
+impl ValidationError {
+    /// Creates an error pointing at the given source location, if known
+    pub fn new(
+        path: impl Into<String>,
+        message: impl Into<String>,
+        source: Option<&SourceLocation>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+            line: source.map(|s| s.line).unwrap_or(0),
+            column: source.map(|s| s.column).unwrap_or(0),
+            file: source.map(|s| s.file.clone()).unwrap_or_default(),
+            suggestions: Vec::new(),
+            fix: None,
+        }
+    }
 }
 
 impl fmt::Display for ValidationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if !self.file.is_empty() {
+        if self.file.is_empty() {
+            write!(f, "{}: {}", self.path, self.message)
+        } else if self.column > 0 {
+            write!(
+                f,
+                "{}:{}:{}: {}: {}",
+                self.file, self.line, self.column, self.path, self.message
+            )
+        } else {
             write!(
                 f,
                 "{}:{}: {}: {}",
                 self.file, self.line, self.path, self.message
             )
-        } else {
-            write!(f, "{}: {}", self.path, self.message)
         }
     }
 }
@@ -42,126 +82,289 @@ impl<'a> Resolver<'a> {
         self.errors.clear();
 
         // Validate relationship references
-        for rel in &self.model.relationships {
-            self.validate_ref(&rel.from, &format!("relationship.from({})", rel.from));
-            self.validate_ref(&rel.to, &format!("relationship.to({})", rel.to));
+        for (i, rel) in self.model.relationships.iter().enumerate() {
+            let key = SourceKey::Relationship(i);
+            let first = self.errors.len();
+            self.validate_ref(
+                &rel.from,
+                "from",
+                None,
+                &format!("relationship.from({})", rel.from),
+                &key,
+            );
+            self.validate_ref(
+                &rel.to,
+                "to",
+                None,
+                &format!("relationship.to({})", rel.to),
+                &key,
+            );
+            self.drop_looping_fixes(first, &rel.from, &rel.to);
         }
 
         // Validate flow step references
-        for flow in &self.model.flows {
-            for step in &flow.steps {
+        for (i, flow) in self.model.flows.iter().enumerate() {
+            for (s, step) in flow.steps.iter().enumerate() {
+                let key = SourceKey::FlowStep(i, s);
+                let first = self.errors.len();
                 self.validate_ref(
                     &step.from,
+                    "from",
+                    None,
                     &format!("flow.{}.step.{}.from", flow.id, step.seq),
+                    &key,
+                );
+                self.validate_ref(
+                    &step.to,
+                    "to",
+                    None,
+                    &format!("flow.{}.step.{}.to", flow.id, step.seq),
+                    &key,
                 );
-                self.validate_ref(&step.to, &format!("flow.{}.step.{}.to", flow.id, step.seq));
+                self.drop_looping_fixes(first, &step.from, &step.to);
             }
         }
 
         // Validate deployment container references
-        for dep in &self.model.deployments {
+        for (i, dep) in self.model.deployments.iter().enumerate() {
             if let Some(nodes) = &dep.nodes {
-                self.validate_deployment_nodes(nodes, &dep.id);
+                self.validate_deployment_nodes(nodes, &dep.id, i, &mut Vec::new());
             }
         }
 
         // Validate container parent references
-        for container in &self.model.containers {
+        for (i, container) in self.model.containers.iter().enumerate() {
             if self.model.get_element(&container.system_id).is_none() {
-                self.errors.push(ValidationError {
-                    path: container.get_full_path(),
-                    message: format!(
+                let source = self
+                    .model
+                    .source(&SourceKey::Element(ElementType::Container, i));
+                self.errors.push(ValidationError::new(
+                    container.get_full_path(),
+                    format!(
                         "container references unknown system {:?}",
                         container.system_id
                     ),
-                    line: 0,
-                    file: String::new(),
-                });
+                    source,
+                ));
             }
         }
 
         // Validate component parent references
-        for component in &self.model.components {
+        for (i, component) in self.model.components.iter().enumerate() {
             let parent_path = format!("{}.{}", component.system_id, component.container_id);
             if self.model.get_element(&parent_path).is_none() {
-                self.errors.push(ValidationError {
-                    path: component.get_full_path(),
-                    message: format!("component references unknown container {:?}", parent_path),
-                    line: 0,
-                    file: String::new(),
-                });
+                let source = self
+                    .model
+                    .source(&SourceKey::Element(ElementType::Component, i));
+                self.errors.push(ValidationError::new(
+                    component.get_full_path(),
+                    format!("component references unknown container {:?}", parent_path),
+                    source,
+                ));
             }
         }
 
         self.errors.clone()
     }
 
-    fn validate_ref(&mut self, ref_: &str, context: &str) {
+    fn validate_ref(
+        &mut self,
+        ref_: &str,
+        field: &str,
+        expected: Option<ElementType>,
+        context: &str,
+        key: &SourceKey,
+    ) {
+        let source = self.model.source(key);
+
         if ref_.is_empty() {
-            self.errors.push(ValidationError {
-                path: context.to_string(),
-                message: "empty reference".to_string(),
-                line: 0,
-                file: String::new(),
-            });
+            self.errors
+                .push(ValidationError::new(context, "empty reference", source));
             return;
         }
 
         if self.model.get_element(ref_).is_none() {
-            let suggestion = self.find_similar(ref_);
-            let msg = if let Some(sugg) = suggestion {
-                format!("unresolved reference {:?} (did you mean {:?}?)", ref_, sugg)
-            } else {
+            let candidates = self.candidates(ref_, expected);
+            let suggestions: Vec<String> = candidates
+                .iter()
+                .map(|(_, _, path)| path.clone())
+                .take(MAX_SUGGESTIONS)
+                .collect();
+            let msg = if suggestions.is_empty() {
                 format!("unresolved reference {:?}", ref_)
+            } else {
+                let mut quoted: Vec<String> =
+                    suggestions.iter().map(|s| format!("{:?}", s)).collect();
+                let last = quoted.pop().unwrap_or_default();
+                let choices = match quoted.is_empty() {
+                    true => last,
+                    false => format!("{} or {}", quoted.join(", "), last),
+                };
+                format!(
+                    "unresolved reference {:?} (did you mean {}?)",
+                    ref_, choices
+                )
             };
 
-            self.errors.push(ValidationError {
-                path: context.to_string(),
-                message: msg,
-                line: 0,
-                file: String::new(),
+            let mut error = ValidationError::new(context, msg, source);
+            error.fix = best_match(&candidates).map(|replacement| Fix {
+                field: field.to_string(),
+                reference: ref_.to_string(),
+                replacement: replacement.to_string(),
             });
+            error.suggestions = suggestions;
+            self.errors.push(error);
         }
     }
 
-    fn validate_deployment_nodes(&mut self, nodes: &[DeploymentNode], dep_id: &str) {
-        for node in nodes {
+    /// Drops the fixes in the errors from `first` on when together they
+    /// would point `from` and `to` at the same element
+    fn drop_looping_fixes(&mut self, first: usize, from: &str, to: &str) {
+        let errors = &mut self.errors[first..];
+        let end = |field: &str, reference: &str| {
+            errors
+                .iter()
+                .filter_map(|e| e.fix.as_ref())
+                .find(|f| f.field == field)
+                .map_or(reference.to_string(), |f| f.replacement.clone())
+        };
+        if end("from", from) == end("to", to) {
+            for error in errors {
+                error.fix = None;
+            }
+        }
+    }
+
+    fn validate_deployment_nodes(
+        &mut self,
+        nodes: &[DeploymentNode],
+        dep_id: &str,
+        dep_idx: usize,
+        path: &mut Vec<usize>,
+    ) {
+        for (i, node) in nodes.iter().enumerate() {
+            path.push(i);
+            let key = SourceKey::DeploymentNode(dep_idx, path.clone());
             if let Some(instances) = &node.instances {
                 for inst in instances {
                     self.validate_ref(
                         &inst.container,
+                        "container",
+                        Some(ElementType::Container),
                         &format!("deployment.{}.node.{}.instance", dep_id, node.id),
+                        &key,
                     );
                 }
             }
             if let Some(children) = &node.children {
-                self.validate_deployment_nodes(children, dep_id);
+                self.validate_deployment_nodes(children, dep_id, dep_idx, path);
             }
+            path.pop();
         }
     }
 
+    /// The closest element path to `ref_`, if any is close enough
     pub fn find_similar(&self, ref_: &str) -> Option<String> {
-        let parts: Vec<&str> = ref_.split('.').collect();
-        let last_part = parts.last().unwrap_or(&"");
-
-        let mut best_match = None;
-        let mut best_score = 0;
-
-        for element in self.model.all_elements() {
-            let id = element.get_id();
-            let score = common_prefix_len(last_part, id);
-            if score > best_score && score >= 2 {
-                best_score = score;
-                best_match = Some(element.get_full_path());
-            }
-        }
+        self.suggestions(ref_, None).into_iter().next()
+    }
+
+    /// Up to `MAX_SUGGESTIONS` element paths close to `ref_`, best first.
+    /// Paths are compared segment by segment, so a typo in any part of the
+    /// path is found; a reference missing its leading segments matches the
+    /// end of the path. Elements of the `expected` type rank first.
+    pub fn suggestions(&self, ref_: &str, expected: Option<ElementType>) -> Vec<String> {
+        self.candidates(ref_, expected)
+            .into_iter()
+            .map(|(_, _, path)| path)
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+
+    /// Every element close enough to suggest, best first, as
+    /// `(unexpected type, distance, path)`
+    fn candidates(&self, ref_: &str, expected: Option<ElementType>) -> Vec<(bool, usize, String)> {
+        let segments: Vec<&str> = ref_.split('.').collect();
+
+        let mut candidates: Vec<(bool, usize, String)> = self
+            .model
+            .all_elements()
+            .into_iter()
+            .filter_map(|element| {
+                let path = element.get_full_path();
+                let distance = path_distance(&segments, &path)?;
+                let unexpected = expected.is_some_and(|t| t != element.get_type());
+                Some((unexpected, distance, path))
+            })
+            .collect();
+        candidates.sort();
+        candidates
+    }
+}
+
+/// The best candidate when it has the expected type and is strictly closer
+/// than the next one; a tie is left for the user to settle
+fn best_match(candidates: &[(bool, usize, String)]) -> Option<&str> {
+    let (unexpected, distance, path) = candidates.first()?;
+    if *unexpected {
+        return None;
+    }
+    match candidates.get(1) {
+        Some((false, next, _)) if next == distance => None,
+        _ => Some(path),
+    }
+}
+
+/// How many suggestions an unresolved reference gets
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Distance between a reference and an element path, or None when they are
+/// too different to suggest. Segments are aligned from the end; each
+/// segment of the path the reference leaves out adds one.
+fn path_distance(segments: &[&str], path: &str) -> Option<usize> {
+    let path: Vec<&str> = path.split('.').collect();
+    if segments.len() > path.len() {
+        return None;
+    }
 
-        best_match
+    let missing = path.len() - segments.len();
+    let mut total = missing;
+    for (a, b) in segments.iter().zip(&path[missing..]) {
+        total += segment_distance(a, b)?;
     }
+    Some(total)
 }
 
-fn common_prefix_len(a: &str, b: &str) -> usize {
-    a.bytes().zip(b.bytes()).take_while(|(a, b)| a == b).count()
+/// Edit distance between two path segments, allowing about one edit per
+/// three characters, or any distance when one abbreviates the other
+fn segment_distance(a: &str, b: &str) -> Option<usize> {
+    let distance = edit_distance(a, b);
+    let allowed = (a.chars().count().max(b.chars().count()) / 3).max(1);
+    let abbreviated = a.len().min(b.len()) >= 2 && (a.starts_with(b) || b.starts_with(a));
+    if distance <= allowed || abbreviated {
+        Some(distance)
+    } else {
+        None
+    }
+}
+
+/// Levenshtein distance between `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
 }
 
 #[cfg(test)]
@@ -206,18 +409,34 @@ mod tests {
             path: "relationship.from".to_string(),
             message: "unresolved reference".to_string(),
             line: 42,
+            column: 0,
             file: "data/model.yaml".to_string(),
+            suggestions: Vec::new(),
+            fix: None,
         };
         assert_eq!(
             err.to_string(),
             "data/model.yaml:42: relationship.from: unresolved reference"
         );
 
+        let err = ValidationError::new(
+            "relationship.from",
+            "unresolved reference",
+            Some(&SourceLocation::new("data/model.yaml", 42, 5)),
+        );
+        assert_eq!(
+            err.to_string(),
+            "data/model.yaml:42:5: relationship.from: unresolved reference"
+        );
+
         let err = ValidationError {
             path: "relationship.from".to_string(),
             message: "unresolved reference".to_string(),
             line: 0,
+            column: 0,
             file: String::new(),
+            suggestions: Vec::new(),
+            fix: None,
         };
         assert_eq!(err.to_string(), "relationship.from: unresolved reference");
     }
@@ -241,6 +460,7 @@ mod tests {
             technology: None,
             tags: None,
             properties: None,
+            implied: false,
         });
 
         model.build_indexes().unwrap();
@@ -261,6 +481,7 @@ mod tests {
             technology: None,
             tags: None,
             properties: None,
+            implied: false,
         });
 
         model.build_indexes().unwrap();
@@ -281,6 +502,7 @@ mod tests {
             technology: None,
             tags: None,
             properties: None,
+            implied: false,
         });
 
         model.build_indexes().unwrap();
@@ -364,11 +586,177 @@ mod tests {
     }
 
     #[test]
-    fn test_common_prefix_len() {
-        assert_eq!(common_prefix_len("api-gateway", "api-gate"), 8);
-        assert_eq!(common_prefix_len("api", "api"), 3);
-        assert_eq!(common_prefix_len("abc", "xyz"), 0);
-        assert_eq!(common_prefix_len("a", "api"), 1);
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("order-servce", "order-service"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "api"), 3);
+        assert_eq!(edit_distance("api", "api"), 0);
+    }
+
+    fn create_model_from_yaml(yaml: &str) -> model::Model {
+        let data: crate::parser::DataFile = serde_yaml::from_str(yaml).unwrap();
+        let mut m = model::Model::new();
+        m.systems = data.systems;
+        m.containers = data.containers;
+        m.components = data.components;
+        m.relationships = data.relationships;
+        m.deployments = data.deployments;
+        m.build_indexes().unwrap();
+        m
+    }
+
+    const SIMILAR_MODEL: &str = r#"
+systems:
+  - id: order-service
+    name: Order Service
+  - id: orders
+    name: Orders
+containers:
+  - id: api
+    name: API
+    systemId: order-service
+  - id: apis
+    name: APIs
+    systemId: orders
+components:
+  - id: api
+    name: Nested API
+    systemId: orders
+    containerId: apis
+"#;
+
+    #[test]
+    fn test_find_similar_typo_in_middle_of_path() {
+        let model = create_model_from_yaml(SIMILAR_MODEL);
+        let resolver = Resolver::new(&model);
+
+        assert_eq!(
+            resolver.find_similar("order-servce.api"),
+            Some("order-service.api".to_string())
+        );
+        assert_eq!(
+            resolver.suggestions("order.api", None),
+            vec!["orders.apis", "order-service.api"]
+        );
+    }
+
+    #[test]
+    fn test_suggestions_prefer_expected_type() {
+        let model = create_model_from_yaml(SIMILAR_MODEL);
+        let resolver = Resolver::new(&model);
+
+        // A reference without its parents matches the end of the path
+        assert_eq!(
+            resolver.suggestions("api", None),
+            vec!["order-service.api", "orders.apis", "orders.apis.api"]
+        );
+        assert_eq!(
+            resolver.suggestions("api", Some(ElementType::Component)),
+            vec!["orders.apis.api", "order-service.api", "orders.apis"]
+        );
+    }
+
+    #[test]
+    fn test_deployment_instance_suggests_containers() {
+        let model = create_model_from_yaml(&format!(
+            "{}{}",
+            SIMILAR_MODEL,
+            r#"deployments:
+  - id: prod
+    name: Production
+    nodes:
+      - id: k8s
+        name: Kubernetes
+        instances:
+          - container: api
+"#
+        ));
+        let errors = Resolver::new(&model).resolve();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].suggestions,
+            vec!["order-service.api", "orders.apis", "orders.apis.api"]
+        );
+        assert_eq!(
+            errors[0].message,
+            "unresolved reference \"api\" (did you mean \"order-service.api\", \"orders.apis\" or \"orders.apis.api\"?)"
+        );
+        assert_eq!(
+            errors[0].fix.as_ref().map(|f| f.replacement.as_str()),
+            Some("order-service.api")
+        );
+    }
+
+    #[test]
+    fn test_no_fix_for_tied_suggestions() {
+        let model = create_model_from_yaml(
+            r#"systems:
+  - id: order
+    name: Order
+  - id: payment
+    name: Payment
+containers:
+  - id: api
+    name: Order API
+    systemId: order
+  - id: api
+    name: Payment API
+    systemId: payment
+relationships:
+  - from: order.api
+    to: api
+"#,
+        );
+        let errors = Resolver::new(&model).resolve();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].suggestions, vec!["order.api", "payment.api"]);
+        assert_eq!(errors[0].fix, None);
+    }
+
+    #[test]
+    fn test_no_fix_that_points_relationship_at_itself() {
+        let model = create_model_from_yaml(
+            r#"systems:
+  - id: order
+    name: Order
+containers:
+  - id: api
+    name: Order API
+    systemId: order
+relationships:
+  - from: order.api
+    to: api
+"#,
+        );
+        let errors = Resolver::new(&model).resolve();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].suggestions, vec!["order.api"]);
+        assert_eq!(errors[0].fix, None);
+    }
+
+    #[test]
+    fn test_no_fix_of_unexpected_type() {
+        let model = create_model_from_yaml(&format!(
+            "{}{}",
+            SIMILAR_MODEL,
+            r#"deployments:
+  - id: prod
+    name: Production
+    nodes:
+      - id: k8s
+        name: Kubernetes
+        instances:
+          - container: orders.apis.ap
+"#
+        ));
+        let errors = Resolver::new(&model).resolve();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].suggestions[0], "orders.apis.api");
+        assert_eq!(errors[0].fix, None);
     }
 
     #[test]
@@ -581,6 +969,7 @@ mod tests {
             technology: None,
             tags: None,
             properties: None,
+            implied: false,
         });
 
         model.build_indexes().unwrap();
@@ -590,5 +979,13 @@ mod tests {
         assert_eq!(errors.len(), 1);
         assert!(errors[0].message.contains("did you mean"));
         assert!(errors[0].message.contains("api-gateway"));
+        assert_eq!(
+            errors[0].fix,
+            Some(Fix {
+                field: "to".to_string(),
+                reference: "api-gate".to_string(),
+                replacement: "api-gateway".to_string(),
+            })
+        );
     }
 }
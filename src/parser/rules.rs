@@ -0,0 +1,305 @@
+use crate::model::{self, Element, ElementType, SourceKey};
+use crate::parser::lint::{Diagnostic, Level};
+use crate::parser::resolver::ValidationError;
+use serde::{Deserialize, Serialize};
+
+/// An architecture rule from the mod file, checked against every
+/// relationship in the model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchitectureRule {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Level violations are reported at (default: error)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<Level>,
+    /// Relationships between matching elements are violations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deny: Option<Constraint>,
+    /// Relationships to elements matching `to` must come from elements
+    /// matching `from`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub only: Option<Constraint>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Constraint {
+    #[serde(default)]
+    pub from: Selector,
+    #[serde(default)]
+    pub to: Selector,
+    /// Only consider relationships between different software systems
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cross_system: bool,
+}
+
+/// Matches elements by type, tags and whether they are external. An empty
+/// selector matches every element.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Selector {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub element_type: Option<ElementType>,
+    /// Tags the element must all have
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external: Option<bool>,
+}
+
+impl Selector {
+    fn matches(&self, model: &model::Model, path: &str) -> bool {
+        let Some(element) = model.get_element(path) else {
+            return false;
+        };
+        if self.element_type.is_some_and(|t| t != element.get_type()) {
+            return false;
+        }
+        if !self.tags.iter().all(|tag| element.get_tags().contains(tag)) {
+            return false;
+        }
+        if let Some(external) = self.external {
+            let is_external = system_of(model, path)
+                .and_then(|id| model.systems.iter().find(|s| s.base.id == id))
+                .is_some_and(|s| s.external.unwrap_or(false));
+            if external != is_external {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Checks the outgoing relationships of every element against the rules
+pub fn check_rules(model: &model::Model, rules: &[ArchitectureRule]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for rule in rules {
+        let level = rule.level.unwrap_or(Level::Error);
+        if level == Level::Off {
+            continue;
+        }
+
+        for element in model.all_elements() {
+            let from = element.get_full_path();
+            for rel in model.get_outgoing_relationships(&from) {
+                let to = rel.to.as_str();
+                let denied = rule.deny.as_ref().is_some_and(|c| {
+                    c.applies(model, &from, to)
+                        && c.from.matches(model, &from)
+                        && c.to.matches(model, to)
+                });
+                let not_allowed = rule.only.as_ref().is_some_and(|c| {
+                    c.applies(model, &from, to)
+                        && c.to.matches(model, to)
+                        && !c.from.matches(model, &from)
+                });
+                let violated = denied || not_allowed;
+                if !violated {
+                    continue;
+                }
+
+                let index = model
+                    .relationships
+                    .iter()
+                    .position(|r| std::ptr::eq(r, rel));
+                let source = index.and_then(|i| model.source(&SourceKey::Relationship(i)));
+                let message = rule
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| format!("violates rule {:?}", rule.id));
+                diagnostics.push(Diagnostic {
+                    rule: rule.id.clone(),
+                    level,
+                    error: ValidationError::new(
+                        format!("relationship({} -> {})", rel.from, rel.to),
+                        message,
+                        source,
+                    ),
+                });
+            }
+        }
+    }
+
+    diagnostics
+}
+
+impl Constraint {
+    fn applies(&self, model: &model::Model, from: &str, to: &str) -> bool {
+        !self.cross_system || system_of(model, from) != system_of(model, to)
+    }
+}
+
+/// The software system an element belongs to, if any
+fn system_of<'a>(model: &'a model::Model, path: &str) -> Option<&'a str> {
+    if let Some(s) = model.systems.iter().find(|s| s.base.id == path) {
+        return Some(&s.base.id);
+    }
+    if let Some(c) = model.containers.iter().find(|c| c.get_full_path() == path) {
+        return Some(&c.system_id);
+    }
+    if let Some(c) = model.components.iter().find(|c| c.get_full_path() == path) {
+        return Some(&c.system_id);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DataFile;
+
+    fn create_model() -> model::Model {
+        let data: DataFile = serde_yaml::from_str(
+            r#"
+systems:
+  - id: shop
+    name: Shop
+  - id: billing
+    name: Billing
+  - id: stripe
+    name: Stripe
+    external: true
+containers:
+  - id: gateway
+    name: Gateway
+    systemId: shop
+    tags: [gateway]
+  - id: api
+    name: API
+    systemId: shop
+  - id: db
+    name: Shop DB
+    systemId: shop
+    tags: [database]
+  - id: db
+    name: Billing DB
+    systemId: billing
+    tags: [database]
+components:
+  - id: orders
+    name: Orders
+    systemId: shop
+    containerId: api
+    tags: [domain]
+  - id: repo
+    name: Repository
+    systemId: shop
+    containerId: api
+    tags: [infrastructure]
+relationships:
+  - from: shop.api.orders
+    to: shop.api.repo
+  - from: shop.api
+    to: shop.db
+  - from: shop.api
+    to: billing.db
+  - from: shop.gateway
+    to: stripe
+  - from: shop.api
+    to: stripe
+"#,
+        )
+        .unwrap();
+
+        let mut m = model::Model::new();
+        m.systems = data.systems;
+        m.containers = data.containers;
+        m.components = data.components;
+        m.relationships = data.relationships;
+        m.build_indexes().unwrap();
+        m
+    }
+
+    fn rules(yaml: &str) -> Vec<ArchitectureRule> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn violations(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.error.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_deny_by_tags() {
+        let model = create_model();
+        let diagnostics = check_rules(
+            &model,
+            &rules(
+                r#"
+- id: domain-independence
+  description: Domain components must not depend on infrastructure
+  deny:
+    from: { tags: [domain] }
+    to: { tags: [infrastructure] }
+"#,
+            ),
+        );
+
+        assert_eq!(
+            violations(&diagnostics),
+            vec!["relationship(shop.api.orders -> shop.api.repo)"]
+        );
+        assert_eq!(diagnostics[0].rule, "domain-independence");
+        assert_eq!(diagnostics[0].level, Level::Error);
+        assert_eq!(
+            diagnostics[0].error.message,
+            "Domain components must not depend on infrastructure"
+        );
+    }
+
+    #[test]
+    fn test_deny_cross_system() {
+        let model = create_model();
+        let diagnostics = check_rules(
+            &model,
+            &rules(
+                r#"
+- id: no-foreign-database
+  level: warn
+  deny:
+    from: { type: container }
+    to: { type: container, tags: [database] }
+    crossSystem: true
+"#,
+            ),
+        );
+
+        assert_eq!(
+            violations(&diagnostics),
+            vec!["relationship(shop.api -> billing.db)"]
+        );
+        assert_eq!(diagnostics[0].level, Level::Warn);
+        assert_eq!(
+            diagnostics[0].error.message,
+            "violates rule \"no-foreign-database\""
+        );
+    }
+
+    #[test]
+    fn test_only_from_gateway() {
+        let model = create_model();
+        let diagnostics = check_rules(
+            &model,
+            &rules(
+                r#"
+- id: gateway-only
+  only:
+    from: { tags: [gateway] }
+    to: { external: true }
+"#,
+            ),
+        );
+
+        assert_eq!(
+            violations(&diagnostics),
+            vec!["relationship(shop.api -> stripe)"]
+        );
+    }
+
+    #[test]
+    fn test_rule_turned_off() {
+        let model = create_model();
+        let diagnostics = check_rules(&model, &rules("- id: nothing\n  level: off\n  deny: {}\n"));
+        assert!(diagnostics.is_empty());
+    }
+}
@@ -0,0 +1,371 @@
+use crate::model::SourceLocation;
+use crate::parser::locations::{Document, Position, SourceError};
+use anyhow::{Context, Result};
+use jsonschema::error::ValidationErrorKind;
+use jsonschema::{Retrieve, Uri, Validator};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Root schema for data files
+pub const DATA_SCHEMA: &str = "c4.schema.json";
+
+/// Root schema for c4.mod.yaml
+pub const MOD_SCHEMA: &str = "mod.schema.json";
+
+/// Schemas shipped with the binary, keyed by file name
+const BUNDLED: &[(&str, &str)] = &[
+    (DATA_SCHEMA, include_str!("../../_schema/c4.schema.json")),
+    (MOD_SCHEMA, include_str!("../../_schema/mod.schema.json")),
+    (
+        "person.schema.json",
+        include_str!("../../_schema/person.schema.json"),
+    ),
+    (
+        "system.schema.json",
+        include_str!("../../_schema/system.schema.json"),
+    ),
+    (
+        "container.schema.json",
+        include_str!("../../_schema/container.schema.json"),
+    ),
+    (
+        "component.schema.json",
+        include_str!("../../_schema/component.schema.json"),
+    ),
+    (
+        "relationship.schema.json",
+        include_str!("../../_schema/relationship.schema.json"),
+    ),
+    (
+        "flow.schema.json",
+        include_str!("../../_schema/flow.schema.json"),
+    ),
+    (
+        "deployment.schema.json",
+        include_str!("../../_schema/deployment.schema.json"),
+    ),
+];
+
+/// Validates workspace files against JSON Schemas
+pub struct SchemaValidator {
+    data: Validator,
+    module: Validator,
+}
+
+impl SchemaValidator {
+    /// Uses the schemas bundled with c4
+    pub fn bundled() -> Result<Self> {
+        Self::load(None, None)
+    }
+
+    /// Uses custom schemas. A directory may override any of the bundled
+    /// schema files, with missing ones falling back to the bundled version;
+    /// a file replaces the data file root schema.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        if path.is_dir() {
+            Self::load(Some(path.to_path_buf()), None)
+        } else if path.is_file() {
+            let dir = path.parent().map(Path::to_path_buf);
+            Self::load(dir, Some(path))
+        } else {
+            Err(anyhow::anyhow!("schema path {:?} does not exist", path))
+        }
+    }
+
+    fn load(dir: Option<PathBuf>, data_root: Option<&Path>) -> Result<Self> {
+        let retriever = SchemaRetriever { dir };
+
+        let data_schema = match data_root {
+            Some(path) => read_schema(path)?,
+            None => retriever.schema(DATA_SCHEMA)?,
+        };
+        let module_schema = retriever.schema(MOD_SCHEMA)?;
+
+        Ok(Self {
+            data: compile(&data_schema, retriever.clone()).context("Invalid data file schema")?,
+            module: compile(&module_schema, retriever).context("Invalid mod file schema")?,
+        })
+    }
+
+    /// Checks a data file, reporting each violation at the offending key or
+    /// value. Content that is not valid YAML yields no errors; the parser
+    /// reports those.
+    pub fn validate_data_file(&self, file: &str, content: &str) -> Vec<SourceError> {
+        validate(&self.data, file, content)
+    }
+
+    /// Checks a c4.mod.yaml file
+    pub fn validate_mod_file(&self, file: &str, content: &str) -> Vec<SourceError> {
+        validate(&self.module, file, content)
+    }
+}
+
+/// Resolves schema references by file name, preferring the custom schema
+/// directory over the bundled schemas
+#[derive(Clone)]
+struct SchemaRetriever {
+    dir: Option<PathBuf>,
+}
+
+impl SchemaRetriever {
+    fn schema(&self, name: &str) -> Result<Value> {
+        if let Some(dir) = &self.dir {
+            let path = dir.join(name);
+            if path.is_file() {
+                return read_schema(&path);
+            }
+        }
+
+        let (_, content) = BUNDLED
+            .iter()
+            .find(|(n, _)| *n == name)
+            .ok_or_else(|| anyhow::anyhow!("schema {:?} not found", name))?;
+        serde_json::from_str(content).with_context(|| format!("Invalid bundled schema {}", name))
+    }
+}
+
+impl Retrieve for SchemaRetriever {
+    fn retrieve(
+        &self,
+        uri: &Uri<&str>,
+    ) -> std::result::Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        let name = uri.path().as_str().rsplit('/').next().unwrap_or_default();
+        self.schema(name).map_err(|e| format!("{:#}", e).into())
+    }
+}
+
+fn read_schema(path: &Path) -> Result<Value> {
+    let data =
+        fs::read_to_string(path).with_context(|| format!("Failed to read schema: {:?}", path))?;
+    serde_json::from_str(&data).with_context(|| format!("Invalid JSON in schema: {:?}", path))
+}
+
+fn compile(schema: &Value, retriever: SchemaRetriever) -> Result<Validator> {
+    jsonschema::options()
+        .with_retriever(retriever)
+        .build(schema)
+        .map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+fn validate(validator: &Validator, file: &str, content: &str) -> Vec<SourceError> {
+    let instance: Value = match serde_yaml::from_str(content) {
+        Ok(Value::Null) | Err(_) => return Vec::new(),
+        Ok(value) => value,
+    };
+
+    let doc = Document::parse(content);
+    let at = |position: Option<Position>| {
+        let p = position.unwrap_or(Position { line: 1, column: 1 });
+        SourceLocation::new(file, p.line, p.column)
+    };
+
+    let mut errors = Vec::new();
+    for error in validator.iter_errors(&instance) {
+        let segments = pointer_segments(error.instance_path.as_str());
+        let prefix = match display_path(&instance, &segments) {
+            path if path.is_empty() => String::new(),
+            path => format!("{}: ", path),
+        };
+
+        match &error.kind {
+            ValidationErrorKind::AdditionalProperties { unexpected } => {
+                for key in unexpected {
+                    let position = doc
+                        .key_position(&segments, key)
+                        .or_else(|| doc.position(&segments));
+                    errors.push(SourceError::new(
+                        at(position),
+                        format!("{}unknown field {:?}", prefix, key),
+                    ));
+                }
+            }
+            ValidationErrorKind::Required { property } => {
+                let property = property.as_str().map(str::to_string);
+                errors.push(SourceError::new(
+                    at(doc.position(&segments)),
+                    format!(
+                        "{}missing required field {:?}",
+                        prefix,
+                        property.unwrap_or_default()
+                    ),
+                ));
+            }
+            _ => errors.push(SourceError::new(
+                at(doc.position(&segments)),
+                format!("{}{}", prefix, error),
+            )),
+        }
+    }
+
+    errors.sort_by_key(|e| (e.location.line, e.location.column));
+    errors
+}
+
+/// Splits a JSON pointer into its unescaped segments
+fn pointer_segments(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+/// Renders pointer segments as `containers[0].technology`
+fn display_path(instance: &Value, segments: &[String]) -> String {
+    let mut path = String::new();
+    let mut value = Some(instance);
+    for segment in segments {
+        match value {
+            Some(Value::Array(items)) => {
+                path.push_str(&format!("[{}]", segment));
+                value = segment.parse::<usize>().ok().and_then(|i| items.get(i));
+            }
+            _ => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(segment);
+                value = value.and_then(|v| v.get(segment));
+            }
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn messages(errors: &[SourceError]) -> Vec<String> {
+        errors.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn test_valid_data_file() {
+        let validator = SchemaValidator::bundled().unwrap();
+        let content = r#"
+systems:
+  - id: shop
+    name: Shop
+    external: false
+containers:
+  - id: api
+    name: API
+    technology: [Rust, Axum]
+relationships:
+  - from: shop.api
+    to: shop
+    technology: HTTPS
+"#;
+        assert!(validator
+            .validate_data_file("shop.yaml", content)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_unknown_field_reported_at_key() {
+        let validator = SchemaValidator::bundled().unwrap();
+        let content = "systems:\n  - id: shop\n    name: Shop\n    owner: team-a\n";
+
+        let errors = validator.validate_data_file("systems.yaml", content);
+        assert_eq!(
+            messages(&errors),
+            vec!["systems.yaml:4:5: systems[0]: unknown field \"owner\""]
+        );
+    }
+
+    #[test]
+    fn test_wrong_type_and_missing_field() {
+        let validator = SchemaValidator::bundled().unwrap();
+        let content = "persons:\n  - id: customer\n    tags: admin\n";
+
+        let errors = validator.validate_data_file("persons.yaml", content);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].to_string(),
+            "persons.yaml:2:5: persons[0]: missing required field \"name\""
+        );
+        assert_eq!(
+            errors[1].location,
+            SourceLocation::new("persons.yaml", 3, 11)
+        );
+        assert!(errors[1].message.starts_with("persons[0].tags: "));
+    }
+
+    #[test]
+    fn test_unknown_top_level_section() {
+        let validator = SchemaValidator::bundled().unwrap();
+        let errors = validator.validate_data_file("x.yaml", "system:\n  - id: shop\n");
+        assert_eq!(
+            messages(&errors),
+            vec!["x.yaml:1:1: unknown field \"system\""]
+        );
+    }
+
+    #[test]
+    fn test_invalid_yaml_and_empty_files_are_skipped() {
+        let validator = SchemaValidator::bundled().unwrap();
+        assert!(validator.validate_data_file("x.yaml", "").is_empty());
+        assert!(validator
+            .validate_data_file("x.yaml", "systems: [\n")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_mod_file_schema() {
+        let validator = SchemaValidator::bundled().unwrap();
+        let errors = validator.validate_mod_file(
+            "c4.mod.yaml",
+            "version: \"1.0\"\nname: test\nincludes: []\n",
+        );
+        assert_eq!(
+            messages(&errors),
+            vec!["c4.mod.yaml:3:1: unknown field \"includes\""]
+        );
+    }
+
+    #[test]
+    fn test_custom_schema_dir_overrides_bundled_file() {
+        let dir = TempDir::new().unwrap();
+        let mut person: Value =
+            serde_json::from_str(include_str!("../../_schema/person.schema.json")).unwrap();
+        person["properties"]["email"] = serde_json::json!({ "type": "string" });
+        fs::write(
+            dir.path().join("person.schema.json"),
+            serde_json::to_string(&person).unwrap(),
+        )
+        .unwrap();
+
+        let validator = SchemaValidator::from_path(dir.path()).unwrap();
+        let content = "persons:\n  - id: customer\n    name: Customer\n    email: a@b.c\n";
+        assert!(validator.validate_data_file("p.yaml", content).is_empty());
+
+        let bundled = SchemaValidator::bundled().unwrap();
+        assert_eq!(bundled.validate_data_file("p.yaml", content).len(), 1);
+    }
+
+    #[test]
+    fn test_custom_root_schema_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("strict.schema.json");
+        fs::write(
+            &path,
+            r#"{
+  "type": "object",
+  "required": ["systems"],
+  "properties": {
+    "systems": { "type": "array", "items": { "$ref": "system.schema.json" } }
+  },
+  "additionalProperties": false
+}"#,
+        )
+        .unwrap();
+
+        let validator = SchemaValidator::from_path(&path).unwrap();
+        let errors = validator.validate_data_file("p.yaml", "persons: []\n");
+        assert_eq!(errors.len(), 2);
+        assert!(SchemaValidator::from_path(&dir.path().join("missing")).is_err());
+    }
+}
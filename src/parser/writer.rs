@@ -1,9 +1,11 @@
 use crate::model::ElementType;
-use crate::parser::file::DataFile;
+use crate::parser::file::{DataFile, FileContext};
+use crate::parser::locations::{Document, Position};
 use crate::parser::parser::Parser;
+use crate::parser::resolver::{Fix, ValidationError};
 use anyhow::{Context, Result};
 use serde_yaml::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::PathBuf;
 
@@ -11,25 +13,37 @@ pub struct Writer<'a> {
     parser: &'a Parser,
 }
 
+/// A file rewritten by `Writer::apply_fixes`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    /// Path relative to the workspace
+    pub file: String,
+    pub before: String,
+    pub after: String,
+}
+
 impl<'a> Writer<'a> {
     pub fn new(parser: &'a Parser) -> Self {
         Self { parser }
     }
 
+    /// Updates fields of the element at `element_path`, such as
+    /// `shop.api` for a container, in the data file that defines it
     pub fn update_element(
         &self,
-        element_id: &str,
+        element_path: &str,
         element_type: ElementType,
         updates: HashMap<String, Value>,
     ) -> Result<()> {
-        let file_path = self.find_element_file(element_id, element_type)?;
+        let file_path = self.find_element_file(element_path, element_type)?;
+        let ctx = self.parser.context_from_path(&file_path);
 
         let data = fs::read_to_string(&file_path)
             .with_context(|| format!("Failed to read file {:?}", file_path))?;
 
         let mut root: Value = serde_yaml::from_str(&data).context("Failed to parse YAML")?;
 
-        self.update_in_ast(&mut root, element_id, element_type, &updates)?;
+        self.update_in_ast(&mut root, &ctx, element_path, element_type, &updates)?;
 
         let output = serde_yaml::to_string(&root).context("Failed to serialize YAML")?;
 
@@ -39,9 +53,76 @@ impl<'a> Writer<'a> {
         Ok(())
     }
 
+    /// Replaces unresolved references with their suggested fix, editing
+    /// only the reference itself so comments and formatting are kept. Only
+    /// the workspace's own files are edited, never imported ones. Files are
+    /// written unless `dry_run` is set; either way the changes are returned.
+    pub fn apply_fixes(
+        &self,
+        errors: &[ValidationError],
+        dry_run: bool,
+    ) -> Result<Vec<FileChange>> {
+        let mut by_file: BTreeMap<&str, Vec<(Position, &Fix)>> = BTreeMap::new();
+        for error in errors {
+            if let Some(fix) = &error.fix {
+                if error.line > 0 && self.parser.owns_file(&error.file) {
+                    let at = Position {
+                        line: error.line,
+                        column: error.column,
+                    };
+                    by_file.entry(&error.file).or_default().push((at, fix));
+                }
+            }
+        }
+
+        let mut changes = Vec::new();
+        for (file, fixes) in by_file {
+            let path = self.parser.root_dir().join(file);
+            let before = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read file {:?}", path))?;
+
+            let doc = Document::parse(&before);
+            let mut edits: Vec<(Position, &Fix)> = Vec::new();
+            for (at, fix) in fixes {
+                for position in doc.value_positions(at, &fix.field, &fix.reference) {
+                    if !edits.iter().any(|(p, _)| *p == position) {
+                        edits.push((position, fix));
+                    }
+                }
+            }
+            // Edit from the end so earlier positions stay valid
+            edits.sort_by_key(|(p, _)| std::cmp::Reverse((p.line, p.column)));
+
+            let mut lines: Vec<String> = before.split('\n').map(str::to_string).collect();
+            for (position, fix) in edits {
+                if let Some(line) = lines.get_mut(position.line - 1) {
+                    replace_scalar(line, position.column, fix);
+                }
+            }
+            let after = lines.join("\n");
+            if after == before {
+                continue;
+            }
+
+            if !dry_run {
+                fs::write(&path, &after)
+                    .with_context(|| format!("Failed to write file {:?}", path))?;
+            }
+            changes.push(FileChange {
+                file: file.to_string(),
+                before,
+                after,
+            });
+        }
+
+        Ok(changes)
+    }
+
+    /// The data file defining the element at `element_path`. Systems and
+    /// containers left out of a file come from its layout context.
     pub fn find_element_file(
         &self,
-        element_id: &str,
+        element_path: &str,
         element_type: ElementType,
     ) -> Result<PathBuf> {
         let mod_file = self
@@ -62,8 +143,9 @@ impl<'a> Writer<'a> {
             for file_path in matches {
                 let data = fs::read_to_string(&file_path)?;
                 let df: DataFile = serde_yaml::from_str(&data)?;
+                let ctx = self.parser.context_from_path(&file_path);
 
-                if self.contains_element(&df, element_id, collection_key) {
+                if self.contains_element(&df, &ctx, element_path, collection_key) {
                     return Ok(file_path);
                 }
             }
@@ -71,16 +153,28 @@ impl<'a> Writer<'a> {
 
         Err(anyhow::anyhow!(
             "element {} not found in any file",
-            element_id
+            element_path
         ))
     }
 
-    fn contains_element(&self, df: &DataFile, element_id: &str, collection_key: &str) -> bool {
+    fn contains_element(
+        &self,
+        df: &DataFile,
+        ctx: &FileContext,
+        element_path: &str,
+        collection_key: &str,
+    ) -> bool {
         match collection_key {
-            "persons" => df.persons.iter().any(|p| p.base.id == element_id),
-            "systems" => df.systems.iter().any(|s| s.base.id == element_id),
-            "containers" => df.containers.iter().any(|c| c.base.id == element_id),
-            "components" => df.components.iter().any(|c| c.base.id == element_id),
+            "persons" => df.persons.iter().any(|p| p.base.id == element_path),
+            "systems" => df.systems.iter().any(|s| s.base.id == element_path),
+            "containers" => df
+                .containers
+                .iter()
+                .any(|c| full_path(ctx, &c.base.id, &c.system_id, None) == element_path),
+            "components" => df.components.iter().any(|c| {
+                let container = Some(c.container_id.as_str());
+                full_path(ctx, &c.base.id, &c.system_id, container) == element_path
+            }),
             _ => false,
         }
     }
@@ -88,7 +182,8 @@ impl<'a> Writer<'a> {
     fn update_in_ast(
         &self,
         root: &mut Value,
-        element_id: &str,
+        ctx: &FileContext,
+        element_path: &str,
         element_type: ElementType,
         updates: &HashMap<String, Value>,
     ) -> Result<()> {
@@ -110,21 +205,35 @@ impl<'a> Writer<'a> {
 
         for item in collection {
             if let Some(item_map) = item.as_mapping_mut() {
-                if self.is_target_element(item_map, element_id) {
+                if self.is_target_element(item_map, ctx, element_type, element_path) {
                     self.update_element_node(item_map, updates)?;
                     return Ok(());
                 }
             }
         }
 
-        Err(anyhow::anyhow!("element {} not found in AST", element_id))
+        Err(anyhow::anyhow!("element {} not found in AST", element_path))
     }
 
-    fn is_target_element(&self, node: &serde_yaml::Mapping, element_id: &str) -> bool {
-        if let Some(Value::String(id)) = node.get(Value::String("id".to_string())) {
-            return id == element_id;
-        }
-        false
+    fn is_target_element(
+        &self,
+        node: &serde_yaml::Mapping,
+        ctx: &FileContext,
+        element_type: ElementType,
+        element_path: &str,
+    ) -> bool {
+        let field = |key: &str| node.get(key).and_then(Value::as_str).unwrap_or_default();
+        let Some(Value::String(id)) = node.get("id") else {
+            return false;
+        };
+        let path = match element_type {
+            ElementType::Person | ElementType::System => id.clone(),
+            ElementType::Container => full_path(ctx, id, field("systemId"), None),
+            ElementType::Component => {
+                full_path(ctx, id, field("systemId"), Some(field("containerId")))
+            }
+        };
+        path == element_path
     }
 
     fn update_element_node(
@@ -139,6 +248,38 @@ impl<'a> Writer<'a> {
     }
 }
 
+/// Full path of a container, or of a component when `container_id` is
+/// given. Empty system and container IDs come from the file context.
+fn full_path(ctx: &FileContext, id: &str, system_id: &str, container_id: Option<&str>) -> String {
+    let or_ctx = |value: &str, fallback: &str| {
+        if value.is_empty() {
+            fallback.to_string()
+        } else {
+            value.to_string()
+        }
+    };
+    let system = or_ctx(system_id, &ctx.system_id);
+    match container_id {
+        Some(container) => format!("{}.{}.{}", system, or_ctx(container, &ctx.container), id),
+        None => format!("{}.{}", system, id),
+    }
+}
+
+/// Replaces the reference in the plain or quoted scalar starting at the
+/// 1-based `column`
+fn replace_scalar(line: &mut String, column: usize, fix: &Fix) {
+    let Some((start, _)) = line.char_indices().nth(column - 1) else {
+        return;
+    };
+    let start = match line[start..].chars().next() {
+        Some('"') | Some('\'') => start + 1,
+        _ => start,
+    };
+    if line[start..].starts_with(&fix.reference) {
+        line.replace_range(start..start + fix.reference.len(), &fix.replacement);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -320,7 +461,7 @@ containers:
 
         let writer = Writer::new(&parser);
         let file = writer
-            .find_element_file("web", ElementType::Container)
+            .find_element_file("api.web", ElementType::Container)
             .unwrap();
         assert!(file.ends_with("containers.yaml"));
     }
@@ -365,7 +506,7 @@ components:
 
         let writer = Writer::new(&parser);
         let file = writer
-            .find_element_file("handler", ElementType::Component)
+            .find_element_file("api.backend.handler", ElementType::Component)
             .unwrap();
         assert!(file.ends_with("components.yaml"));
     }
@@ -408,7 +549,7 @@ containers:
             Value::String("Updated Web App".to_string()),
         );
 
-        let result = writer.update_element("web", ElementType::Container, updates);
+        let result = writer.update_element("api.web", ElementType::Container, updates);
         assert!(result.is_ok());
 
         let content = fs::read_to_string(root.join("data/containers.yaml")).unwrap();
@@ -460,7 +601,7 @@ components:
             Value::String("Updated Handler".to_string()),
         );
 
-        let result = writer.update_element("handler", ElementType::Component, updates);
+        let result = writer.update_element("api.backend.handler", ElementType::Component, updates);
         assert!(result.is_ok());
 
         let content = fs::read_to_string(root.join("data/components.yaml")).unwrap();
@@ -511,6 +652,63 @@ persons:
 
         let df = DataFile::default();
         // Using an internal check - "unknown" collection key should return false
-        assert!(!writer.contains_element(&df, "any_id", "unknown"));
+        let ctx = FileContext::new("model.yaml".to_string());
+        assert!(!writer.contains_element(&df, &ctx, "any_id", "unknown"));
+    }
+
+    #[test]
+    fn test_writer_apply_fixes() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        fs::write(
+            root.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"*.yaml\"\n",
+        )
+        .unwrap();
+        let content = r#"systems:
+  - id: shop
+    name: Shop
+  - id: payments
+    name: Payments
+
+relationships:
+  # Checkout pays through the payment service
+  - from: shop
+    to: "paymnts"
+flows:
+  - id: checkout
+    name: Checkout
+    steps:
+      - seq: 1
+        from: shop
+        to: pay
+"#;
+        fs::write(root.join("model.yaml"), content).unwrap();
+
+        let mut parser = Parser::new(root);
+        let model = parser.parse().unwrap();
+        let errors = crate::parser::Resolver::new(&model).resolve();
+        assert_eq!(errors.len(), 2);
+
+        let writer = Writer::new(&parser);
+        let changes = writer.apply_fixes(&errors, true).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].file, "model.yaml");
+        assert_eq!(
+            changes[0].after,
+            content
+                .replace("\"paymnts\"", "\"payments\"")
+                .replace("to: pay\n", "to: payments\n")
+        );
+        // A dry run leaves the file alone
+        assert_eq!(
+            fs::read_to_string(root.join("model.yaml")).unwrap(),
+            content
+        );
+
+        writer.apply_fixes(&errors, false).unwrap();
+        let mut parser = Parser::new(root);
+        let model = parser.parse().unwrap();
+        assert!(crate::parser::Resolver::new(&model).resolve().is_empty());
     }
 }
@@ -20,8 +20,8 @@ pub struct Hub {
     register_rx: Arc<RwLock<mpsc::UnboundedReceiver<Client>>>,
     unregister_tx: mpsc::UnboundedSender<String>,
     unregister_rx: Arc<RwLock<mpsc::UnboundedReceiver<String>>>,
-    broadcast_tx: mpsc::UnboundedSender<Vec<u8>>,
-    broadcast_rx: Arc<RwLock<mpsc::UnboundedReceiver<Vec<u8>>>>,
+    broadcast_tx: mpsc::UnboundedSender<WsMessage>,
+    broadcast_rx: Arc<RwLock<mpsc::UnboundedReceiver<WsMessage>>>,
     stop_tx: mpsc::UnboundedSender<()>,
     stop_rx: Arc<RwLock<mpsc::UnboundedReceiver<()>>>,
 }
@@ -64,10 +64,9 @@ impl Hub {
                 }
                 Some(message) = broadcast_rx.recv() => {
                     let clients = self.clients.read().await;
-                    let ws_message = WsMessage::Binary(message);
 
                     for client in clients.iter() {
-                        let _ = client.send.send(ws_message.clone());
+                        let _ = client.send.send(message.clone());
                     }
                 }
                 Some(_) = stop_rx.recv() => {
@@ -92,7 +91,12 @@ impl Hub {
     }
 
     pub fn broadcast(&self, message: Vec<u8>) {
-        let _ = self.broadcast_tx.send(message);
+        let _ = self.broadcast_tx.send(WsMessage::Binary(message));
+    }
+
+    /// Broadcasts a text frame, which browsers deliver as a string
+    pub fn broadcast_text(&self, message: String) {
+        let _ = self.broadcast_tx.send(WsMessage::Text(message));
     }
 
     pub async fn client_count(&self) -> usize {
@@ -208,6 +212,29 @@ mod tests {
         let _ = run_handle.await;
     }
 
+    #[tokio::test]
+    async fn test_hub_broadcast_text() {
+        let hub = Arc::new(Hub::new());
+        let hub_run = hub.clone();
+        let run_handle = tokio::spawn(async move {
+            hub_run.run().await;
+        });
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        hub.register(Client::new("client".to_string(), tx));
+        sleep(Duration::from_millis(50)).await;
+
+        hub.broadcast_text(r#"{"type":"reload"}"#.to_string());
+
+        match rx.recv().await {
+            Some(WsMessage::Text(text)) => assert_eq!(text, r#"{"type":"reload"}"#),
+            other => panic!("Expected text message, got {:?}", other),
+        }
+
+        hub.stop();
+        let _ = run_handle.await;
+    }
+
     #[tokio::test]
     async fn test_hub_multiple_clients() {
         let hub = Hub::new();
@@ -9,6 +9,9 @@ pub use docs::{
 pub use hub::{Client, Hub};
 pub use watcher::Watcher;
 
+use crate::model::ElementType;
+use crate::parser::locations::SourceError;
+use crate::parser::Writer;
 use axum::{
     extract::{ws::WebSocket, Path, State, WebSocketUpgrade},
     http::{header, StatusCode, Uri},
@@ -19,11 +22,12 @@ use axum::{
 use futures_util::{SinkExt, StreamExt};
 use rust_embed::RustEmbed;
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::net::TcpListener;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tower_http::cors::CorsLayer;
 
@@ -48,6 +52,55 @@ impl Default for Config {
     }
 }
 
+impl Config {
+    /// URL for opening the server in a browser. Wildcard bind addresses are
+    /// reachable locally, so they map to localhost.
+    pub fn url(&self) -> String {
+        let host = self.host.trim_start_matches('[').trim_end_matches(']');
+        match host.parse::<IpAddr>() {
+            Ok(ip) if ip.is_unspecified() => format!("http://localhost:{}", self.port),
+            Ok(IpAddr::V6(ip)) => format!("http://[{}]:{}", ip, self.port),
+            _ => format!("http://{}:{}", host, self.port),
+        }
+    }
+}
+
+/// Resolves a bind host, which may be an IPv4 or IPv6 address (optionally in
+/// brackets) or a hostname such as `localhost`
+pub async fn resolve_host(host: &str, port: u16) -> std::io::Result<Vec<SocketAddr>> {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(vec![SocketAddr::new(ip, port)]);
+    }
+
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await?.collect();
+    if addrs.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "no addresses found",
+        ));
+    }
+    Ok(addrs)
+}
+
+/// Binds the first address `host` resolves to that accepts a listener
+pub async fn bind(host: &str, port: u16) -> Result<TcpListener, String> {
+    let addrs = resolve_host(host, port)
+        .await
+        .map_err(|e| format!("failed to resolve host '{}': {}", host, e))?;
+
+    let mut last_error = None;
+    for addr in addrs {
+        match TcpListener::bind(addr).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) => last_error = Some((addr, e)),
+        }
+    }
+
+    let (addr, e) = last_error.expect("resolve_host returns at least one address");
+    Err(format!("failed to bind {}: {}", addr, e))
+}
+
 #[derive(RustEmbed)]
 #[folder = "src/exporter/static/dist"]
 struct StaticAssets;
@@ -57,6 +110,96 @@ pub struct ServerState {
     hub: Arc<Hub>,
     model: Arc<RwLock<Option<serde_json::Value>>>,
     config: Config,
+    // Serializes reloads so a slow parse can't overwrite a newer model
+    reload_lock: Arc<Mutex<()>>,
+}
+
+/// A parse failure reported to clients when the workspace no longer parses
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParseError {
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+}
+
+impl ParseError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            file: None,
+            line: None,
+            column: None,
+        }
+    }
+}
+
+impl From<&anyhow::Error> for ParseError {
+    fn from(err: &anyhow::Error) -> Self {
+        match err.downcast_ref::<SourceError>() {
+            Some(e) => Self {
+                message: e.message.clone(),
+                file: Some(e.location.file.clone()),
+                line: Some(e.location.line),
+                column: Some(e.location.column),
+            },
+            None => Self::new(format!("{:#}", err)),
+        }
+    }
+}
+
+impl ServerState {
+    /// Re-parses the workspace, swaps the served model and pushes the new
+    /// model to clients. On failure the last good model stays in place and
+    /// clients receive the parse errors instead.
+    pub async fn reload(&self) {
+        let _guard = self.reload_lock.lock().await;
+
+        let work_dir = self.config.work_dir.clone();
+        let result = tokio::task::spawn_blocking(move || load_model(&work_dir))
+            .await
+            .unwrap_or_else(|e| Err(vec![ParseError::new(format!("reload failed: {}", e))]));
+
+        match result {
+            Ok(model) => {
+                *self.model.write().await = Some(model.clone());
+                let message = serde_json::json!({ "type": "model", "model": model });
+                self.hub.broadcast_text(message.to_string());
+            }
+            Err(errors) => {
+                if self.config.verbose {
+                    for error in &errors {
+                        eprintln!("Parse error: {}", error.message);
+                    }
+                }
+                let message = serde_json::json!({
+                    "type": "error",
+                    "message": format!("Parse errors: {} errors encountered", errors.len()),
+                    "errors": errors,
+                });
+                self.hub.broadcast_text(message.to_string());
+            }
+        }
+    }
+}
+
+/// Parses the workspace and serializes the model as served by /api/model
+pub fn load_model(work_dir: &std::path::Path) -> Result<serde_json::Value, Vec<ParseError>> {
+    let mut parser = crate::parser::Parser::new(work_dir);
+    let model = parser.parse().map_err(|e| {
+        let errors: Vec<ParseError> = parser.errors().iter().map(ParseError::from).collect();
+        if errors.is_empty() {
+            vec![ParseError::from(&e)]
+        } else {
+            errors
+        }
+    })?;
+
+    serde_json::to_value(&model)
+        .map_err(|e| vec![ParseError::new(format!("failed to serialize model: {}", e))])
 }
 
 pub struct Server {
@@ -71,6 +214,7 @@ impl Server {
             hub: hub.clone(),
             model: Arc::new(RwLock::new(None)),
             config: config.clone(),
+            reload_lock: Arc::new(Mutex::new(())),
         };
 
         let watcher = if !config.no_reload {
@@ -81,7 +225,7 @@ impl Server {
                     if state.config.verbose {
                         eprintln!("File changed: {}", path);
                     }
-                    state.hub.broadcast(br#"{"type":"reload"}"#.to_vec());
+                    state.reload().await;
                 });
             })?)
         } else {
@@ -99,13 +243,12 @@ impl Server {
 
         let app = self.create_router();
 
-        let addr = SocketAddr::from(([127, 0, 0, 1], self.state.config.port));
+        let listener = bind(&self.state.config.host, self.state.config.port).await?;
 
         if self.state.config.verbose {
-            eprintln!("Server running at http://{}", addr);
+            eprintln!("Server running at http://{}", listener.local_addr()?);
         }
 
-        let listener = TcpListener::bind(addr).await?;
         axum::serve(listener, app).await?;
 
         Ok(())
@@ -196,23 +339,134 @@ async fn handle_update_element(
     Path((element_type, id)): Path<(String, String)>,
     Json(update): Json<ElementUpdate>,
 ) -> Response {
-    if !["person", "system", "container", "component"].contains(&element_type.as_str()) {
-        return (StatusCode::BAD_REQUEST, "Invalid element type").into_response();
-    }
+    let element_type = match element_type.as_str() {
+        "person" => ElementType::Person,
+        "system" => ElementType::System,
+        "container" => ElementType::Container,
+        "component" => ElementType::Component,
+        _ => {
+            return UpdateError::new(StatusCode::BAD_REQUEST, "Invalid element type")
+                .into_response()
+        }
+    };
 
     if state.config.verbose {
-        eprintln!("Updating {} {}: {:?}", element_type, id, update.fields);
+        eprintln!("Updating {:?} {}: {:?}", element_type, id, update.fields);
     }
 
-    state.hub.broadcast(br#"{"type":"reload"}"#.to_vec());
+    // Hold the reload lock so the watcher can't swap in a model mid-edit
+    let _guard = state.reload_lock.lock().await;
 
-    let model = state.model.read().await;
-    match model.as_ref() {
-        Some(m) => Json(m.clone()).into_response(),
-        None => Json(serde_json::json!({})).into_response(),
+    let work_dir = state.config.work_dir.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        apply_update(&work_dir, element_type, &id, update.fields)
+    })
+    .await
+    .unwrap_or_else(|e| {
+        Err(UpdateError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("update failed: {}", e),
+        ))
+    });
+
+    match result {
+        Ok(model) => {
+            *state.model.write().await = Some(model.clone());
+            let message = serde_json::json!({ "type": "model", "model": model });
+            state.hub.broadcast_text(message.to_string());
+            Json(model).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Error body returned by the element update endpoint
+#[derive(Debug, Serialize)]
+struct UpdateError {
+    #[serde(skip)]
+    status: StatusCode,
+    error: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<ParseError>,
+}
+
+impl UpdateError {
+    fn new(status: StatusCode, error: impl Into<String>) -> Self {
+        Self {
+            status,
+            error: error.into(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+impl IntoResponse for UpdateError {
+    fn into_response(self) -> Response {
+        (self.status, Json(&self)).into_response()
     }
 }
 
+/// Writes the update to the element's data file and re-parses the workspace.
+/// The file is restored if the edited workspace no longer parses.
+fn apply_update(
+    work_dir: &std::path::Path,
+    element_type: ElementType,
+    id: &str,
+    mut fields: serde_json::Map<String, serde_json::Value>,
+) -> Result<serde_json::Value, UpdateError> {
+    // The UI sends the element type along with its fields
+    fields.remove("type");
+
+    // Containers and components are addressed by their full path, while data
+    // files hold the local ID
+    let local_id = id.rsplit('.').next().unwrap_or(id);
+
+    if let Some(new_id) = fields.remove("id") {
+        if new_id.as_str() != Some(local_id) && new_id.as_str() != Some(id) {
+            return Err(UpdateError::new(
+                StatusCode::BAD_REQUEST,
+                "element id cannot be changed",
+            ));
+        }
+    }
+
+    let mut updates = HashMap::new();
+    for (field, value) in fields {
+        let value = serde_yaml::to_value(value).map_err(|e| {
+            UpdateError::new(
+                StatusCode::BAD_REQUEST,
+                format!("invalid value for {}: {}", field, e),
+            )
+        })?;
+        updates.insert(field, value);
+    }
+
+    let mut parser = crate::parser::Parser::new(work_dir);
+    parser
+        .load_mod_file(&work_dir.join("c4.mod.yaml"))
+        .map_err(|e| UpdateError::new(StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e)))?;
+
+    let writer = Writer::new(&parser);
+    let file = writer.find_element_file(id, element_type).map_err(|_| {
+        UpdateError::new(StatusCode::NOT_FOUND, format!("element {} not found", id))
+    })?;
+    let original = std::fs::read_to_string(&file)
+        .map_err(|e| UpdateError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    writer
+        .update_element(id, element_type, updates)
+        .map_err(|e| UpdateError::new(StatusCode::UNPROCESSABLE_ENTITY, format!("{:#}", e)))?;
+
+    load_model(work_dir).map_err(|errors| {
+        let _ = std::fs::write(&file, &original);
+        UpdateError {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            error: "update would leave the model invalid; no changes were saved".to_string(),
+            errors,
+        }
+    })
+}
+
 async fn handle_websocket(
     ws: WebSocketUpgrade,
     State(state): State<ServerState>,
@@ -288,6 +542,49 @@ mod tests {
         assert!(config.verbose);
     }
 
+    #[test]
+    fn test_config_url() {
+        let url = |host: &str| {
+            Config {
+                host: host.to_string(),
+                port: 4400,
+                ..Default::default()
+            }
+            .url()
+        };
+
+        assert_eq!(url("localhost"), "http://localhost:4400");
+        assert_eq!(url("127.0.0.1"), "http://127.0.0.1:4400");
+        assert_eq!(url("0.0.0.0"), "http://localhost:4400");
+        assert_eq!(url("::"), "http://localhost:4400");
+        assert_eq!(url("::1"), "http://[::1]:4400");
+        assert_eq!(url("[::1]"), "http://[::1]:4400");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_host() {
+        let addrs = resolve_host("0.0.0.0", 4400).await.unwrap();
+        assert_eq!(addrs, vec![SocketAddr::from(([0, 0, 0, 0], 4400))]);
+
+        let addrs = resolve_host("[::1]", 4400).await.unwrap();
+        assert_eq!(addrs, vec!["[::1]:4400".parse().unwrap()]);
+
+        let addrs = resolve_host("localhost", 4400).await.unwrap();
+        assert!(addrs.iter().all(|a| a.ip().is_loopback()));
+    }
+
+    #[tokio::test]
+    async fn test_bind_reports_errors() {
+        let listener = bind("127.0.0.1", 0).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let err = bind("127.0.0.1", port).await.unwrap_err();
+        assert!(err.starts_with(&format!("failed to bind 127.0.0.1:{}", port)));
+
+        let err = bind("no-such-host.invalid", 4400).await.unwrap_err();
+        assert!(err.starts_with("failed to resolve host 'no-such-host.invalid'"));
+    }
+
     #[tokio::test]
     async fn test_server_creation() {
         let temp_dir = TempDir::new().unwrap();
@@ -420,34 +717,238 @@ mod tests {
         assert_eq!(status, StatusCode::BAD_REQUEST);
     }
 
+    fn write_element_workspace(dir: &std::path::Path) {
+        std::fs::write(
+            dir.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"model.yaml\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("model.yaml"),
+            r#"
+persons:
+  - id: user
+    name: User
+systems:
+  - id: shop
+    name: Shop
+containers:
+  - id: api
+    name: API
+    systemId: shop
+components:
+  - id: orders
+    name: Orders
+    systemId: shop
+    containerId: api
+"#,
+        )
+        .unwrap();
+    }
+
+    async fn update(
+        state: ServerState,
+        element_type: &str,
+        id: &str,
+        fields: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let update = ElementUpdate {
+            fields: fields.as_object().unwrap().clone(),
+        };
+        let response = handle_update_element(
+            State(state),
+            Path((element_type.to_string(), id.to_string())),
+            Json(update),
+        )
+        .await;
+
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
     #[tokio::test]
     async fn test_update_element_valid_types() {
         let temp_dir = TempDir::new().unwrap();
+        write_element_workspace(temp_dir.path());
         let config = Config {
             work_dir: temp_dir.path().to_path_buf(),
+            no_reload: true,
             ..Default::default()
         };
 
-        for element_type in &["person", "system", "container", "component"] {
+        for (element_type, id) in [
+            ("person", "user"),
+            ("system", "shop"),
+            ("container", "shop.api"),
+            ("component", "shop.api.orders"),
+        ] {
             let server = Server::new(config.clone()).unwrap();
             let state = server.state.clone();
 
-            let update = ElementUpdate {
-                fields: serde_json::Map::new(),
-            };
-
-            let response = handle_update_element(
-                State(state),
-                Path((element_type.to_string(), "id".to_string())),
-                Json(update),
+            let (status, _) = update(
+                state,
+                element_type,
+                id,
+                serde_json::json!({ "type": element_type, "description": "Edited" }),
             )
             .await;
-
-            let status = response.into_response().status();
             assert_eq!(status, StatusCode::OK);
         }
     }
 
+    #[tokio::test]
+    async fn test_update_element_persists_to_yaml() {
+        let temp_dir = TempDir::new().unwrap();
+        write_element_workspace(temp_dir.path());
+        let config = Config {
+            work_dir: temp_dir.path().to_path_buf(),
+            no_reload: true,
+            ..Default::default()
+        };
+        let server = Server::new(config).unwrap();
+        let state = server.state.clone();
+
+        let (status, model) = update(
+            state.clone(),
+            "container",
+            "shop.api",
+            serde_json::json!({ "name": "Orders API", "technology": "Rust" }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(model["containers"][0]["name"], "Orders API");
+
+        let content = std::fs::read_to_string(temp_dir.path().join("model.yaml")).unwrap();
+        assert!(content.contains("Orders API"));
+        assert!(!content.contains("type:"));
+
+        let served = state.model.read().await.clone().unwrap();
+        assert_eq!(served["containers"][0]["name"], "Orders API");
+    }
+
+    #[tokio::test]
+    async fn test_update_element_by_full_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(
+            root.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"systems/*/*.yaml\"\n",
+        )
+        .unwrap();
+        for system in ["order", "payment"] {
+            std::fs::create_dir_all(root.join("systems").join(system)).unwrap();
+            std::fs::write(
+                root.join("systems").join(system).join("model.yaml"),
+                format!(
+                    "systems:\n  - id: {}\n    name: {}\ncontainers:\n  - id: api\n    name: API\n",
+                    system, system
+                ),
+            )
+            .unwrap();
+        }
+        let order = std::fs::read_to_string(root.join("systems/order/model.yaml")).unwrap();
+        let config = Config {
+            work_dir: root.to_path_buf(),
+            no_reload: true,
+            ..Default::default()
+        };
+        let server = Server::new(config).unwrap();
+
+        let (status, _) = update(
+            server.state.clone(),
+            "container",
+            "payment.api",
+            serde_json::json!({ "name": "Payment API" }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        let payment = std::fs::read_to_string(root.join("systems/payment/model.yaml")).unwrap();
+        assert!(payment.contains("Payment API"));
+        assert_eq!(
+            std::fs::read_to_string(root.join("systems/order/model.yaml")).unwrap(),
+            order
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_element_not_found() {
+        let temp_dir = TempDir::new().unwrap();
+        write_element_workspace(temp_dir.path());
+        let config = Config {
+            work_dir: temp_dir.path().to_path_buf(),
+            no_reload: true,
+            ..Default::default()
+        };
+        let server = Server::new(config).unwrap();
+
+        let (status, body) = update(
+            server.state.clone(),
+            "system",
+            "missing",
+            serde_json::json!({ "name": "Missing" }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "element missing not found");
+    }
+
+    #[tokio::test]
+    async fn test_update_element_invalid_result_is_rolled_back() {
+        let temp_dir = TempDir::new().unwrap();
+        write_element_workspace(temp_dir.path());
+        let original = std::fs::read_to_string(temp_dir.path().join("model.yaml")).unwrap();
+        let config = Config {
+            work_dir: temp_dir.path().to_path_buf(),
+            no_reload: true,
+            ..Default::default()
+        };
+        let server = Server::new(config).unwrap();
+
+        let (status, body) = update(
+            server.state.clone(),
+            "system",
+            "shop",
+            serde_json::json!({ "name": { "nested": true } }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(!body["errors"].as_array().unwrap().is_empty());
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("model.yaml")).unwrap(),
+            original
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_element_rejects_id_change() {
+        let temp_dir = TempDir::new().unwrap();
+        write_element_workspace(temp_dir.path());
+        let config = Config {
+            work_dir: temp_dir.path().to_path_buf(),
+            no_reload: true,
+            ..Default::default()
+        };
+        let server = Server::new(config).unwrap();
+
+        let (status, body) = update(
+            server.state.clone(),
+            "person",
+            "user",
+            serde_json::json!({ "id": "admin" }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "element id cannot be changed");
+    }
+
     #[tokio::test]
     async fn test_server_shutdown() {
         let temp_dir = TempDir::new().unwrap();
@@ -488,6 +989,102 @@ mod tests {
         sleep(Duration::from_millis(300)).await;
     }
 
+    fn write_workspace(dir: &std::path::Path, systems: &str) {
+        std::fs::write(
+            dir.join("c4.mod.yaml"),
+            "version: \"1.0\"\nname: test\ninclude:\n  - \"model.yaml\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("model.yaml"), systems).unwrap();
+    }
+
+    async fn next_message(
+        rx: &mut tokio::sync::mpsc::UnboundedReceiver<WsMessage>,
+    ) -> serde_json::Value {
+        match rx.recv().await {
+            Some(WsMessage::Text(text)) => serde_json::from_str(&text).unwrap(),
+            other => panic!("Expected text message, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reload_pushes_model_and_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        write_workspace(temp_dir.path(), "systems:\n  - id: shop\n    name: Shop\n");
+        let config = Config {
+            work_dir: temp_dir.path().to_path_buf(),
+            no_reload: true,
+            ..Default::default()
+        };
+
+        let server = Server::new(config).unwrap();
+        let state = server.state.clone();
+        let hub = state.hub.clone();
+        tokio::spawn(async move {
+            hub.run().await;
+        });
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        state.hub.register(Client::new("test".to_string(), tx));
+        sleep(Duration::from_millis(50)).await;
+
+        state.reload().await;
+        let message = next_message(&mut rx).await;
+        assert_eq!(message["type"], "model");
+        assert_eq!(message["model"]["systems"][0]["id"], "shop");
+        let served = state.model.read().await.clone().unwrap();
+        assert_eq!(served["systems"][0]["id"], "shop");
+
+        std::fs::write(temp_dir.path().join("model.yaml"), "systems: [").unwrap();
+        state.reload().await;
+        let message = next_message(&mut rx).await;
+        assert_eq!(message["type"], "error");
+        assert_eq!(message["errors"].as_array().unwrap().len(), 1);
+        assert_eq!(message["errors"][0]["file"], "model.yaml");
+        assert!(message["errors"][0]["line"].is_u64());
+
+        // The last good model keeps being served
+        let served = state.model.read().await.clone().unwrap();
+        assert_eq!(served["systems"][0]["id"], "shop");
+
+        state.hub.stop();
+    }
+
+    #[tokio::test]
+    async fn test_file_change_swaps_model() {
+        let temp_dir = TempDir::new().unwrap();
+        write_workspace(temp_dir.path(), "systems: []\n");
+        let config = Config {
+            work_dir: temp_dir.path().to_path_buf(),
+            no_reload: false,
+            ..Default::default()
+        };
+
+        let server = Server::new(config).unwrap();
+        let state = server.state.clone();
+        sleep(Duration::from_millis(50)).await;
+
+        std::fs::write(
+            temp_dir.path().join("model.yaml"),
+            "systems:\n  - id: shop\n    name: Shop\n",
+        )
+        .unwrap();
+
+        let mut swapped = false;
+        for _ in 0..30 {
+            sleep(Duration::from_millis(100)).await;
+            if let Some(model) = state.model.read().await.as_ref() {
+                swapped = model["systems"][0]["id"] == "shop";
+            }
+            if swapped {
+                break;
+            }
+        }
+        assert!(swapped);
+
+        server.shutdown().await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_create_router() {
         let temp_dir = TempDir::new().unwrap();